@@ -2,10 +2,14 @@ use super::ApiClient;
 use crate::errors::FrontendResult;
 use http::Method;
 use ibis_database::common::{
+    PaginationParams,
     ResolveObjectParams,
     SuccessResponse,
     article::{ApiConflict, Article, ArticleView, EditVersion, EditView},
+    category::CategoryView,
+    link::{LinkTarget, WantedArticle},
     newtypes::{ArticleId, ConflictId, InstanceId, PersonId},
+    template::Template,
 };
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -22,6 +26,11 @@ pub struct GetArticleParams {
 pub struct ListArticlesParams {
     pub only_local: Option<bool>,
     pub instance_id: Option<InstanceId>,
+    /// Only return articles in this category or its subcategories
+    pub category: Option<String>,
+    /// Page number starting at 1
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -59,16 +68,42 @@ pub struct ForkArticleParams {
     pub new_title: String,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MoveArticleParams {
+    pub article_id: ArticleId,
+    pub new_title: String,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ApproveArticleParams {
     pub article_id: ArticleId,
     pub approve: bool,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DeleteArticleParams {
+    pub article_id: ArticleId,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RevertArticleParams {
+    pub article_id: ArticleId,
+    /// Version to which the article text should be reverted
+    pub version: EditVersion,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct GetEditList {
     pub article_id: Option<ArticleId>,
     pub person_id: Option<PersonId>,
+    /// Only return the edit with this version, requires article_id
+    pub version: Option<EditVersion>,
+    /// Exclude edits made by bot accounts. Defaults to the setting of the logged in user.
+    pub hide_bots: Option<bool>,
+    /// Page number starting at 1, pages contain the most recent edits first. If neither page nor
+    /// limit is given, all edits are returned.
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -87,6 +122,40 @@ pub struct FollowArticleParams {
     pub follow: bool,
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ResolveTemplatesParams {
+    /// Markdown text which contains template calls
+    pub text: String,
+    /// Instance of the article which contains the text, templates without domain are loaded
+    /// from here. Defaults to the local instance.
+    pub domain: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ListTemplateUsageParams {
+    pub article_id: ArticleId,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ListBacklinksParams {
+    pub article_id: ArticleId,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ListCategoriesParams {
+    /// List subcategories of this category, or top level categories if empty
+    pub parent: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct FindMissingLinksParams {
+    /// Markdown text which contains article links
+    pub text: String,
+    /// Instance of the article which contains the text, links without domain point here.
+    /// Defaults to the local instance.
+    pub domain: Option<String>,
+}
+
 impl ApiClient {
     pub async fn create_article(&self, data: &CreateArticleParams) -> FrontendResult<ArticleView> {
         self.post("/api/v1/article", Some(&data)).await
@@ -111,10 +180,23 @@ impl ApiClient {
         self.post("/api/v1/article/fork", Some(params)).await
     }
 
+    /// Change the title of a local article. The previous title keeps working as redirect.
+    pub async fn move_article(&self, params: &MoveArticleParams) -> FrontendResult<ArticleView> {
+        self.post("/api/v1/article/move", Some(params)).await
+    }
+
     pub async fn protect_article(&self, params: &ProtectArticleParams) -> FrontendResult<Article> {
         self.post("/api/v1/article/protect", Some(params)).await
     }
 
+    pub async fn revert_article(
+        &self,
+        params: &RevertArticleParams,
+    ) -> FrontendResult<ArticleView> {
+        self.post("/api/v1/article/revert", Some(params)).await
+    }
+
+    /// Resolve article by ap_id or by handle like `acct:Title@example.com`.
     pub async fn resolve_article(&self, id: Url) -> FrontendResult<ArticleView> {
         let resolve_object = ResolveObjectParams { id };
         self.send(Method::GET, "/api/v1/article/resolve", Some(resolve_object))
@@ -126,7 +208,11 @@ impl ApiClient {
             article_id: Some(article_id),
             ..Default::default()
         };
-        self.send(Method::GET, "/api/v1/edit/list", Some(data))
+        self.list_edits(&data).await
+    }
+
+    pub async fn list_edits(&self, params: &GetEditList) -> FrontendResult<Vec<EditView>> {
+        self.send(Method::GET, "/api/v1/edit/list", Some(params))
             .await
     }
 
@@ -142,6 +228,14 @@ impl ApiClient {
         self.post("/api/v1/article/approve", Some(&params)).await
     }
 
+    /// Delete an article, only allowed for admins. If the article is local, deletion is
+    /// federated to all instances following it.
+    pub async fn delete_article(&self, article_id: ArticleId) -> FrontendResult<()> {
+        let params = DeleteArticleParams { article_id };
+        self.send(Method::DELETE, "/api/v1/article", Some(params))
+            .await
+    }
+
     pub async fn get_conflict(&self, conflict_id: ConflictId) -> FrontendResult<ApiConflict> {
         let params = GetConflictParams { conflict_id };
         self.get("/api/v1/conflict", Some(params)).await
@@ -162,6 +256,54 @@ impl ApiClient {
         self.post("/api/v1/article/follow", Some(params)).await
     }
 
+    /// Load all templates which are needed to render the text, including templates which are
+    /// used by other templates. Remote templates which are not known yet are missing from the
+    /// result, they are fetched in the background and returned by later calls.
+    pub async fn resolve_templates(
+        &self,
+        params: ResolveTemplatesParams,
+    ) -> FrontendResult<Vec<Template>> {
+        self.post("/api/v1/article/templates", Some(params)).await
+    }
+
+    /// Articles which use the given article as template.
+    pub async fn list_template_usage(&self, article_id: ArticleId) -> FrontendResult<Vec<Article>> {
+        let params = ListTemplateUsageParams { article_id };
+        self.get("/api/v1/article/templates/usage", Some(params))
+            .await
+    }
+
+    /// Articles which link to the given article.
+    pub async fn list_backlinks(&self, article_id: ArticleId) -> FrontendResult<Vec<Article>> {
+        let params = ListBacklinksParams { article_id };
+        self.get("/api/v1/article/backlinks", Some(params)).await
+    }
+
+    /// Links in the text which point to articles that are not known on this instance.
+    pub async fn find_missing_links(
+        &self,
+        params: FindMissingLinksParams,
+    ) -> FrontendResult<Vec<LinkTarget>> {
+        self.post("/api/v1/article/links/missing", Some(params))
+            .await
+    }
+
+    /// Direct subcategories of the given category, with the number of articles in each.
+    pub async fn list_categories(
+        &self,
+        params: ListCategoriesParams,
+    ) -> FrontendResult<Vec<CategoryView>> {
+        self.get("/api/v1/category/list", Some(params)).await
+    }
+
+    /// Local articles which are linked from other articles but don't exist yet.
+    pub async fn list_wanted_articles(
+        &self,
+        params: PaginationParams,
+    ) -> FrontendResult<Vec<WantedArticle>> {
+        self.get("/api/v1/article/wanted", Some(params)).await
+    }
+
     #[cfg(debug_assertions)]
     pub async fn edit_article_without_conflict(
         &self,
@@ -1,3 +1,4 @@
+use ibis_database::error::{ApiError, ErrorType};
 use leptos::prelude::*;
 use log::warn;
 use serde::{Deserialize, Serialize};
@@ -10,16 +11,37 @@ use std::{
 
 pub type FrontendResult<T> = Result<T, FrontendError>;
 
+/// Error from an API call. Errors returned by the backend keep their type, other errors such as
+/// network failures have type [ErrorType::Internal].
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct FrontendError(String);
+pub struct FrontendError {
+    error_type: ErrorType,
+    message: String,
+}
 
 impl FrontendError {
     pub fn new(message: impl Into<String>) -> Self {
-        Self(message.into())
+        Self {
+            error_type: ErrorType::Internal,
+            message: message.into(),
+        }
+    }
+
+    pub fn error_type(&self) -> ErrorType {
+        self.error_type
     }
 
     pub fn message(self) -> String {
-        self.0
+        self.message
+    }
+}
+
+impl From<ApiError> for FrontendError {
+    fn from(value: ApiError) -> Self {
+        Self {
+            error_type: value.error,
+            message: value.message,
+        }
     }
 }
 
@@ -38,7 +60,7 @@ impl<T> FrontendResultExt<T> for FrontendResult<T> {
             Ok(o) => on_success(o),
             Err(e) => {
                 warn!("{e}");
-                ErrorPopup::set(e.0);
+                ErrorPopup::set(e.message);
             }
         }
     }
@@ -70,7 +92,7 @@ impl ErrorPopup {
 
 impl Display for FrontendError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.message)
     }
 }
 
@@ -79,30 +101,37 @@ impl Error for FrontendError {}
 #[cfg(feature = "ssr")]
 impl From<reqwest::Error> for FrontendError {
     fn from(value: reqwest::Error) -> Self {
-        Self(value.to_string())
+        Self::new(value.to_string())
     }
 }
 #[cfg(not(feature = "ssr"))]
 impl From<gloo_net::Error> for FrontendError {
     fn from(value: gloo_net::Error) -> Self {
-        Self(value.to_string())
+        Self::new(value.to_string())
+    }
+}
+
+#[cfg(not(feature = "ssr"))]
+impl From<leptos::wasm_bindgen::JsValue> for FrontendError {
+    fn from(value: leptos::wasm_bindgen::JsValue) -> Self {
+        Self::new(format!("{value:?}"))
     }
 }
 
 impl From<url::ParseError> for FrontendError {
     fn from(value: url::ParseError) -> Self {
-        Self(value.to_string())
+        Self::new(value.to_string())
     }
 }
 
 impl From<serde_urlencoded::ser::Error> for FrontendError {
     fn from(value: serde_urlencoded::ser::Error) -> Self {
-        Self(value.to_string())
+        Self::new(value.to_string())
     }
 }
 
 impl From<ParseIntError> for FrontendError {
     fn from(value: ParseIntError) -> Self {
-        Self(value.to_string())
+        Self::new(value.to_string())
     }
 }
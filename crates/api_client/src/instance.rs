@@ -2,18 +2,26 @@ use super::ApiClient;
 use crate::errors::FrontendResult;
 use http::Method;
 use ibis_database::common::{
+    PaginationParams,
     ResolveObjectParams,
     SuccessResponse,
-    article::Article,
-    instance::{Instance, InstanceView, InstanceWithArticles, SiteView},
+    article::ArticleSearchView,
+    instance::{Instance, InstanceBlock, InstanceView, InstanceWithArticles, SiteView},
     newtypes::InstanceId,
 };
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct SearchArticleParams {
     pub query: String,
+    /// Only return articles from this instance
+    pub instance_id: Option<InstanceId>,
+    /// Only return articles in this category or its subcategories
+    pub category: Option<String>,
+    /// Page number starting at 1
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -28,6 +36,15 @@ pub struct FollowInstanceParams {
     pub follow: bool,
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BlockInstanceParams {
+    pub domain: String,
+    pub block: bool,
+    /// When blocking, also remove articles and comments which were federated from the domain
+    #[serde(default)]
+    pub purge: bool,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct UpdateInstanceParams {
     pub name: Option<String>,
@@ -39,8 +56,11 @@ impl ApiClient {
         self.get("/api/v1/instance", Some(&params)).await
     }
 
-    pub async fn list_instances(&self) -> FrontendResult<Vec<InstanceWithArticles>> {
-        self.get("/api/v1/instance/list", None::<i32>).await
+    pub async fn list_instances(
+        &self,
+        params: PaginationParams,
+    ) -> FrontendResult<Vec<InstanceWithArticles>> {
+        self.get("/api/v1/instance/list", Some(params)).await
     }
 
     pub async fn update_local_instance(
@@ -50,10 +70,14 @@ impl ApiClient {
         self.patch("/api/v1/instance", Some(params)).await
     }
 
-    pub async fn search(&self, params: &SearchArticleParams) -> FrontendResult<Vec<Article>> {
+    pub async fn search(
+        &self,
+        params: &SearchArticleParams,
+    ) -> FrontendResult<Vec<ArticleSearchView>> {
         self.send(Method::GET, "/api/v1/search", Some(params)).await
     }
 
+    /// Resolve instance by ap_id or by handle like `acct:example.com@example.com`.
     pub async fn resolve_instance(&self, id: Url) -> FrontendResult<Instance> {
         let resolve_object = ResolveObjectParams { id };
         self.send(
@@ -73,6 +97,17 @@ impl ApiClient {
         self.post("/api/v1/instance/follow", Some(params)).await
     }
 
+    pub async fn block_instance(
+        &self,
+        params: &BlockInstanceParams,
+    ) -> FrontendResult<SuccessResponse> {
+        self.post("/api/v1/instance/block", Some(params)).await
+    }
+
+    pub async fn list_instance_blocks(&self) -> FrontendResult<Vec<InstanceBlock>> {
+        self.get("/api/v1/instance/block/list", None::<()>).await
+    }
+
     pub async fn site(&self) -> FrontendResult<SiteView> {
         self.get("/api/v1/site", None::<()>).await
     }
@@ -1,5 +1,6 @@
 use crate::errors::{FrontendError, FrontendResult};
 use http::{Method, StatusCode};
+use ibis_database::error::ApiError;
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, sync::LazyLock};
@@ -9,6 +10,7 @@ pub mod comment;
 pub mod errors;
 pub mod instance;
 pub mod notifications;
+pub mod upload;
 pub mod user;
 
 pub static CLIENT: LazyLock<ApiClient> = LazyLock::new(|| ApiClient::new(None));
@@ -19,6 +21,8 @@ pub struct ApiClient {
     client: reqwest::Client,
     #[cfg(feature = "ssr")]
     test_hostname: Option<String>,
+    #[cfg(feature = "ssr")]
+    api_token: Option<String>,
 }
 
 impl ApiClient {
@@ -33,6 +37,7 @@ impl ApiClient {
             Self {
                 client,
                 test_hostname,
+                api_token: None,
             }
         }
         #[cfg(not(feature = "ssr"))]
@@ -41,6 +46,14 @@ impl ApiClient {
         }
     }
 
+    /// Authenticate all requests with the given api token instead of login cookie, for use
+    /// in bots and scripts.
+    #[cfg(feature = "ssr")]
+    pub fn with_api_token(mut self, api_token: impl Into<String>) -> Self {
+        self.api_token = Some(api_token.into());
+        self
+    }
+
     async fn get<T, R>(&self, endpoint: &str, query: Option<R>) -> FrontendResult<T>
     where
         T: for<'de> Deserialize<'de>,
@@ -71,22 +84,53 @@ impl ApiClient {
         P: Serialize + Debug,
         T: for<'de> Deserialize<'de>,
     {
-        use ibis_database::common::{AUTH_COOKIE, Auth};
-        use leptos::prelude::use_context;
-        use reqwest::header::HeaderName;
-
-        let mut req = self
+        let req = self
             .client
             .request(method.clone(), self.request_endpoint(path, true)?);
-        req = if method == Method::GET {
+        let req = if method == Method::GET {
             req.query(&params)
         } else {
             req.form(&params)
         };
+        self.send_request(req).await
+    }
+
+    /// Upload a file as multipart form
+    #[cfg(feature = "ssr")]
+    async fn send_file<T>(&self, path: &str, filename: String, data: Vec<u8>) -> FrontendResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        use reqwest::multipart::{Form, Part};
+
+        let form = Form::new().part("file", Part::bytes(data).file_name(filename));
+        let req = self
+            .client
+            .post(self.request_endpoint(path, true)?)
+            .multipart(form);
+        self.send_request(req).await
+    }
+
+    #[cfg(feature = "ssr")]
+    async fn send_request<T>(&self, mut req: reqwest::RequestBuilder) -> FrontendResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        use ibis_database::common::{AUTH_COOKIE, Auth, ClientIp};
+        use leptos::prelude::use_context;
+        use reqwest::header::HeaderName;
+
         let auth = use_context::<Auth>();
         if let Some(Auth(Some(auth))) = auth {
             req = req.header(HeaderName::from_static(AUTH_COOKIE), auth);
         }
+        // Otherwise all requests from server side rendering would have the IP of the server
+        if let Some(ClientIp(ip)) = use_context::<ClientIp>() {
+            req = req.header("X-Forwarded-For", ip.to_string());
+        }
+        if let Some(api_token) = &self.api_token {
+            req = req.bearer_auth(api_token);
+        }
         let res = req.send().await?;
         let status = res.status();
         let url = res.url().to_string();
@@ -149,19 +193,54 @@ impl ApiClient {
         })
     }
 
+    /// Upload a file as multipart form
+    #[cfg(not(feature = "ssr"))]
+    fn send_file<'a, T>(
+        &'a self,
+        path: &'a str,
+        filename: String,
+        data: Vec<u8>,
+    ) -> impl std::future::Future<Output = FrontendResult<T>> + Send + 'a
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        use gloo_net::http::*;
+        use js_sys::{Array, Uint8Array};
+        use leptos::web_sys::{Blob, FormData, RequestCredentials};
+        use send_wrapper::SendWrapper;
+
+        SendWrapper::new(async move {
+            let bytes = Uint8Array::from(data.as_slice());
+            let blob = Blob::new_with_u8_array_sequence(&Array::of1(&bytes))?;
+            let form = FormData::new()?;
+            form.append_with_blob_and_filename("file", &blob, &filename)?;
+            // content type with multipart boundary is set by the browser
+            let req = RequestBuilder::new(&self.request_endpoint(path, false)?)
+                .method(Method::POST)
+                .credentials(RequestCredentials::Include)
+                .body(form)?;
+            let res = req.send().await?;
+            let status = res.status();
+            let text = res.text().await?;
+            Self::response(status, text, &res.url())
+        })
+    }
+
     fn response<T>(status: u16, text: String, url: &str) -> FrontendResult<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let json = serde_json::from_str(&text).map_err(|e| {
-            info!("Failed to deserialize api response: {e} from {text} on {url}");
-            FrontendError::new(&text)
-        })?;
         if status == StatusCode::OK {
-            Ok(json)
+            serde_json::from_str(&text).map_err(|e| {
+                info!("Failed to deserialize api response: {e} from {text} on {url}");
+                FrontendError::new(&text)
+            })
         } else {
             info!("API error: {text} on {url} status {status}");
-            Err(FrontendError::new(text))
+            match serde_json::from_str::<ApiError>(&text) {
+                Ok(e) => Err(e.into()),
+                Err(_) => Err(FrontendError::new(text)),
+            }
         }
     }
 
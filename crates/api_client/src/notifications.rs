@@ -1,6 +1,7 @@
 use super::ApiClient;
 use crate::errors::FrontendResult;
 use ibis_database::common::{
+    PaginationParams,
     SuccessResponse,
     newtypes::{ArticleNotifId, CommentId},
     notifications::ApiNotification,
@@ -18,8 +19,11 @@ pub struct ArticleNotifMarkAsReadParams {
 }
 
 impl ApiClient {
-    pub async fn notifications_list(&self) -> FrontendResult<Vec<ApiNotification>> {
-        self.get("/api/v1/user/notifications/list", None::<()>)
+    pub async fn notifications_list(
+        &self,
+        params: PaginationParams,
+    ) -> FrontendResult<Vec<ApiNotification>> {
+        self.get("/api/v1/user/notifications/list", Some(params))
             .await
     }
 
@@ -0,0 +1,39 @@
+use super::ApiClient;
+use crate::errors::FrontendResult;
+use http::Method;
+use ibis_database::common::{
+    PaginationParams,
+    SuccessResponse,
+    newtypes::UploadId,
+    upload::UploadView,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+pub struct UploadFileParams {
+    /// Original name of the file, the extension is used to detect the file type
+    pub filename: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DeleteUploadParams {
+    pub id: UploadId,
+}
+
+impl ApiClient {
+    pub async fn upload_file(&self, params: UploadFileParams) -> FrontendResult<UploadView> {
+        self.send_file("/api/v1/upload", params.filename, params.data)
+            .await
+    }
+
+    pub async fn list_uploads(&self, params: PaginationParams) -> FrontendResult<Vec<UploadView>> {
+        self.get("/api/v1/upload/list", Some(params)).await
+    }
+
+    pub async fn delete_upload(&self, id: UploadId) -> FrontendResult<SuccessResponse> {
+        let params = DeleteUploadParams { id };
+        self.send(Method::DELETE, "/api/v1/upload", Some(params))
+            .await
+    }
+}
@@ -1,24 +1,67 @@
 use super::ApiClient;
 use crate::{article::GetEditList, errors::FrontendResult};
+use http::Method;
 use ibis_database::common::{
     SuccessResponse,
     article::EditView,
     instance::InstanceFollow,
-    newtypes::PersonId,
-    user::{LocalUserView, Person},
+    newtypes::{ApiTokenId, PersonId, RegistrationApplicationId, SessionId},
+    user::{
+        ApiToken,
+        ApiTokenScope,
+        CaptchaResponse,
+        CreateApiTokenResponse,
+        EnableTotpResponse,
+        LocalUserView,
+        OAuthAuthorizeResponse,
+        Person,
+        SessionView,
+        TotpSetupResponse,
+        UserExport,
+    },
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct RegisterUserParams {
     pub username: String,
     pub password: String,
+    /// Answer to `Options::registration_question`
+    pub answer: Option<String>,
+    /// Id and solution of the captcha, required if `Options::registration_captcha` is enabled
+    pub captcha_id: Option<String>,
+    pub captcha_answer: Option<String>,
+    /// Optional email address, a verification link is sent to it
+    pub email: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
+pub struct ApproveRegistrationParams {
+    pub id: RegistrationApplicationId,
+    pub approve: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct LoginUserParams {
     pub username: String,
     pub password: String,
+    /// Code from authenticator app or recovery code, if two factor authentication is enabled
+    pub totp_code: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct OAuthAuthorizeParams {
+    /// Id of the provider from `SiteView::oauth_providers`
+    pub provider: String,
+}
+
+/// Query parameters which the provider passes to `/oauth/callback`
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct OAuthLoginParams {
+    pub code: String,
+    pub state: String,
+    /// Code from authenticator app or recovery code, if two factor authentication is enabled
+    pub totp_code: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -28,9 +71,85 @@ pub struct GetUserParams {
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BanUserParams {
+    pub person_id: PersonId,
+    pub ban: bool,
+    /// When banning, also delete comments and revert edits of the user
+    #[serde(default)]
+    pub remove_content: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct UpdateUserParams {
     pub display_name: Option<String>,
     pub bio: Option<String>,
+    pub bot: Option<bool>,
+    pub hide_bot_edits: Option<bool>,
+    /// Change the email address, which needs to be verified again. Empty string removes it.
+    pub email: Option<String>,
+    /// Current password, required to change the email address
+    pub password: Option<String>,
+    /// Receive a daily email with new notifications
+    pub email_notifications: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ChangePasswordParams {
+    pub old_password: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct VerifyEmailParams {
+    pub token: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RequestPasswordResetParams {
+    pub email: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ResetPasswordParams {
+    /// Token from the password reset email
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct EnableTotpParams {
+    /// Current code from the authenticator app, to confirm that it was set up correctly
+    pub code: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DisableTotpParams {
+    pub password: String,
+    /// Code from authenticator app or recovery code
+    pub code: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DeleteAccountParams {
+    /// Current password of the user, to confirm the deletion
+    pub password: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DeleteSessionParams {
+    pub id: SessionId,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CreateApiTokenParams {
+    /// Description of the token, eg name of the bot which uses it
+    pub name: String,
+    pub scope: ApiTokenScope,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DeleteApiTokenParams {
+    pub id: ApiTokenId,
 }
 
 impl ApiClient {
@@ -38,14 +157,67 @@ impl ApiClient {
         self.post("/api/v1/account/register", Some(&params)).await
     }
 
+    pub async fn get_captcha(&self) -> FrontendResult<CaptchaResponse> {
+        self.get("/api/v1/account/captcha", None::<()>).await
+    }
+
     pub async fn login(&self, params: LoginUserParams) -> FrontendResult<LocalUserView> {
         self.post("/api/v1/account/login", Some(&params)).await
     }
 
+    /// Returns the url of the provider where the user needs to login. Afterwards the provider
+    /// redirects to `/oauth/callback`, which completes the login with [ApiClient::oauth_login].
+    pub async fn oauth_authorize(
+        &self,
+        provider: String,
+    ) -> FrontendResult<OAuthAuthorizeResponse> {
+        let params = OAuthAuthorizeParams { provider };
+        self.get("/api/v1/account/oauth/authorize", Some(params))
+            .await
+    }
+
+    /// Login with the code from the provider. If no local account is linked yet, it is linked
+    /// to the logged in user or a new account is created.
+    pub async fn oauth_login(&self, params: &OAuthLoginParams) -> FrontendResult<LocalUserView> {
+        self.post("/api/v1/account/oauth/login", Some(params)).await
+    }
+
     pub async fn logout(&self) -> FrontendResult<SuccessResponse> {
         self.post("/api/v1/account/logout", None::<()>).await
     }
 
+    /// Log out on all devices, by revoking every session of the current user.
+    pub async fn logout_all(&self) -> FrontendResult<SuccessResponse> {
+        self.post("/api/v1/account/logout_all", None::<()>).await
+    }
+
+    pub async fn list_sessions(&self) -> FrontendResult<Vec<SessionView>> {
+        self.get("/api/v1/account/sessions", None::<()>).await
+    }
+
+    pub async fn delete_session(&self, id: SessionId) -> FrontendResult<SuccessResponse> {
+        let params = DeleteSessionParams { id };
+        self.send(Method::DELETE, "/api/v1/account/session", Some(params))
+            .await
+    }
+
+    pub async fn create_api_token(
+        &self,
+        params: &CreateApiTokenParams,
+    ) -> FrontendResult<CreateApiTokenResponse> {
+        self.post("/api/v1/account/api_token", Some(params)).await
+    }
+
+    pub async fn list_api_tokens(&self) -> FrontendResult<Vec<ApiToken>> {
+        self.get("/api/v1/account/api_token/list", None::<()>).await
+    }
+
+    pub async fn delete_api_token(&self, id: ApiTokenId) -> FrontendResult<SuccessResponse> {
+        let params = DeleteApiTokenParams { id };
+        self.send(Method::DELETE, "/api/v1/account/api_token", Some(params))
+            .await
+    }
+
     pub async fn get_user(&self, data: GetUserParams) -> FrontendResult<Person> {
         self.get("/api/v1/user", Some(data)).await
     }
@@ -61,11 +233,93 @@ impl ApiClient {
         self.post("/api/v1/account/update", Some(data)).await
     }
 
+    pub async fn change_password(
+        &self,
+        params: &ChangePasswordParams,
+    ) -> FrontendResult<SuccessResponse> {
+        self.post("/api/v1/account/change_password", Some(params))
+            .await
+    }
+
+    /// Generate a new secret for two factor authentication. It only needs to be entered when
+    /// logging in after it is confirmed with [ApiClient::enable_totp].
+    pub async fn setup_totp(&self) -> FrontendResult<TotpSetupResponse> {
+        self.post("/api/v1/account/totp/setup", None::<()>).await
+    }
+
+    /// Returns recovery codes which can be used if the authenticator app is lost.
+    pub async fn enable_totp(&self, code: String) -> FrontendResult<EnableTotpResponse> {
+        let params = EnableTotpParams { code };
+        self.post("/api/v1/account/totp/enable", Some(&params))
+            .await
+    }
+
+    pub async fn disable_totp(
+        &self,
+        params: &DisableTotpParams,
+    ) -> FrontendResult<SuccessResponse> {
+        self.post("/api/v1/account/totp/disable", Some(params))
+            .await
+    }
+
+    pub async fn verify_email(&self, token: String) -> FrontendResult<SuccessResponse> {
+        let params = VerifyEmailParams { token };
+        self.post("/api/v1/account/verify_email", Some(&params))
+            .await
+    }
+
+    /// Send an email with a link to reset the password. Succeeds even if no account with this
+    /// email exists, so that it can't be used to find out which addresses are registered.
+    pub async fn request_password_reset(&self, email: String) -> FrontendResult<SuccessResponse> {
+        let params = RequestPasswordResetParams { email };
+        self.post("/api/v1/account/request_password_reset", Some(&params))
+            .await
+    }
+
+    /// Set a new password using the token from the reset email. This logs out all sessions.
+    pub async fn reset_password(
+        &self,
+        params: &ResetPasswordParams,
+    ) -> FrontendResult<SuccessResponse> {
+        self.post("/api/v1/account/reset_password", Some(params))
+            .await
+    }
+
+    /// Permanently delete the account of the logged in user. Edits, comments and uploads are
+    /// kept, but attributed to a placeholder user.
+    pub async fn delete_account(
+        &self,
+        params: &DeleteAccountParams,
+    ) -> FrontendResult<SuccessResponse> {
+        self.post("/api/v1/account/delete", Some(params)).await
+    }
+
+    pub async fn export_user_data(&self) -> FrontendResult<UserExport> {
+        self.get("/api/v1/account/export", None::<()>).await
+    }
+
+    pub async fn ban_user(&self, params: &BanUserParams) -> FrontendResult<Person> {
+        self.post("/api/v1/user/ban", Some(params)).await
+    }
+
+    pub async fn approve_registration(
+        &self,
+        id: RegistrationApplicationId,
+        approve: bool,
+    ) -> FrontendResult<SuccessResponse> {
+        let params = ApproveRegistrationParams { id, approve };
+        self.post("/api/v1/user/approve", Some(&params)).await
+    }
+
+    pub async fn list_banned_users(&self) -> FrontendResult<Vec<Person>> {
+        self.get("/api/v1/user/banned", None::<()>).await
+    }
+
     pub async fn get_person_edits(&self, person_id: PersonId) -> FrontendResult<Vec<EditView>> {
         let data = GetEditList {
             person_id: Some(person_id),
             ..Default::default()
         };
-        self.get("/api/v1/edit/list", Some(data)).await
+        self.list_edits(&data).await
     }
 }
@@ -10,41 +10,70 @@ use ibis_api_client::{
     article::{
         ApproveArticleParams,
         CreateArticleParams,
+        DeleteArticleParams,
         DeleteConflictParams,
         EditArticleParams,
+        FindMissingLinksParams,
         FollowArticleParams,
         ForkArticleParams,
         GetArticleParams,
         GetConflictParams,
         ListArticlesParams,
+        ListBacklinksParams,
+        ListCategoriesParams,
+        ListTemplateUsageParams,
+        MoveArticleParams,
         ProtectArticleParams,
+        ResolveTemplatesParams,
+        RevertArticleParams,
     },
     instance::SearchArticleParams,
 };
 use ibis_database::{
     common::{
+        PaginationParams,
         ResolveObjectParams,
         SuccessResponse,
         article::{
             ApiConflict,
             Article,
+            ArticleSearchView,
             ArticleView,
             Conflict,
             Edit,
             EditVersion,
             can_edit_article,
         },
+        category::{CategoryView, normalize_category},
         instance::Instance,
+        link::{
+            LinkTarget,
+            MAX_CHECKED_LINKS,
+            WantedArticle,
+            parse_article_links,
+            rewrite_local_links,
+        },
+        template::{MAX_TEMPLATE_DEPTH, MAX_TEMPLATES, Template, parse_template_calls},
+        user::ApiTokenScope,
         utils::{extract_domain, http_protocol_str},
     },
-    error::BackendResult,
+    error::{BackendError, BackendResult},
     impls::{IbisContext, article::DbArticleForm, conflict::DbConflictForm, edit::DbEditForm},
+    utils::limit_and_offset,
 };
 use ibis_federate::{
-    activities::{create_article::CreateArticle, submit_article_update},
+    activities::{
+        create_article::CreateArticle,
+        delete::Delete,
+        submit_article_update,
+        update_local_article::UpdateLocalArticle,
+    },
     objects::article::ArticleWrapper,
-    validate::{validate_article_title, validate_not_empty},
+    validate::{validate_article_title, validate_not_banned, validate_not_empty},
+    webfinger::{resolve_object, webfinger_resolve},
 };
+use moka::sync::Cache;
+use std::{collections::HashSet, sync::LazyLock, time::Duration};
 use url::Url;
 
 /// Create a new article with empty text, and federate it to followers.
@@ -54,9 +83,25 @@ pub(crate) async fn create_article(
     context: Data<IbisContext>,
     Form(mut params): Form<CreateArticleParams>,
 ) -> BackendResult<Json<ArticleView>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
     params.title = validate_article_title(&params.title)?;
     validate_not_empty(&params.text)?;
 
+    // Moved articles keep the ap_id of their original title, and previous titles redirect to
+    // them, so these titles can't be used for new articles.
+    if let Ok(existing) = Article::read_local_from_title(&params.title, &context) {
+        if existing.title == params.title {
+            return Err(BackendError::conflict(
+                "Article with this title already exists",
+            ));
+        }
+        return Err(BackendError::validation(format!(
+            "Title was previously used by the article {}",
+            existing.title
+        )));
+    }
+
     let local_instance = Instance::read_local(&context)?;
     let ap_id = Url::parse(&format!(
         "{}://{}/article/{}",
@@ -65,6 +110,11 @@ pub(crate) async fn create_article(
         params.title
     ))?
     .into();
+    if Article::read_from_ap_id(&ap_id, &context).is_ok() {
+        return Err(BackendError::validation(
+            "Title was previously used by another article",
+        ));
+    }
     let form = DbArticleForm {
         title: params.title,
         text: String::new(),
@@ -84,14 +134,7 @@ pub(crate) async fn create_article(
         resolve_conflict_id: None,
     };
 
-    let _ = edit_article(
-        UserExt {
-            local_user_view: user.clone(),
-        },
-        context.reset_request_count(),
-        Form(edit_data),
-    )
-    .await?;
+    let _ = edit_article(user.clone(), context.reset_request_count(), Form(edit_data)).await?;
 
     Article::follow(article.id, &user, &context)?;
 
@@ -117,6 +160,8 @@ pub(crate) async fn edit_article(
     context: Data<IbisContext>,
     Form(mut params): Form<EditArticleParams>,
 ) -> BackendResult<Json<Option<ApiConflict>>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
     validate_not_empty(&params.new_text)?;
     // resolve conflict if any
     if let Some(resolve_conflict_id) = params.resolve_conflict_id {
@@ -124,20 +169,21 @@ pub(crate) async fn edit_article(
     }
     let original_article = Article::read_view(params.article_id, Some(&user), &context)?;
     if params.new_text == original_article.article.text {
-        return Err(anyhow!("Edit contains no changes").into());
+        return Err(BackendError::validation("Edit contains no changes"));
     }
     if params.summary.is_empty() {
-        return Err(anyhow!("No summary given").into());
+        return Err(BackendError::validation("No summary given"));
     }
     can_edit_article(&original_article.article, user.local_user.admin)?;
     // ensure trailing newline for clean diffs
     if !params.new_text.ends_with('\n') {
         params.new_text.push('\n');
     }
-    let local_link = format!("](https://{}", context.config.federation.domain);
-    if params.new_text.contains(&local_link) {
-        return Err(anyhow!("Links to local instance don't work over federation").into());
-    }
+    params.new_text = rewrite_local_links(
+        &params.new_text,
+        &context.config.federation.domain,
+        original_article.article.local,
+    );
 
     // Markdown formatting
     let new_text = fmtm::format(&params.new_text, Some(80))?;
@@ -157,7 +203,7 @@ pub(crate) async fn edit_article(
     } else {
         // There have been other changes since this edit was initiated. Get the common ancestor
         // version and generate a diff to find out what exactly has changed.
-        let edits = Edit::list_for_article(original_article.article.id, &context)?;
+        let edits = Edit::list_for_article(original_article.article.id, None, None, &context)?;
         let ancestor = generate_article_version(&edits, &params.previous_version_id)?;
         let patch = create_patch(&ancestor, &new_text);
 
@@ -177,6 +223,54 @@ pub(crate) async fn edit_article(
     }
 }
 
+/// Revert an article to the text it had at an earlier version. This is submitted as a new edit,
+/// so it federates like any other change and can itself be reverted.
+#[debug_handler]
+pub(crate) async fn revert_article(
+    user: UserExt,
+    context: Data<IbisContext>,
+    Form(params): Form<RevertArticleParams>,
+) -> BackendResult<Json<ArticleView>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
+    let original_article = Article::read_view(params.article_id, Some(&user), &context)?;
+    can_edit_article(&original_article.article, user.local_user.admin)?;
+    let revert_to = Edit::read(&params.version, &context)?;
+    if revert_to.article_id != original_article.article.id {
+        return Err(BackendError::validation(
+            "Edit belongs to a different article",
+        ));
+    }
+    if revert_to.pending {
+        return Err(BackendError::validation("Cannot revert to pending edit"));
+    }
+
+    let edits = Edit::list_for_article(original_article.article.id, None, None, &context)?;
+    let new_text = generate_article_version(&edits, &params.version)?;
+    if new_text == original_article.article.text {
+        return Err(BackendError::validation("Article already has this version"));
+    }
+    let summary = format!(
+        "Revert to \"{}\" from {}",
+        revert_to.summary,
+        revert_to.published.format("%Y-%m-%d %H:%M")
+    );
+    submit_article_update(
+        new_text,
+        summary,
+        original_article.latest_version,
+        &original_article.article,
+        user.person.id,
+        &context,
+    )
+    .await?;
+    Ok(Json(Article::read_view(
+        original_article.article.id,
+        Some(&user),
+        &context,
+    )?))
+}
+
 /// Retrieve an article by ID. It must already be stored in the local database.
 #[debug_handler]
 pub(crate) async fn get_article(
@@ -193,12 +287,16 @@ pub(crate) async fn get_article(
         )?)),
         (None, Some(id)) => {
             if query.domain.is_some() {
-                return Err(anyhow!("Cant combine id and instance_domain").into());
+                return Err(BackendError::validation(
+                    "Cant combine id and instance_domain",
+                ));
             }
             let article = Article::read_view(id, user.as_ref(), &context)?;
             Ok(Json(article))
         }
-        _ => Err(anyhow!("Must pass exactly one of title, id").into()),
+        _ => Err(BackendError::validation(
+            "Must pass exactly one of title, id",
+        )),
     }
 }
 
@@ -207,9 +305,14 @@ pub(crate) async fn list_articles(
     Query(query): Query<ListArticlesParams>,
     context: Data<IbisContext>,
 ) -> BackendResult<Json<Vec<Article>>> {
+    let (limit, offset) = limit_and_offset(query.page, query.limit)?;
+    let category = validate_category(query.category)?;
     Ok(Json(Article::read_all(
         query.only_local,
         query.instance_id,
+        category.as_deref(),
+        Some(limit),
+        Some(offset),
         &context,
     )?))
 }
@@ -222,6 +325,8 @@ pub(crate) async fn fork_article(
     context: Data<IbisContext>,
     Form(mut params): Form<ForkArticleParams>,
 ) -> BackendResult<Json<ArticleView>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
     // TODO: lots of code duplicated from create_article(), can move it into helper
     let original_article = Article::read_view(params.article_id, Some(&user), &context)?;
     params.new_title = validate_article_title(&params.new_title)?;
@@ -248,7 +353,7 @@ pub(crate) async fn fork_article(
     // copy edits to new article
     // this could also be done in sql
 
-    let edits = Edit::list_for_article(original_article.article.id, &context)?;
+    let edits = Edit::list_for_article(original_article.article.id, None, None, &context)?;
     for e in edits {
         let ap_id = DbEditForm::generate_ap_id(&article, &e.hash)?;
         let form = DbEditForm {
@@ -274,27 +379,232 @@ pub(crate) async fn fork_article(
 
 /// Fetch a remote article, including edits collection. Allows viewing and editing. Note that new
 /// article changes can only be received if we follow the instance, or if it is refetched manually.
+///
+/// The article can be given either by ap_id or by handle like `acct:Title@example.com`.
 #[debug_handler]
 pub(super) async fn resolve_article(
     user: UserExt,
     Query(query): Query<ResolveObjectParams>,
     context: Data<IbisContext>,
 ) -> BackendResult<Json<ArticleView>> {
-    let article: ArticleWrapper = ObjectId::from(query.id).dereference(&context).await?;
+    let article: ArticleWrapper = resolve_object(query.id, &context).await?;
     Ok(Json(Article::read_view(article.id, Some(&user), &context)?))
 }
 
+/// Remote templates which were recently fetched in the background, so that rendering the same
+/// article again doesn't fetch them again.
+static TEMPLATE_FETCHES: LazyLock<Cache<String, ()>> = LazyLock::new(|| {
+    Cache::builder()
+        .time_to_live(Duration::from_secs(10 * 60))
+        .max_capacity(10_000)
+        .build()
+});
+
+/// Load the templates which are used in the text, including templates used by other templates
+/// up to [MAX_TEMPLATE_DEPTH]. Templates which don't exist are left out, so they can be shown as
+/// missing. Only templates which are already known are returned, remote templates which are not
+/// known yet are fetched from their instance in the background.
+#[debug_handler]
+pub(crate) async fn resolve_templates(
+    context: Data<IbisContext>,
+    Form(params): Form<ResolveTemplatesParams>,
+) -> BackendResult<Json<Vec<Template>>> {
+    let mut templates = vec![];
+    let mut missing = vec![];
+    let mut checked = HashSet::new();
+    let mut pending: Vec<_> = parse_template_calls(&params.text)
+        .into_iter()
+        .map(|call| (call.title.clone(), call.domain_or(&params.domain), 1))
+        .collect();
+    while let Some((title, domain, depth)) = pending.pop() {
+        if checked.len() >= MAX_TEMPLATES || !checked.insert((title.clone(), domain.clone())) {
+            continue;
+        }
+        let remote_domain = domain
+            .clone()
+            .filter(|d| d != &context.config.federation.domain);
+        let article = match Article::read_view((&title, remote_domain.clone()), None, &context) {
+            Ok(view) if view.article.approved => view.article,
+            Ok(_) => continue,
+            Err(_) => {
+                if let Some(remote_domain) = remote_domain {
+                    missing.push(format!("{title}@{remote_domain}"));
+                }
+                continue;
+            }
+        };
+        if depth < MAX_TEMPLATE_DEPTH {
+            pending.extend(
+                parse_template_calls(&article.text)
+                    .into_iter()
+                    .map(|call| (call.title.clone(), call.domain_or(&domain), depth + 1)),
+            );
+        }
+        templates.push(Template {
+            title,
+            domain,
+            text: article.text,
+        });
+    }
+    fetch_templates(missing, &context);
+    Ok(Json(templates))
+}
+
+fn fetch_templates(handles: Vec<String>, context: &Data<IbisContext>) {
+    let handles: Vec<_> = handles
+        .into_iter()
+        .filter(|h| !TEMPLATE_FETCHES.contains_key(h))
+        .collect();
+    if handles.is_empty() {
+        return;
+    }
+    let context = context.reset_request_count();
+    tokio::spawn(async move {
+        for handle in handles {
+            TEMPLATE_FETCHES.insert(handle.clone(), ());
+            webfinger_resolve::<ArticleWrapper>(&handle, &context)
+                .await
+                .inspect_err(|e| log::warn!("Failed to fetch template {handle}: {e}"))
+                .ok();
+        }
+    });
+}
+
+/// Articles which use the given article as template.
+#[debug_handler]
+pub(crate) async fn list_template_usage(
+    Query(params): Query<ListTemplateUsageParams>,
+    context: Data<IbisContext>,
+) -> BackendResult<Json<Vec<Article>>> {
+    let template = Article::read(params.article_id, &context)?;
+    Ok(Json(Article::list_template_usage(&template, &context)?))
+}
+
+/// Articles which link to the given article.
+#[debug_handler]
+pub(crate) async fn list_backlinks(
+    Query(params): Query<ListBacklinksParams>,
+    context: Data<IbisContext>,
+) -> BackendResult<Json<Vec<Article>>> {
+    let article = Article::read(params.article_id, &context)?;
+    Ok(Json(Article::list_backlinks(&article, &context)?))
+}
+
+/// Links in the text which point to articles that are not known on this instance, so that
+/// they can be shown as missing. Remote articles are not fetched.
+#[debug_handler]
+pub(crate) async fn find_missing_links(
+    context: Data<IbisContext>,
+    Form(params): Form<FindMissingLinksParams>,
+) -> BackendResult<Json<Vec<LinkTarget>>> {
+    let targets: HashSet<_> = parse_article_links(&params.text)
+        .iter()
+        .map(|link| link.target(&params.domain))
+        .collect();
+    let targets: Vec<_> = targets.into_iter().take(MAX_CHECKED_LINKS).collect();
+    // Local articles are checked without domain
+    let without_local_domain = |target: &LinkTarget| LinkTarget {
+        title: target.title.clone(),
+        domain: target
+            .domain
+            .clone()
+            .filter(|d| *d != context.config.federation.domain),
+    };
+    let existing = Article::existing_links(
+        &targets.iter().map(without_local_domain).collect(),
+        &context,
+    )?;
+    let missing = targets
+        .into_iter()
+        .filter(|target| !existing.contains(&without_local_domain(target)))
+        .collect();
+    Ok(Json(missing))
+}
+
+/// Local articles which are linked from other articles but don't exist yet.
+#[debug_handler]
+pub(crate) async fn list_wanted_articles(
+    Query(query): Query<PaginationParams>,
+    context: Data<IbisContext>,
+) -> BackendResult<Json<Vec<WantedArticle>>> {
+    let (limit, offset) = limit_and_offset(query.page, query.limit)?;
+    Ok(Json(Article::list_wanted(limit, offset, &context)?))
+}
+
 /// Search articles for matching title or body text.
 #[debug_handler]
 pub(super) async fn search_article(
     Query(query): Query<SearchArticleParams>,
     context: Data<IbisContext>,
-) -> BackendResult<Json<Vec<Article>>> {
+) -> BackendResult<Json<Vec<ArticleSearchView>>> {
     if query.query.is_empty() {
-        return Err(anyhow!("Query is empty").into());
+        return Err(BackendError::validation("Query is empty"));
     }
-    let article = Article::search(&query.query, &context)?;
-    Ok(Json(article))
+    let (limit, offset) = limit_and_offset(query.page, query.limit)?;
+    let category = validate_category(query.category)?;
+    let articles = Article::search(
+        &query.query,
+        query.instance_id,
+        category.as_deref(),
+        limit,
+        offset,
+        &context,
+    )?;
+    Ok(Json(articles))
+}
+
+/// Direct subcategories of the given category, or top level categories. Categories are added
+/// to articles with `[[Category:Name]]` in the text.
+#[debug_handler]
+pub(crate) async fn list_categories(
+    Query(query): Query<ListCategoriesParams>,
+    context: Data<IbisContext>,
+) -> BackendResult<Json<Vec<CategoryView>>> {
+    let parent = validate_category(query.parent)?;
+    Ok(Json(CategoryView::list(parent.as_deref(), &context)?))
+}
+
+/// Category names in query params are normalized in the same way as in article text.
+fn validate_category(category: Option<String>) -> BackendResult<Option<String>> {
+    category
+        .filter(|c| !c.is_empty())
+        .map(|c| normalize_category(&c).ok_or_else(|| BackendError::validation("Invalid category")))
+        .transpose()
+}
+
+/// Change the title of a local article, and federate the change to followers. The previous
+/// title is kept as redirect.
+#[debug_handler]
+pub(crate) async fn move_article(
+    user: UserExt,
+    context: Data<IbisContext>,
+    Form(mut params): Form<MoveArticleParams>,
+) -> BackendResult<Json<ArticleView>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
+    let article = Article::read_view(params.article_id, Some(&user), &context)?.article;
+    if !article.local {
+        return Err(BackendError::validation("Only local articles can be moved"));
+    }
+    if article.protected {
+        check_is_admin(&user)?;
+    }
+    params.new_title = validate_article_title(&params.new_title)?;
+    if let Ok(existing) = Article::read_view((&params.new_title, None), None, &context) {
+        if existing.article.id != article.id {
+            return Err(BackendError::conflict(
+                "Article with this title already exists",
+            ));
+        }
+    }
+
+    let article = Article::update_title(article.id, &params.new_title, &context)?;
+    UpdateLocalArticle::send(article.into(), vec![], &context).await?;
+    Ok(Json(Article::read_view(
+        params.article_id,
+        Some(&user),
+        &context,
+    )?))
 }
 
 #[debug_handler]
@@ -323,6 +633,35 @@ pub async fn approve_article(
     Ok(Json(()))
 }
 
+/// Delete an article. Local articles are kept as tombstone so that other instances notice the
+/// deletion, remote articles are only removed from the local database.
+#[debug_handler]
+pub(crate) async fn delete_article(
+    user: UserExt,
+    context: Data<IbisContext>,
+    Form(params): Form<DeleteArticleParams>,
+) -> BackendResult<Json<()>> {
+    check_is_admin(&user)?;
+    let article = Article::read(params.article_id, &context)?;
+    remove_article(&article, &context).await?;
+    Ok(Json(()))
+}
+
+/// Local articles are marked as removed and the deletion is sent to other instances. Remote
+/// articles are only deleted locally.
+pub(super) async fn remove_article(
+    article: &Article,
+    context: &Data<IbisContext>,
+) -> BackendResult<()> {
+    if article.local {
+        let article = Article::update_removed(article.id, true, context)?;
+        Delete::send_article(&article, context).await?;
+    } else {
+        Article::delete(article.id, context)?;
+    }
+    Ok(())
+}
+
 #[debug_handler]
 pub async fn get_conflict(
     user: UserExt,
@@ -342,6 +681,7 @@ pub async fn delete_conflict(
     context: Data<IbisContext>,
     Form(params): Form<DeleteConflictParams>,
 ) -> BackendResult<Json<()>> {
+    user.check_scope(ApiTokenScope::Edit)?;
     Conflict::delete(params.conflict_id, user.person.id, &context)?;
     Ok(Json(()))
 }
@@ -352,6 +692,7 @@ pub(crate) async fn follow_article(
     context: Data<IbisContext>,
     Form(params): Form<FollowArticleParams>,
 ) -> BackendResult<Json<SuccessResponse>> {
+    user.check_scope(ApiTokenScope::Edit)?;
     if params.follow {
         Article::follow(params.id, &user, &context)?;
     } else {
@@ -375,7 +716,7 @@ pub async fn db_conflict_to_api_conflict(
     };
 
     // create common ancestor version
-    let edits = Edit::list_for_article(original_article.id, context)?;
+    let edits = Edit::list_for_article(original_article.id, None, None, context)?;
     let ancestor = generate_article_version(&edits, &conflict.previous_version_id)?;
 
     let patch = Patch::from_str(&conflict.diff)?;
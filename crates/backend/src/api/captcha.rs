@@ -0,0 +1,231 @@
+use crate::utils::generate_secret;
+use axum::Json;
+use axum_macros::debug_handler;
+use ibis_database::{common::user::CaptchaResponse, error::BackendResult};
+use moka::sync::Cache;
+use rand::{Rng, seq::SliceRandom, thread_rng};
+use std::{fmt::Write, sync::LazyLock, time::Duration};
+
+/// Number of characters in each captcha
+const CAPTCHA_LENGTH: usize = 5;
+
+/// Solutions for captchas which were not used yet, keyed by captcha id
+static CAPTCHA_ANSWERS: LazyLock<Cache<String, String>> = LazyLock::new(|| {
+    Cache::builder()
+        .time_to_live(Duration::from_secs(10 * 60))
+        .max_capacity(10_000)
+        .build()
+});
+
+/// Points of the strokes which make up a character
+type Glyph = (char, &'static [&'static [(f32, f32)]]);
+
+/// Characters as line segments on a 4x6 grid. They are drawn as paths instead of text, so that
+/// the solution can't simply be read from the svg. Similar looking characters are left out.
+const GLYPHS: &[Glyph] = &[
+    (
+        '2',
+        &[&[
+            (0., 1.),
+            (1., 0.),
+            (3., 0.),
+            (4., 1.),
+            (4., 2.),
+            (0., 6.),
+            (4., 6.),
+        ]],
+    ),
+    (
+        '3',
+        &[&[
+            (0., 0.),
+            (4., 0.),
+            (2., 2.5),
+            (4., 3.5),
+            (4., 5.),
+            (3., 6.),
+            (1., 6.),
+            (0., 5.),
+        ]],
+    ),
+    ('4', &[&[(3., 6.), (3., 0.), (0., 4.), (4., 4.)]]),
+    (
+        '5',
+        &[&[
+            (4., 0.),
+            (0., 0.),
+            (0., 2.5),
+            (3., 2.5),
+            (4., 3.5),
+            (4., 5.),
+            (3., 6.),
+            (0., 6.),
+        ]],
+    ),
+    ('7', &[&[(0., 0.), (4., 0.), (1.5, 6.)]]),
+    (
+        'A',
+        &[&[(0., 6.), (2., 0.), (4., 6.)], &[(1., 3.5), (3., 3.5)]],
+    ),
+    (
+        'C',
+        &[&[
+            (4., 1.),
+            (3., 0.),
+            (1., 0.),
+            (0., 1.),
+            (0., 5.),
+            (1., 6.),
+            (3., 6.),
+            (4., 5.),
+        ]],
+    ),
+    (
+        'E',
+        &[
+            &[(4., 0.), (0., 0.), (0., 6.), (4., 6.)],
+            &[(0., 3.), (3., 3.)],
+        ],
+    ),
+    (
+        'F',
+        &[&[(4., 0.), (0., 0.), (0., 6.)], &[(0., 3.), (3., 3.)]],
+    ),
+    (
+        'H',
+        &[
+            &[(0., 0.), (0., 6.)],
+            &[(4., 0.), (4., 6.)],
+            &[(0., 3.), (4., 3.)],
+        ],
+    ),
+    (
+        'K',
+        &[
+            &[(0., 0.), (0., 6.)],
+            &[(4., 0.), (0., 4.)],
+            &[(1.3, 2.7), (4., 6.)],
+        ],
+    ),
+    ('L', &[&[(0., 0.), (0., 6.), (4., 6.)]]),
+    ('M', &[&[(0., 6.), (0., 0.), (2., 3.), (4., 0.), (4., 6.)]]),
+    ('N', &[&[(0., 6.), (0., 0.), (4., 6.), (4., 0.)]]),
+    (
+        'P',
+        &[&[
+            (0., 6.),
+            (0., 0.),
+            (3., 0.),
+            (4., 1.),
+            (4., 2.),
+            (3., 3.),
+            (0., 3.),
+        ]],
+    ),
+    (
+        'R',
+        &[
+            &[
+                (0., 6.),
+                (0., 0.),
+                (3., 0.),
+                (4., 1.),
+                (4., 2.),
+                (3., 3.),
+                (0., 3.),
+            ],
+            &[(2., 3.), (4., 6.)],
+        ],
+    ),
+    ('T', &[&[(0., 0.), (4., 0.)], &[(2., 0.), (2., 6.)]]),
+    ('W', &[&[(0., 0.), (1., 6.), (2., 2.), (3., 6.), (4., 0.)]]),
+    ('X', &[&[(0., 0.), (4., 6.)], &[(4., 0.), (0., 6.)]]),
+    (
+        'Y',
+        &[&[(0., 0.), (2., 3.), (4., 0.)], &[(2., 3.), (2., 6.)]],
+    ),
+];
+
+/// Generate a new captcha. The solution is stored in memory and can only be used once.
+#[debug_handler]
+pub(crate) async fn get_captcha() -> BackendResult<Json<CaptchaResponse>> {
+    let (answer, svg) = generate_captcha();
+    let id = generate_secret();
+    CAPTCHA_ANSWERS.insert(id.clone(), answer);
+    Ok(Json(CaptchaResponse { id, svg }))
+}
+
+/// Check the answer for a captcha, ignoring case. Each captcha can only be checked once.
+pub(super) fn check_captcha(id: &str, answer: &str) -> bool {
+    CAPTCHA_ANSWERS
+        .remove(id)
+        .is_some_and(|expected| expected.eq_ignore_ascii_case(answer.trim()))
+}
+
+/// Returns the answer and svg image
+fn generate_captcha() -> (String, String) {
+    const SCALE: f32 = 6.;
+    const WIDTH: f32 = 40. * CAPTCHA_LENGTH as f32;
+    const HEIGHT: f32 = 60.;
+    let mut rng = thread_rng();
+    let mut answer = String::new();
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" fill="none" stroke="currentColor" stroke-linecap="round">"#
+    );
+    for i in 0..CAPTCHA_LENGTH {
+        let (char, lines) = GLYPHS.choose(&mut rng).expect("glyphs not empty");
+        answer.push(*char);
+        let x = 8. + i as f32 * 40. + rng.gen_range(-3.0..3.0);
+        let y = 10. + rng.gen_range(-4.0..4.0);
+        let rotate = rng.gen_range(-20.0..20.0);
+        let _ = write!(
+            svg,
+            r#"<g transform="translate({x:.1} {y:.1}) rotate({rotate:.1} 12 18)" stroke-width="{:.1}">"#,
+            rng.gen_range(2.0..3.5)
+        );
+        for line in *lines {
+            let points: Vec<_> = line
+                .iter()
+                .map(|(px, py)| {
+                    let px = px * SCALE + rng.gen_range(-1.5..1.5);
+                    let py = py * SCALE + rng.gen_range(-1.5..1.5);
+                    format!("{px:.1},{py:.1}")
+                })
+                .collect();
+            let _ = write!(svg, r#"<polyline points="{}"/>"#, points.join(" "));
+        }
+        svg.push_str("</g>");
+    }
+    // noise lines crossing the characters
+    for _ in 0..6 {
+        let _ = write!(
+            svg,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke-width="1"/>"#,
+            rng.gen_range(0.0..WIDTH / 2.),
+            rng.gen_range(0.0..HEIGHT),
+            rng.gen_range(WIDTH / 2.0..WIDTH),
+            rng.gen_range(0.0..HEIGHT)
+        );
+    }
+    svg.push_str("</svg>");
+    (answer, svg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_captcha() {
+        let (answer, svg) = generate_captcha();
+        assert_eq!(CAPTCHA_LENGTH, answer.len());
+        assert!(svg.starts_with("<svg"));
+        assert!(!svg.contains(&answer));
+
+        CAPTCHA_ANSWERS.insert("id".to_string(), answer.clone());
+        assert!(!check_captcha("other", &answer));
+        assert!(check_captcha("id", &answer.to_lowercase()));
+        // can only be used once
+        assert!(!check_captcha("id", &answer));
+    }
+}
@@ -1,6 +1,5 @@
 use super::UserExt;
 use activitypub_federation::config::Data;
-use anyhow::anyhow;
 use axum::{Form, Json};
 use axum_macros::debug_handler;
 use chrono::Utc;
@@ -8,9 +7,10 @@ use ibis_api_client::comment::{CreateCommentParams, EditCommentParams};
 use ibis_database::{
     common::{
         comment::{Comment, CommentView},
+        user::ApiTokenScope,
         utils::http_protocol_str,
     },
-    error::BackendResult,
+    error::{BackendError, BackendResult},
     impls::{
         IbisContext,
         comment::{DbCommentInsertForm, DbCommentUpdateForm},
@@ -23,7 +23,7 @@ use ibis_federate::{
         undo_delete_comment::UndoDeleteComment,
     },
     objects::comment::CommentWrapper,
-    validate::{validate_comment_max_depth, validate_not_empty},
+    validate::{validate_comment_max_depth, validate_not_banned, validate_not_empty},
 };
 use url::Url;
 
@@ -33,15 +33,19 @@ pub(crate) async fn create_comment(
     context: Data<IbisContext>,
     Form(params): Form<CreateCommentParams>,
 ) -> BackendResult<Json<CommentView>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
     validate_not_empty(&params.content)?;
     let mut depth = 0;
     if let Some(parent_id) = params.parent_id {
         let parent = Comment::read(parent_id, &context)?;
         if parent.deleted {
-            return Err(anyhow!("Cant reply to deleted comment").into());
+            return Err(BackendError::validation("Cant reply to deleted comment"));
         }
         if parent.article_id != params.article_id {
-            return Err(anyhow!("Invalid article_id/parent_id combination").into());
+            return Err(BackendError::validation(
+                "Invalid article_id/parent_id combination",
+            ));
         }
         depth = parent.depth + 1;
         validate_comment_max_depth(depth)?;
@@ -86,15 +90,19 @@ pub(crate) async fn edit_comment(
     context: Data<IbisContext>,
     Form(params): Form<EditCommentParams>,
 ) -> BackendResult<Json<CommentView>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
     if let Some(content) = &params.content {
         validate_not_empty(content)?;
     }
     if params.content.is_none() && params.deleted.is_none() {
-        return Err(anyhow!("Edit has no parameters").into());
+        return Err(BackendError::validation("Edit has no parameters"));
     }
     let orig_comment = Comment::read(params.id, &context)?;
     if orig_comment.creator_id != user.person.id {
-        return Err(anyhow!("Cannot edit comment created by another user").into());
+        return Err(BackendError::forbidden(
+            "Cannot edit comment created by another user",
+        ));
     }
     let form = DbCommentUpdateForm {
         content: params.content,
@@ -1,25 +1,35 @@
-use super::{UserExt, empty_to_none};
+use super::{UserExt, check_is_admin, empty_to_none};
 use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
 use anyhow::anyhow;
 use axum::{Form, Json, extract::Query};
 use axum_macros::debug_handler;
-use ibis_api_client::instance::{FollowInstanceParams, GetInstanceParams, UpdateInstanceParams};
+use ibis_api_client::instance::{
+    BlockInstanceParams,
+    FollowInstanceParams,
+    GetInstanceParams,
+    UpdateInstanceParams,
+};
 use ibis_database::{
     common::{
+        PaginationParams,
         ResolveObjectParams,
         SuccessResponse,
-        instance::{Instance, InstanceView, InstanceWithArticles},
+        instance::{Instance, InstanceBlock, InstanceView, InstanceWithArticles},
+        user::ApiTokenScope,
         utils::http_protocol_str,
     },
-    error::BackendResult,
+    error::{BackendError, BackendResult},
     impls::{
         IbisContext,
         instance::{DbInstanceUpdateForm, InstanceViewQuery},
     },
+    utils::limit_and_offset,
 };
 use ibis_federate::{
     activities::{follow::Follow, undo_follow::UndoFollow},
+    clear_blocked_instances_cache,
     objects::instance::InstanceWrapper,
+    webfinger::resolve_object,
 };
 use moka::sync::Cache;
 use std::{sync::LazyLock, time::Duration};
@@ -47,7 +57,7 @@ pub(crate) async fn get_instance(
                 Instance::read_view(Id(id), &context)?
             }
         }
-        _ => return Err(anyhow!("invalid params").into()),
+        _ => return Err(BackendError::validation("invalid params")),
     };
 
     Ok(Json(instance))
@@ -74,6 +84,7 @@ pub(crate) async fn follow_instance(
     context: Data<IbisContext>,
     Form(params): Form<FollowInstanceParams>,
 ) -> BackendResult<Json<SuccessResponse>> {
+    user.check_scope(ApiTokenScope::Edit)?;
     let instance = Instance::read(params.id, &context)?;
     let person = user.person.clone();
     let actor = user.inner().person.into();
@@ -90,32 +101,76 @@ pub(crate) async fn follow_instance(
 
 /// Fetch a remote instance actor. This automatically synchronizes the remote articles collection to
 /// the local instance, and allows for interactions such as following.
+///
+/// The instance can be given either by ap_id or by handle like `acct:example.com@example.com`.
 #[debug_handler]
 pub(super) async fn resolve_instance(
     Query(params): Query<ResolveObjectParams>,
     context: Data<IbisContext>,
 ) -> BackendResult<Json<Instance>> {
-    let instance: InstanceWrapper = ObjectId::from(params.id).dereference(&context).await?;
+    let instance: InstanceWrapper = resolve_object(params.id, &context).await?;
     Ok(Json(instance.0))
 }
 
 #[debug_handler]
 pub(crate) async fn list_instance_views(
+    Query(query): Query<PaginationParams>,
     context: Data<IbisContext>,
 ) -> BackendResult<Json<Vec<InstanceWithArticles>>> {
+    let (limit, offset) = limit_and_offset(query.page, query.limit)?;
     let instances = if cfg!(debug_assertions) {
-        Instance::list_with_articles(&context)?
+        Instance::list_with_articles(limit, offset, &context)?
     } else {
         // Cache result of the db read in prod because it uses a lot of queries and rarely changes
-        static CACHE: LazyLock<Cache<(), Vec<InstanceWithArticles>>> = LazyLock::new(|| {
-            Cache::builder()
-                .max_capacity(1)
-                .time_to_live(Duration::from_secs(60 * 60))
-                .build()
-        });
+        static CACHE: LazyLock<Cache<(i64, i64), Vec<InstanceWithArticles>>> =
+            LazyLock::new(|| {
+                Cache::builder()
+                    .max_capacity(100)
+                    .time_to_live(Duration::from_secs(60 * 60))
+                    .build()
+            });
         CACHE
-            .try_get_with((), || Instance::list_with_articles(&context))
+            .try_get_with((limit, offset), || {
+                Instance::list_with_articles(limit, offset, &context)
+            })
             .map_err(|e| anyhow!(e))?
     };
     Ok(Json(instances))
 }
+
+/// Block or unblock federation with a domain. Takes effect immediately for all incoming and
+/// outgoing federation requests. Optionally removes existing content from the blocked domain.
+#[debug_handler]
+pub(crate) async fn block_instance(
+    user: UserExt,
+    context: Data<IbisContext>,
+    Form(params): Form<BlockInstanceParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    check_is_admin(&user)?;
+    let domain = params.domain.trim().to_lowercase();
+    if domain.is_empty() || domain.contains(['/', ',']) {
+        return Err(BackendError::validation(format!("Invalid domain {domain}")));
+    }
+    if domain == context.config.federation.domain {
+        return Err(BackendError::validation("Cannot block local instance"));
+    }
+    if params.block {
+        InstanceBlock::create(&domain, &context)?;
+        if params.purge {
+            Instance::purge(&domain, &context)?;
+        }
+    } else {
+        InstanceBlock::delete(&domain, &context)?;
+    }
+    clear_blocked_instances_cache();
+    Ok(Json(SuccessResponse::default()))
+}
+
+#[debug_handler]
+pub(crate) async fn list_instance_blocks(
+    user: UserExt,
+    context: Data<IbisContext>,
+) -> BackendResult<Json<Vec<InstanceBlock>>> {
+    check_is_admin(&user)?;
+    Ok(Json(InstanceBlock::list(&context)?))
+}
@@ -2,12 +2,19 @@ use crate::api::{
     article::{
         create_article,
         edit_article,
+        find_missing_links,
         fork_article,
         get_article,
         get_conflict,
         list_articles,
+        list_backlinks,
+        list_categories,
+        list_template_usage,
+        list_wanted_articles,
+        move_article,
         protect_article,
         resolve_article,
+        resolve_templates,
         search_article,
     },
     comment::{create_comment, edit_comment},
@@ -15,55 +22,88 @@ use crate::api::{
     user::{get_user, login_user, logout_user, register_user},
 };
 use activitypub_federation::config::Data;
-use anyhow::anyhow;
-use article::{approve_article, delete_conflict, follow_article};
+use article::{approve_article, delete_article, delete_conflict, follow_article, revert_article};
 use axum::{
     Extension,
     Json,
     Router,
-    extract::{Query, rejection::ExtensionRejection},
+    extract::{DefaultBodyLimit, Query, rejection::ExtensionRejection},
     response::IntoResponse,
     routing::{delete, get, patch, post},
 };
 use axum_macros::{FromRequestParts, debug_handler};
-use http::StatusCode;
+use captcha::get_captcha;
 use ibis_api_client::article::GetEditList;
 use ibis_database::{
     common::{
         article::{Edit, EditView},
-        instance::{Instance, SiteView},
-        user::{LocalUserView, Person},
+        instance::{Instance, OAuthProvider, SiteView},
+        user::{ApiTokenScope, LocalUserView, Person},
     },
-    error::BackendResult,
+    error::{BackendError, BackendResult, ErrorType},
     impls::{IbisContext, edit::ViewEditParams},
+    utils::limit_and_offset,
 };
-use instance::{list_instance_views, update_instance};
+use instance::{block_instance, list_instance_blocks, list_instance_views, update_instance};
+use oauth::{oauth_authorize, oauth_login};
 use std::ops::Deref;
+use totp::{disable_totp, enable_totp, setup_totp};
+use upload::{delete_upload, list_uploads, upload_file};
 use user::{
+    approve_registration,
     article_notif_mark_as_read,
+    ban_user,
+    change_password,
     count_notifications,
+    create_api_token,
+    delete_account,
+    delete_api_token,
+    delete_session,
+    export_user_data,
     get_user_follows,
+    list_api_tokens,
+    list_banned_users,
     list_notifications,
+    list_sessions,
+    logout_all,
+    request_password_reset,
+    reset_password,
     update_user_profile,
+    verify_email,
 };
 
 mod article;
+mod captcha;
 mod comment;
 mod instance;
+mod oauth;
+pub mod totp;
+pub(super) mod upload;
 pub(super) mod user;
 
 pub fn api_routes() -> Router<()> {
     Router::new()
         .route(
             "/article",
-            get(get_article).post(create_article).patch(edit_article),
+            get(get_article)
+                .post(create_article)
+                .patch(edit_article)
+                .delete(delete_article),
         )
         .route("/article/list", get(list_articles))
         .route("/article/fork", post(fork_article))
         .route("/article/resolve", get(resolve_article))
+        .route("/article/move", post(move_article))
         .route("/article/protect", post(protect_article))
         .route("/article/approve", post(approve_article))
         .route("/article/follow", post(follow_article))
+        .route("/article/revert", post(revert_article))
+        .route("/article/templates", post(resolve_templates))
+        .route("/article/templates/usage", get(list_template_usage))
+        .route("/article/backlinks", get(list_backlinks))
+        .route("/article/links/missing", post(find_missing_links))
+        .route("/article/wanted", get(list_wanted_articles))
+        .route("/category/list", get(list_categories))
         .route("/edit/list", get(edit_list))
         .route("/conflict", get(get_conflict))
         .route("/conflict", delete(delete_conflict))
@@ -74,9 +114,22 @@ pub fn api_routes() -> Router<()> {
         .route("/instance/follow", post(follow_instance))
         .route("/instance/resolve", get(resolve_instance))
         .route("/instance/list", get(list_instance_views))
+        .route("/instance/block", post(block_instance))
+        .route("/instance/block/list", get(list_instance_blocks))
         .route("/search", get(search_article))
+        .route(
+            "/upload",
+            // size limit is checked by the handler, based on the config
+            post(upload_file)
+                .delete(delete_upload)
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route("/upload/list", get(list_uploads))
         .route("/user", get(get_user))
         .route("/user/follows", get(get_user_follows))
+        .route("/user/ban", post(ban_user))
+        .route("/user/banned", get(list_banned_users))
+        .route("/user/approve", post(approve_registration))
         .route("/user/notifications/list", get(list_notifications))
         .route("/user/notifications/count", get(count_notifications))
         .route(
@@ -84,17 +137,40 @@ pub fn api_routes() -> Router<()> {
             post(article_notif_mark_as_read),
         )
         .route("/account/register", post(register_user))
+        .route("/account/captcha", get(get_captcha))
         .route("/account/login", post(login_user))
+        .route("/account/oauth/authorize", get(oauth_authorize))
+        .route("/account/oauth/login", post(oauth_login))
         .route("/account/logout", post(logout_user))
+        .route("/account/logout_all", post(logout_all))
+        .route("/account/sessions", get(list_sessions))
+        .route("/account/session", delete(delete_session))
+        .route("/account/api_token", post(create_api_token))
+        .route("/account/api_token", delete(delete_api_token))
+        .route("/account/api_token/list", get(list_api_tokens))
         .route("/account/update", post(update_user_profile))
+        .route("/account/change_password", post(change_password))
+        .route("/account/delete", post(delete_account))
+        .route("/account/export", get(export_user_data))
+        .route("/account/totp/setup", post(setup_totp))
+        .route("/account/totp/enable", post(enable_totp))
+        .route("/account/totp/disable", post(disable_totp))
+        .route("/account/verify_email", post(verify_email))
+        .route(
+            "/account/request_password_reset",
+            post(request_password_reset),
+        )
+        .route("/account/reset_password", post(reset_password))
         .route("/site", get(site_view))
 }
 
-pub fn check_is_admin(user: &LocalUserView) -> BackendResult<()> {
+pub fn check_is_admin(user: &UserExt) -> BackendResult<()> {
     if !user.local_user.admin {
-        return Err(anyhow!("Only admin can perform this action").into());
+        return Err(BackendError::forbidden(
+            "Only admin can perform this action",
+        ));
     }
-    Ok(())
+    user.check_scope(ApiTokenScope::Admin)
 }
 
 #[debug_handler]
@@ -107,6 +183,16 @@ pub(crate) async fn site_view(
         config: context.config.options.clone(),
         admin: Person::read_admin(&context)?,
         instance: Instance::read_local(&context)?,
+        email_enabled: context.config.email.is_some(),
+        oauth_providers: context
+            .config
+            .oauth_providers
+            .iter()
+            .map(|p| OAuthProvider {
+                id: p.id.clone(),
+                display_name: p.display_name.clone(),
+            })
+            .collect(),
     }))
 }
 
@@ -117,17 +203,29 @@ pub async fn edit_list(
     user: Option<UserExt>,
     context: Data<IbisContext>,
 ) -> BackendResult<Json<Vec<EditView>>> {
-    let params = if let Some(article_id) = query.article_id {
-        ViewEditParams::ArticleId(article_id)
-    } else if let Some(person_id) = query.person_id {
-        ViewEditParams::PersonId(person_id)
+    let params = match (query.article_id, query.person_id, query.version) {
+        (Some(article_id), _, Some(version)) => ViewEditParams::Version(article_id, version),
+        (Some(article_id), _, None) => ViewEditParams::ArticleId(article_id),
+        (None, Some(person_id), _) => ViewEditParams::PersonId(person_id),
+        (None, None, _) => {
+            return Err(BackendError::validation(
+                "Must provide article_id or person_id",
+            ));
+        }
+    };
+    let (limit, offset) = if query.page.is_none() && query.limit.is_none() {
+        (i64::MAX, 0)
     } else {
-        return Err(anyhow!("Must provide article_id or person_id").into());
+        limit_and_offset(query.page, query.limit)?
     };
+    let user = user.map(|u| u.inner());
+    let hide_bots = query.hide_bots.unwrap_or_else(|| {
+        user.as_ref()
+            .map(|u| u.local_user.hide_bot_edits)
+            .unwrap_or_default()
+    });
     Ok(Json(Edit::view(
-        params,
-        &user.map(|u| u.inner()),
-        &context,
+        params, hide_bots, &user, limit, offset, &context,
     )?))
 }
 
@@ -141,17 +239,40 @@ fn empty_to_none(val: &mut Option<String>) {
     }
 }
 
-#[derive(FromRequestParts)]
+#[derive(FromRequestParts, Clone)]
 #[from_request(rejection(NotLoggedInError))]
 pub struct UserExt {
     #[from_request(via(Extension))]
     local_user_view: LocalUserView,
+    /// Only set if the request was authenticated with an api token
+    token_scope: Option<Extension<ApiTokenScope>>,
 }
 
 impl UserExt {
     pub fn inner(self) -> LocalUserView {
         self.local_user_view
     }
+
+    /// Requests with an api token need at least the given scope, login sessions can perform
+    /// all actions.
+    pub fn check_scope(&self, scope: ApiTokenScope) -> BackendResult<()> {
+        match &self.token_scope {
+            Some(Extension(token_scope)) if *token_scope < scope => Err(BackendError::forbidden(
+                format!("Api token needs scope {scope:?} for this action"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Account settings and api tokens can only be managed with a login session.
+    pub fn check_not_api_token(&self) -> BackendResult<()> {
+        if self.token_scope.is_some() {
+            return Err(BackendError::forbidden(
+                "Api tokens cannot manage the account",
+            ));
+        }
+        Ok(())
+    }
 }
 impl Deref for UserExt {
     type Target = LocalUserView;
@@ -169,6 +290,6 @@ pub struct NotLoggedInError;
 
 impl IntoResponse for NotLoggedInError {
     fn into_response(self) -> axum::response::Response {
-        (StatusCode::FORBIDDEN, "Login required").into_response()
+        BackendError::new(ErrorType::NotLoggedIn, "Login required").into_response()
     }
 }
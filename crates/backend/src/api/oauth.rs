@@ -0,0 +1,302 @@
+use super::{
+    UserExt,
+    totp::check_login_totp,
+    user::{create_cookie, generate_login_token},
+};
+use crate::utils::{HTTP_CLIENT, generate_secret};
+use activitypub_federation::config::Data;
+use axum::{Form, Json, extract::Query, http::HeaderMap};
+use axum_extra::extract::cookie::CookieJar;
+use axum_macros::debug_handler;
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use ibis_api_client::user::{OAuthAuthorizeParams, OAuthLoginParams};
+use ibis_database::{
+    common::{
+        newtypes::LocalUserId,
+        user::{LocalUser, LocalUserView, OAuthAuthorizeResponse, Person, RegistrationApplication},
+        utils::http_protocol_str,
+    },
+    config::IbisConfigOAuthProvider,
+    error::{BackendError, BackendResult, ErrorType},
+    impls::{IbisContext, user::OAuthAccount},
+};
+use ibis_federate::validate::{validate_email, validate_user_name};
+use moka::sync::Cache;
+use rand::{Rng, thread_rng};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{sync::LazyLock, time::Duration};
+use url::Url;
+
+/// Login attempts which were started with [oauth_authorize], keyed by the `state` parameter
+static PENDING_LOGINS: LazyLock<Cache<String, PendingLogin>> = LazyLock::new(|| {
+    Cache::builder()
+        .time_to_live(Duration::from_secs(10 * 60))
+        .max_capacity(10_000)
+        .build()
+});
+
+/// Timeout for requests to the provider
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Logins which only need the two factor code, keyed by the `state` parameter
+static PENDING_TOTP: LazyLock<Cache<String, LocalUserView>> = LazyLock::new(|| {
+    Cache::builder()
+        .time_to_live(Duration::from_secs(5 * 60))
+        .max_capacity(10_000)
+        .build()
+});
+
+#[derive(Clone)]
+struct PendingLogin {
+    provider: String,
+    /// PKCE verifier, the provider only gets its hash during authorization
+    code_verifier: String,
+    /// User who started the login, the provider account is linked to this user
+    local_user_id: Option<LocalUserId>,
+}
+
+/// Endpoints of the provider from `/.well-known/openid-configuration`
+#[derive(Deserialize)]
+struct ProviderMetadata {
+    authorization_endpoint: Url,
+    token_endpoint: Url,
+    userinfo_endpoint: Url,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+    preferred_username: Option<String>,
+    name: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+}
+
+fn provider<'a>(id: &str, context: &'a IbisContext) -> BackendResult<&'a IbisConfigOAuthProvider> {
+    context
+        .config
+        .oauth_providers
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| BackendError::not_found("Unknown login provider"))
+}
+
+fn redirect_uri(context: &IbisContext) -> String {
+    format!(
+        "{}://{}/oauth/callback",
+        http_protocol_str(),
+        context.config.federation.domain
+    )
+}
+
+async fn read_metadata(provider: &IbisConfigOAuthProvider) -> BackendResult<ProviderMetadata> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        provider.issuer.trim_end_matches('/')
+    );
+    Ok(HTTP_CLIENT
+        .get(url)
+        .timeout(PROVIDER_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// Start login with an OpenID Connect provider, using authorization code flow with PKCE.
+#[debug_handler]
+pub(crate) async fn oauth_authorize(
+    context: Data<IbisContext>,
+    user: Option<UserExt>,
+    Query(params): Query<OAuthAuthorizeParams>,
+) -> BackendResult<Json<OAuthAuthorizeResponse>> {
+    if let Some(user) = &user {
+        user.check_not_api_token()?;
+    }
+    let provider = provider(&params.provider, &context)?;
+    let metadata = read_metadata(provider).await?;
+    let state = generate_secret();
+    let code_verifier = format!("{}{}", generate_secret(), generate_secret());
+    let code_challenge = BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+    PENDING_LOGINS.insert(
+        state.clone(),
+        PendingLogin {
+            provider: provider.id.clone(),
+            code_verifier,
+            local_user_id: user.map(|u| u.local_user.id),
+        },
+    );
+    let mut url = metadata.authorization_endpoint;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", &redirect_uri(&context))
+        .append_pair("scope", &provider.scopes)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &code_challenge)
+        .append_pair("code_challenge_method", "S256");
+    Ok(Json(OAuthAuthorizeResponse {
+        url: url.to_string(),
+    }))
+}
+
+/// Complete the login with the code from the provider. The provider account is linked to the
+/// logged in user, or to a new account if it wasn't used before. It needs to be called by the
+/// same user who started the login, so that nobody else can link their account.
+///
+/// If the linked account has two factor authentication, the code is required as well. When it
+/// is missing the call fails with [ErrorType::TotpRequired], and can be repeated with the same
+/// state and the code, without going through the provider again.
+#[debug_handler]
+pub(crate) async fn oauth_login(
+    context: Data<IbisContext>,
+    user: Option<UserExt>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Form(params): Form<OAuthLoginParams>,
+) -> BackendResult<(CookieJar, Json<LocalUserView>)> {
+    if let Some(user) = &user {
+        user.check_not_api_token()?;
+    }
+    let logged_in = user.as_ref().map(|u| u.local_user.id);
+    let user = match PENDING_TOTP.remove(&params.state) {
+        Some(user) => user,
+        None => provider_user(&params, user, &context).await?,
+    };
+    if !user.local_user.approved {
+        return Err(BackendError::forbidden(
+            "Your registration is waiting for approval by admins",
+        ));
+    }
+    // linking a provider account to the current user doesnt log in as anyone else
+    if logged_in != Some(user.local_user.id) {
+        if let Err(e) = check_login_totp(&user, params.totp_code.as_deref(), &context) {
+            if e.error_type == ErrorType::TotpRequired {
+                PENDING_TOTP.insert(params.state, user);
+            }
+            return Err(e);
+        }
+    }
+    let token = generate_login_token(&user, &headers, &context)?;
+    let jar = jar.add(create_cookie(token, &context));
+    Ok((jar, Json(user)))
+}
+
+/// Exchange the code for the account at the provider, and return the local user which it is
+/// linked to.
+async fn provider_user(
+    params: &OAuthLoginParams,
+    user: Option<UserExt>,
+    context: &IbisContext,
+) -> BackendResult<LocalUserView> {
+    let pending = PENDING_LOGINS
+        .remove(&params.state)
+        .ok_or_else(|| BackendError::validation("Login expired, please try again"))?;
+    if pending.local_user_id != user.as_ref().map(|u| u.local_user.id) {
+        return Err(BackendError::forbidden(
+            "Login was started by a different user",
+        ));
+    }
+    let provider = provider(&pending.provider, context)?;
+    let metadata = read_metadata(provider).await?;
+    let token: TokenResponse = HTTP_CLIENT
+        .post(metadata.token_endpoint)
+        .timeout(PROVIDER_TIMEOUT)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", &params.code),
+            ("redirect_uri", &redirect_uri(context)),
+            ("client_id", &provider.client_id),
+            ("client_secret", &provider.client_secret),
+            ("code_verifier", &pending.code_verifier),
+        ])
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|_| BackendError::forbidden("Login failed at provider"))?
+        .json()
+        .await?;
+    let user_info: UserInfo = HTTP_CLIENT
+        .get(metadata.userinfo_endpoint)
+        .timeout(PROVIDER_TIMEOUT)
+        .bearer_auth(token.access_token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(
+        match (
+            OAuthAccount::read_user(&provider.id, &user_info.sub, context),
+            user,
+        ) {
+            (Ok(linked), _) => linked,
+            (Err(_), Some(user)) => {
+                OAuthAccount::create(user.local_user.id, &provider.id, &user_info.sub, context)?;
+                user.inner()
+            }
+            (Err(_), None) => create_user(provider, &user_info, context)?,
+        },
+    )
+}
+
+/// Create a new local user for the provider account. The username is taken from the provider
+/// if possible, with a random suffix if it is already taken.
+///
+/// This ignores `registration_open` and the captcha, as `create_accounts` is configured per
+/// provider and the provider already checked the user. If registration approval is enabled the
+/// account still needs to be approved by an admin.
+fn create_user(
+    provider: &IbisConfigOAuthProvider,
+    user_info: &UserInfo,
+    context: &IbisContext,
+) -> BackendResult<LocalUserView> {
+    if !provider.create_accounts {
+        return Err(BackendError::forbidden(
+            "No account is linked to this login, and creating accounts is disabled",
+        ));
+    }
+    let base = user_info
+        .preferred_username
+        .as_ref()
+        .or(user_info.name.as_ref())
+        .map(|n| {
+            n.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .take(15)
+                .collect::<String>()
+        })
+        .filter(|n| validate_user_name(n).is_ok())
+        .unwrap_or_else(|| "user".to_string());
+    let mut username = base.clone();
+    while Person::read_local_from_name(&username, context).is_ok() {
+        username = format!("{base}_{}", thread_rng().gen_range(1000..10000));
+    }
+    // Without password, login is only possible through the provider until the user sets one
+    let mut user = Person::create_local(username, None, false, context)?;
+    OAuthAccount::create(user.local_user.id, &provider.id, &user_info.sub, context)?;
+    if context.config.options.registration_approval {
+        RegistrationApplication::create(user.local_user.id, None, context)?;
+        user.local_user.approved = false;
+    }
+
+    let email = user_info.email.as_deref().map(validate_email);
+    if let (Some(Ok(email)), true) = (email, user_info.email_verified) {
+        if LocalUser::read_from_email(&email, context).is_err() {
+            LocalUser::update_email(user.local_user.id, Some(email.clone()), context)?;
+            LocalUser::set_email_verified(user.local_user.id, context)?;
+            user.local_user.email = Some(email);
+            user.local_user.email_verified = true;
+        }
+    }
+    Ok(user)
+}
@@ -0,0 +1,235 @@
+use super::{UserExt, user::check_password};
+use activitypub_federation::config::Data;
+use axum::{Form, Json};
+use axum_macros::debug_handler;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use ibis_api_client::user::{DisableTotpParams, EnableTotpParams};
+use ibis_database::{
+    common::{
+        SuccessResponse,
+        user::{EnableTotpResponse, LocalUser, LocalUserView, TotpSetupResponse},
+    },
+    error::{BackendError, BackendResult, ErrorType},
+    impls::IbisContext,
+};
+use rand::{Rng, distributions::Alphanumeric, thread_rng};
+use sha1::Sha1;
+use url::form_urlencoded::byte_serialize;
+
+/// Length of each totp step in seconds
+const TOTP_PERIOD: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+const RECOVERY_CODE_COUNT: usize = 10;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Generate a new secret. Two factor authentication stays disabled until it is confirmed with
+/// [enable_totp].
+#[debug_handler]
+pub(crate) async fn setup_totp(
+    context: Data<IbisContext>,
+    user: UserExt,
+) -> BackendResult<Json<TotpSetupResponse>> {
+    user.check_not_api_token()?;
+    if user.local_user.totp_enabled {
+        return Err(BackendError::conflict(
+            "Two factor authentication is already enabled",
+        ));
+    }
+    let secret = base32_encode(&thread_rng().r#gen::<[u8; 20]>());
+    LocalUser::set_totp_secret(user.local_user.id, Some(secret.clone()), &context)?;
+    let issuer: String = byte_serialize(context.domain().as_bytes()).collect();
+    let username: String = byte_serialize(user.person.username.as_bytes()).collect();
+    let uri = format!(
+        "otpauth://totp/{issuer}:{username}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD}"
+    );
+    Ok(Json(TotpSetupResponse { secret, uri }))
+}
+
+/// Enable two factor authentication after checking a code for the secret from [setup_totp].
+#[debug_handler]
+pub(crate) async fn enable_totp(
+    context: Data<IbisContext>,
+    user: UserExt,
+    Form(params): Form<EnableTotpParams>,
+) -> BackendResult<Json<EnableTotpResponse>> {
+    user.check_not_api_token()?;
+    let Some(secret) = &user.local_user.totp_secret else {
+        return Err(BackendError::validation(
+            "Two factor authentication needs to be set up first",
+        ));
+    };
+    if user.local_user.totp_enabled {
+        return Err(BackendError::conflict(
+            "Two factor authentication is already enabled",
+        ));
+    }
+    if !use_totp_code(&user, secret, &params.code, &context)? {
+        return Err(BackendError::validation("Invalid two factor code"));
+    }
+    let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(10)
+                .map(|c| char::from(c).to_ascii_lowercase())
+                .collect()
+        })
+        .collect();
+    LocalUser::enable_totp(user.local_user.id, &recovery_codes, &context)?;
+    Ok(Json(EnableTotpResponse { recovery_codes }))
+}
+
+#[debug_handler]
+pub(crate) async fn disable_totp(
+    context: Data<IbisContext>,
+    user: UserExt,
+    Form(params): Form<DisableTotpParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    user.check_not_api_token()?;
+    check_password(&user, &params.password)?;
+    check_login_totp(&user, Some(&params.code), &context)?;
+    LocalUser::set_totp_secret(user.local_user.id, None, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// Second login step for users with two factor authentication. Accepts either a code from the
+/// authenticator app or a recovery code.
+pub(super) fn check_login_totp(
+    user: &LocalUserView,
+    code: Option<&str>,
+    context: &IbisContext,
+) -> BackendResult<()> {
+    let (true, Some(secret)) = (user.local_user.totp_enabled, &user.local_user.totp_secret) else {
+        return Ok(());
+    };
+    let Some(code) = code.map(str::trim).filter(|c| !c.is_empty()) else {
+        return Err(BackendError::new(
+            ErrorType::TotpRequired,
+            "Two factor code is required",
+        ));
+    };
+    if use_totp_code(user, secret, code, context)?
+        || LocalUser::use_totp_recovery_code(user.local_user.id, &code.to_lowercase(), context)?
+    {
+        Ok(())
+    } else {
+        Err(BackendError::forbidden("Invalid two factor code"))
+    }
+}
+
+/// Checks the code and marks its time step as used, so that an intercepted code cant be used
+/// a second time.
+fn use_totp_code(
+    user: &LocalUserView,
+    secret: &str,
+    code: &str,
+    context: &IbisContext,
+) -> BackendResult<bool> {
+    match check_totp_code(secret, code, Utc::now().timestamp())? {
+        Some(step) => LocalUser::use_totp_step(user.local_user.id, step, context),
+        None => Ok(false),
+    }
+}
+
+/// Returns the time step which matches the code. Codes from the previous and next step are also
+/// accepted, in case the clocks of client and server are slightly out of sync.
+fn check_totp_code(secret: &str, code: &str, time: i64) -> BackendResult<Option<i64>> {
+    let step = time / TOTP_PERIOD;
+    for s in [step - 1, step, step + 1] {
+        if generate_totp_code(secret, s * TOTP_PERIOD)? == code.trim() {
+            return Ok(Some(s));
+        }
+    }
+    Ok(None)
+}
+
+/// Generate the code for the given unix time, as described in RFC 6238.
+pub fn generate_totp_code(secret: &str, time: i64) -> BackendResult<String> {
+    let secret = base32_decode(secret)
+        .ok_or_else(|| BackendError::validation("Invalid two factor secret"))?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&secret).expect("hmac accepts any key length");
+    mac.update(&(time / TOTP_PERIOD).to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    // dynamic truncation from RFC 4226
+    let offset = (hash[hash.len() - 1] & 0xf) as usize;
+    let value = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    Ok(format!(
+        "{:0width$}",
+        value % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    ))
+}
+
+/// Base32 without padding, as used for totp secrets
+fn base32_encode(data: &[u8]) -> String {
+    let mut res = String::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            res.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        res.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    res
+}
+
+fn base32_decode(data: &str) -> Option<Vec<u8>> {
+    let mut res = vec![];
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            res.push((buffer >> bits) as u8);
+        }
+    }
+    Some(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn test_totp() {
+        // test vectors from RFC 6238, truncated to 6 digits
+        let secret = base32_encode(b"12345678901234567890");
+        assert_eq!("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", secret);
+        assert_eq!(
+            b"12345678901234567890".to_vec(),
+            base32_decode(&secret).unwrap()
+        );
+        assert_eq!("287082", generate_totp_code(&secret, 59).unwrap());
+        assert_eq!("081804", generate_totp_code(&secret, 1111111109).unwrap());
+        assert_eq!("005924", generate_totp_code(&secret, 1234567890).unwrap());
+
+        assert_eq!(
+            Some(1111111109 / TOTP_PERIOD),
+            check_totp_code(&secret, "081804", 1111111109 + 30).unwrap()
+        );
+        assert!(
+            check_totp_code(&secret, "081804", 1111111109 + 90)
+                .unwrap()
+                .is_none()
+        );
+        assert!(base32_decode("invalid!").is_none());
+    }
+}
@@ -0,0 +1,242 @@
+use super::UserExt;
+use crate::{storage::Storage, thumbnail::generate_thumbnail, utils::generate_secret};
+use activitypub_federation::config::Data;
+use axum::{
+    Form,
+    Json,
+    extract::{Multipart, Path, Query},
+    http::{
+        HeaderValue,
+        header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+    },
+    response::{IntoResponse, Response},
+};
+use axum_macros::debug_handler;
+use ibis_api_client::upload::DeleteUploadParams;
+use ibis_database::{
+    common::{
+        PaginationParams,
+        SuccessResponse,
+        upload::{Upload, UploadView},
+        user::ApiTokenScope,
+    },
+    error::{BackendError, BackendResult},
+    impls::{IbisContext, upload::DbUploadForm},
+    utils::limit_and_offset,
+};
+use ibis_federate::validate::validate_not_banned;
+use tokio::task::spawn_blocking;
+
+/// Other files than images are only accepted with these types, based on the file extension.
+/// Images are detected from the file content instead.
+const ALLOWED_FILE_TYPES: &[&str] = &[
+    "application/pdf",
+    "text/plain",
+    "audio/mpeg",
+    "audio/ogg",
+    "video/mp4",
+    "video/webm",
+];
+
+/// Upload an image or other file, which can then be embedded in articles. The file needs to
+/// be in the first field of the multipart form.
+#[debug_handler]
+pub(crate) async fn upload_file(
+    context: Data<IbisContext>,
+    user: UserExt,
+    mut multipart: Multipart,
+) -> BackendResult<Json<UploadView>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    validate_not_banned(&user.person)?;
+    let config = &context.config.upload;
+    let Some(mut field) = multipart.next_field().await? else {
+        return Err(BackendError::validation("No file was uploaded"));
+    };
+    let original_name = original_name(field.file_name().unwrap_or_default());
+    let mut data = vec![];
+    while let Some(chunk) = field.chunk().await? {
+        if data.len() + chunk.len() > config.max_size {
+            return Err(BackendError::validation(format!(
+                "File is too large, maximum size is {} MB",
+                config.max_size / 1_000_000
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    if data.is_empty() {
+        return Err(BackendError::validation("Uploaded file is empty"));
+    }
+    let (content_type, extension) = detect_type(&original_name, &data)?;
+
+    let storage = Storage::new(&context);
+    let name = generate_secret();
+    let (data, thumbnail) = {
+        let content_type = content_type.clone();
+        let size = config.thumbnail_size;
+        spawn_blocking(move || {
+            let thumbnail = generate_thumbnail(&data, &content_type, size);
+            (data, thumbnail)
+        })
+        .await?
+    };
+    let thumbnail = match thumbnail {
+        Some(thumbnail) => {
+            let filename = format!("{name}-thumbnail.png");
+            storage.write(&filename, thumbnail, "image/png").await?;
+            Some(filename)
+        }
+        None => None,
+    };
+    let filename = format!("{name}.{extension}");
+    let size = data.len() as i64;
+    storage.write(&filename, data, &content_type).await?;
+
+    let form = DbUploadForm {
+        uploader_id: user.person.id,
+        filename,
+        original_name,
+        content_type,
+        size,
+        thumbnail,
+    };
+    Ok(Json(Upload::create(form, &context)?))
+}
+
+/// Media library with all files which were uploaded to this instance.
+#[debug_handler]
+pub(crate) async fn list_uploads(
+    context: Data<IbisContext>,
+    Query(query): Query<PaginationParams>,
+) -> BackendResult<Json<Vec<UploadView>>> {
+    let (limit, offset) = limit_and_offset(query.page, query.limit)?;
+    Ok(Json(Upload::list(limit, offset, &context)?))
+}
+
+/// Uploads can be deleted by the uploader and by admins. Articles which embed the file are
+/// not changed.
+#[debug_handler]
+pub(crate) async fn delete_upload(
+    context: Data<IbisContext>,
+    user: UserExt,
+    Form(params): Form<DeleteUploadParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    user.check_scope(ApiTokenScope::Edit)?;
+    let upload = Upload::read(params.id, &context)?;
+    if upload.uploader_id != user.person.id && !user.local_user.admin {
+        return Err(BackendError::forbidden(
+            "Only the uploader or admins can delete this file",
+        ));
+    }
+    let storage = Storage::new(&context);
+    storage.delete(&upload.filename).await?;
+    if let Some(thumbnail) = &upload.thumbnail {
+        storage.delete(thumbnail).await?;
+    }
+    Upload::delete(upload.id, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// Serve an uploaded file or thumbnail. Files other than images, audio and video are always
+/// downloaded instead of being opened in the browser.
+#[debug_handler]
+pub(crate) async fn get_media(
+    context: Data<IbisContext>,
+    Path(filename): Path<String>,
+) -> BackendResult<Response> {
+    let upload = Upload::read_from_filename(&filename, &context)?;
+    let data = Storage::new(&context).read(&filename).await?;
+    let content_type = if upload.filename == filename {
+        upload.content_type.as_str()
+    } else {
+        "image/png"
+    };
+    let disposition = if ["image/", "audio/", "video/"]
+        .iter()
+        .any(|t| content_type.starts_with(t))
+    {
+        "inline".to_string()
+    } else {
+        let name: String = upload
+            .original_name
+            .chars()
+            .filter(|c| c.is_ascii_graphic() || *c == ' ')
+            .collect();
+        format!("attachment; filename=\"{name}\"")
+    };
+    let mut res = data.into_response();
+    let headers = res.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type)?);
+    headers.insert(CONTENT_DISPOSITION, HeaderValue::from_str(&disposition)?);
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    // files are never changed, only deleted
+    headers.insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=31536000, immutable"),
+    );
+    Ok(res)
+}
+
+/// Remove path and characters which could cause problems in headers or markdown.
+fn original_name(name: &str) -> String {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '"' | '[' | ']' | '(' | ')'))
+        .take(100)
+        .collect();
+    let name = name.trim();
+    if name.is_empty() {
+        "file".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns content type and file extension. Images need to have one of the common formats,
+/// svg is rejected because it can contain scripts.
+fn detect_type(name: &str, data: &[u8]) -> BackendResult<(String, String)> {
+    let image = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("image/png", "png"))
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some(("image/jpeg", "jpg"))
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some(("image/gif", "gif"))
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        Some(("image/webp", "webp"))
+    } else {
+        None
+    };
+    if let Some((content_type, extension)) = image {
+        return Ok((content_type.to_string(), extension.to_string()));
+    }
+    let extension = name
+        .rsplit_once('.')
+        .map(|(_, e)| e.to_lowercase())
+        .unwrap_or_default();
+    let content_type = mime_guess::from_ext(&extension)
+        .first_raw()
+        .filter(|t| ALLOWED_FILE_TYPES.contains(t))
+        .ok_or_else(|| BackendError::validation("This file type is not allowed"))?;
+    Ok((content_type.to_string(), extension))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn test_detect_type() {
+        let png = detect_type("image.txt", b"\x89PNG\r\n\x1a\n...").unwrap();
+        assert_eq!(("image/png".to_string(), "png".to_string()), png);
+        let pdf = detect_type("Document.PDF", b"%PDF-1.4").unwrap();
+        assert_eq!(("application/pdf".to_string(), "pdf".to_string()), pdf);
+        assert!(detect_type("image.png", b"not an image").is_err());
+        assert!(detect_type("image.svg", b"<svg></svg>").is_err());
+        assert!(detect_type("page.html", b"<html></html>").is_err());
+
+        assert_eq!("photo.jpg", original_name("C:\\Users\\me\\photo.jpg"));
+        assert_eq!("a b.png", original_name("../a [b].png"));
+        assert_eq!("file", original_name(""));
+    }
+}
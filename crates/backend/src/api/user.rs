@@ -1,27 +1,92 @@
-use super::{UserExt, empty_to_none};
+use super::{
+    UserExt,
+    article::remove_article,
+    captcha::check_captcha,
+    check_is_admin,
+    empty_to_none,
+    totp::check_login_totp,
+};
+use crate::{
+    email::{
+        RESET_PASSWORD_EXPIRY,
+        VERIFY_EMAIL_EXPIRY,
+        email_config,
+        send_password_reset_email,
+        send_verification_email,
+    },
+    utils::{generate_article_version_at, generate_secret},
+};
 use activitypub_federation::config::Data;
-use anyhow::anyhow;
-use axum::{Form, Json, extract::Query};
+use axum::{
+    Extension,
+    Form,
+    Json,
+    extract::Query,
+    http::{HeaderMap, header::USER_AGENT},
+};
 use axum_extra::extract::cookie::{Cookie, CookieJar, Expiration, SameSite};
 use axum_macros::debug_handler;
 use bcrypt::verify;
 use chrono::Utc;
+use diffy::merge;
 use ibis_api_client::{
     notifications::ArticleNotifMarkAsReadParams,
-    user::{GetUserParams, LoginUserParams, RegisterUserParams, UpdateUserParams},
+    user::{
+        ApproveRegistrationParams,
+        BanUserParams,
+        ChangePasswordParams,
+        CreateApiTokenParams,
+        DeleteAccountParams,
+        DeleteApiTokenParams,
+        DeleteSessionParams,
+        GetUserParams,
+        LoginUserParams,
+        RegisterUserParams,
+        RequestPasswordResetParams,
+        ResetPasswordParams,
+        UpdateUserParams,
+        VerifyEmailParams,
+    },
 };
 use ibis_database::{
     common::{
         AUTH_COOKIE,
+        Auth,
+        PaginationParams,
         SuccessResponse,
+        article::{Article, Edit},
+        comment::Comment,
         instance::InstanceFollow,
+        newtypes::{PersonId, SessionId},
         notifications::ApiNotification,
-        user::{LocalUserView, Person},
+        user::{
+            ApiToken,
+            ApiTokenScope,
+            CreateApiTokenResponse,
+            LocalUser,
+            LocalUserView,
+            Person,
+            RegistrationApplication,
+            Session,
+            SessionView,
+            UserExport,
+        },
     },
-    error::BackendResult,
-    impls::{IbisContext, notifications::Notification, read_jwt_secret, user::PersonUpdateForm},
+    error::{BackendError, BackendResult},
+    impls::{
+        IbisContext,
+        comment::DbCommentUpdateForm,
+        edit::ViewEditParams,
+        notifications::Notification,
+        read_jwt_secret,
+        user::{EmailToken, EmailTokenKind, LocalUserUpdateForm, PersonUpdateForm},
+    },
+    utils::limit_and_offset,
+};
+use ibis_federate::{
+    activities::{comment::delete_comment::DeleteComment, delete::Delete, submit_article_update},
+    validate::{validate_display_name, validate_email, validate_not_empty, validate_user_name},
 };
-use ibis_federate::validate::{validate_display_name, validate_user_name};
 use jsonwebtoken::{
     DecodingKey,
     EncodingKey,
@@ -40,17 +105,30 @@ struct Claims {
     pub sub: String,
     /// hostname
     pub iss: String,
+    /// Login session, the token is only valid as long as the session exists
+    pub sid: SessionId,
     /// Creation time as unix timestamp
     pub iat: i64,
     /// Expiration time
     pub exp: u64,
 }
 
-fn generate_login_token(person: &Person, context: &Data<IbisContext>) -> BackendResult<String> {
+/// Creates a new session for the user and returns an auth token for it.
+pub(super) fn generate_login_token(
+    user: &LocalUserView,
+    headers: &HeaderMap,
+    context: &Data<IbisContext>,
+) -> BackendResult<String> {
     let hostname = context.domain().to_string();
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(ToString::to_string);
+    let session = Session::create(user.local_user.id, user_agent, context)?;
     let claims = Claims {
-        sub: person.username.clone(),
+        sub: user.person.username.clone(),
         iss: hostname,
+        sid: session.id,
         iat: Utc::now().timestamp(),
         exp: get_current_timestamp() + 60 * 60 * 24 * 365,
     };
@@ -61,26 +139,68 @@ fn generate_login_token(person: &Person, context: &Data<IbisContext>) -> Backend
     Ok(jwt)
 }
 
-pub async fn validate(jwt: &str, context: &IbisContext) -> BackendResult<LocalUserView> {
+fn decode_claims(jwt: &str, context: &IbisContext) -> BackendResult<Claims> {
     let validation = Validation::default();
     let secret = read_jwt_secret(context)?;
     let key = DecodingKey::from_secret(secret.as_bytes());
-    let claims = decode::<Claims>(jwt, &key, &validation)?;
-    Person::read_local_from_name(&claims.claims.sub, context)
+    Ok(decode::<Claims>(jwt, &key, &validation)?.claims)
+}
+
+pub async fn validate(jwt: &str, context: &IbisContext) -> BackendResult<LocalUserView> {
+    let claims = decode_claims(jwt, context)?;
+    let user = Person::read_local_from_name(&claims.sub, context)?;
+    Session::mark_used(claims.sid, user.local_user.id, context)?;
+    Ok(user)
+}
+
+/// Session of the auth token which was used for the current request.
+fn current_session(auth: Option<Extension<Auth>>, context: &IbisContext) -> Option<SessionId> {
+    let jwt = auth?.0.0?;
+    decode_claims(&jwt, context).ok().map(|c| c.sid)
 }
 
 #[debug_handler]
 pub(crate) async fn register_user(
     context: Data<IbisContext>,
     jar: CookieJar,
-    Form(params): Form<RegisterUserParams>,
+    headers: HeaderMap,
+    Form(mut params): Form<RegisterUserParams>,
 ) -> BackendResult<(CookieJar, Json<LocalUserView>)> {
-    if !context.config.options.registration_open {
-        return Err(anyhow!("Registration is closed").into());
+    let options = &context.config.options;
+    if !options.registration_open || !options.password_login {
+        return Err(BackendError::forbidden("Registration is closed"));
+    }
+    if options.registration_captcha {
+        let (Some(id), Some(answer)) = (&params.captcha_id, &params.captcha_answer) else {
+            return Err(BackendError::validation("Captcha is required"));
+        };
+        if !check_captcha(id, answer) {
+            return Err(BackendError::validation("Captcha answer is wrong"));
+        }
     }
     validate_user_name(&params.username)?;
-    let user = Person::create_local(params.username, params.password, false, &context)?;
-    let token = generate_login_token(&user.person, &context)?;
+    empty_to_none(&mut params.answer);
+    if options.registration_approval && options.registration_question.is_some() {
+        validate_not_empty(params.answer.as_deref().unwrap_or_default())?;
+    }
+    empty_to_none(&mut params.email);
+    let email = params
+        .email
+        .map(|e| check_new_email(&e, &context))
+        .transpose()?;
+    let mut user = Person::create_local(params.username, Some(params.password), false, &context)?;
+    if let Some(email) = email {
+        set_email(&user, email.clone(), &context)?;
+        user.local_user.email = Some(email);
+    }
+
+    // Dont login if the user first needs to be approved
+    if options.registration_approval {
+        RegistrationApplication::create(user.local_user.id, params.answer, &context)?;
+        user.local_user.approved = false;
+        return Ok((jar, Json(user)));
+    }
+    let token = generate_login_token(&user, &headers, &context)?;
     let jar = jar.add(create_cookie(token, &context));
     Ok((jar, Json(user)))
 }
@@ -89,19 +209,34 @@ pub(crate) async fn register_user(
 pub(crate) async fn login_user(
     context: Data<IbisContext>,
     jar: CookieJar,
+    headers: HeaderMap,
     Form(params): Form<LoginUserParams>,
 ) -> BackendResult<(CookieJar, Json<LocalUserView>)> {
-    let user = Person::read_local_from_name(&params.username, &context)?;
-    let valid = verify(&params.password, &user.local_user.password_encrypted)?;
+    if !context.config.options.password_login {
+        return Err(BackendError::forbidden("Password login is disabled"));
+    }
+    // Same error for unknown username and wrong password, so that usernames cant be guessed
+    let user = Person::read_local_from_name(&params.username, &context)
+        .map_err(|_| BackendError::forbidden("Invalid login"))?;
+    let valid = match &user.local_user.password_encrypted {
+        Some(password_encrypted) => verify(&params.password, password_encrypted)?,
+        None => false,
+    };
     if !valid {
-        return Err(anyhow!("Invalid login").into());
+        return Err(BackendError::forbidden("Invalid login"));
+    }
+    if !user.local_user.approved {
+        return Err(BackendError::forbidden(
+            "Your registration is waiting for approval by admins",
+        ));
     }
-    let token = generate_login_token(&user.person, &context)?;
+    check_login_totp(&user, params.totp_code.as_deref(), &context)?;
+    let token = generate_login_token(&user, &headers, &context)?;
     let jar = jar.add(create_cookie(token, &context));
     Ok((jar, Json(user)))
 }
 
-fn create_cookie(jwt: String, context: &Data<IbisContext>) -> Cookie<'static> {
+pub(super) fn create_cookie(jwt: String, context: &Data<IbisContext>) -> Cookie<'static> {
     let mut cookie = Cookie::build((AUTH_COOKIE, jwt));
 
     // Must not set cookie domain on localhost
@@ -124,12 +259,99 @@ fn create_cookie(jwt: String, context: &Data<IbisContext>) -> Cookie<'static> {
 #[debug_handler]
 pub(crate) async fn logout_user(
     context: Data<IbisContext>,
+    user: Option<UserExt>,
+    auth: Option<Extension<Auth>>,
     jar: CookieJar,
 ) -> BackendResult<(CookieJar, Json<SuccessResponse>)> {
+    if let (Some(user), Some(session_id)) = (user, current_session(auth, &context)) {
+        Session::delete(session_id, user.local_user.id, &context)?;
+    }
     let jar = jar.remove(create_cookie(String::new(), &context));
     Ok((jar, Json(SuccessResponse::default())))
 }
 
+/// Revoke all sessions of the current user, including the one used for this request.
+#[debug_handler]
+pub(crate) async fn logout_all(
+    context: Data<IbisContext>,
+    user: UserExt,
+    jar: CookieJar,
+) -> BackendResult<(CookieJar, Json<SuccessResponse>)> {
+    user.check_not_api_token()?;
+    Session::delete_all(user.local_user.id, None, &context)?;
+    let jar = jar.remove(create_cookie(String::new(), &context));
+    Ok((jar, Json(SuccessResponse::default())))
+}
+
+#[debug_handler]
+pub(crate) async fn list_sessions(
+    context: Data<IbisContext>,
+    user: UserExt,
+    auth: Option<Extension<Auth>>,
+) -> BackendResult<Json<Vec<SessionView>>> {
+    user.check_not_api_token()?;
+    let current = current_session(auth, &context);
+    let sessions = Session::list(user.local_user.id, &context)?
+        .into_iter()
+        .map(|session| SessionView {
+            current: Some(session.id) == current,
+            session,
+        })
+        .collect();
+    Ok(Json(sessions))
+}
+
+#[debug_handler]
+pub(crate) async fn delete_session(
+    context: Data<IbisContext>,
+    user: UserExt,
+    Form(params): Form<DeleteSessionParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    user.check_not_api_token()?;
+    Session::delete(params.id, user.local_user.id, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// Create an api token for the current user. The token is only returned once.
+#[debug_handler]
+pub(crate) async fn create_api_token(
+    context: Data<IbisContext>,
+    user: UserExt,
+    Form(params): Form<CreateApiTokenParams>,
+) -> BackendResult<Json<CreateApiTokenResponse>> {
+    user.check_not_api_token()?;
+    let name = params.name.trim().to_string();
+    validate_not_empty(&name)?;
+    if params.scope == ApiTokenScope::Admin && !user.local_user.admin {
+        return Err(BackendError::forbidden(
+            "Only admin can create tokens with admin scope",
+        ));
+    }
+    let token = generate_secret();
+    let api_token = ApiToken::create(user.local_user.id, name, params.scope, &token, &context)?;
+    Ok(Json(CreateApiTokenResponse { api_token, token }))
+}
+
+#[debug_handler]
+pub(crate) async fn list_api_tokens(
+    context: Data<IbisContext>,
+    user: UserExt,
+) -> BackendResult<Json<Vec<ApiToken>>> {
+    user.check_not_api_token()?;
+    Ok(Json(ApiToken::list(user.local_user.id, &context)?))
+}
+
+#[debug_handler]
+pub(crate) async fn delete_api_token(
+    context: Data<IbisContext>,
+    user: UserExt,
+    Form(params): Form<DeleteApiTokenParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    user.check_not_api_token()?;
+    ApiToken::delete(params.id, user.local_user.id, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
+
 #[debug_handler]
 pub(crate) async fn get_user(
     params: Query<GetUserParams>,
@@ -156,23 +378,203 @@ pub(crate) async fn update_user_profile(
     user: UserExt,
     Form(mut params): Form<UpdateUserParams>,
 ) -> BackendResult<Json<SuccessResponse>> {
+    user.check_scope(ApiTokenScope::Edit)?;
     empty_to_none(&mut params.display_name);
     empty_to_none(&mut params.bio);
     validate_display_name(&params.display_name)?;
+    // The email can be used to reset the password, so changing it requires the same
+    // permissions as changing the password
+    let new_email = match params.email.as_deref().map(str::trim) {
+        Some("") => Some(None),
+        Some(email) if Some(&validate_email(email)?) != user.local_user.email.as_ref() => {
+            Some(Some(check_new_email(email, &context)?))
+        }
+        Some(_) | None => None,
+    };
+    if new_email.is_some() {
+        user.check_not_api_token()?;
+        check_password(&user, params.password.as_deref().unwrap_or_default())?;
+    }
     let form = PersonUpdateForm {
         display_name: params.display_name,
         bio: params.bio,
+        bot: params.bot,
     };
-    Person::update_profile(&form, user.person.id, &context)?;
+    if form.display_name.is_some() || form.bio.is_some() || form.bot.is_some() {
+        Person::update_profile(&form, user.person.id, &context)?;
+    }
+    if params.hide_bot_edits.is_some() || params.email_notifications.is_some() {
+        let form = LocalUserUpdateForm {
+            hide_bot_edits: params.hide_bot_edits,
+            email_notifications: params.email_notifications,
+        };
+        LocalUser::update(&form, user.local_user.id, &context)?;
+    }
+    match new_email {
+        Some(Some(email)) => set_email(&user, email, &context)?,
+        Some(None) => LocalUser::update_email(user.local_user.id, None, &context)?,
+        None => {}
+    }
     Ok(Json(SuccessResponse::default()))
 }
 
+/// Validates an email address for registration or profile update, and checks that it is not
+/// verified by another account.
+fn check_new_email(email: &str, context: &IbisContext) -> BackendResult<String> {
+    email_config(context)?;
+    let email = validate_email(email)?;
+    if LocalUser::read_from_email(&email, context).is_ok() {
+        return Err(BackendError::conflict("Email is already in use"));
+    }
+    Ok(email)
+}
+
+/// Store the unverified email address and send the verification link.
+fn set_email(user: &LocalUserView, email: String, context: &IbisContext) -> BackendResult<()> {
+    LocalUser::update_email(user.local_user.id, Some(email.clone()), context)?;
+    send_verification_email(user, &email, context)
+}
+
+#[debug_handler]
+pub(crate) async fn verify_email(
+    context: Data<IbisContext>,
+    Form(params): Form<VerifyEmailParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    let local_user_id = EmailToken::consume(
+        &params.token,
+        EmailTokenKind::VerifyEmail,
+        VERIFY_EMAIL_EXPIRY,
+        &context,
+    )?;
+    LocalUser::set_email_verified(local_user_id, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// Send a password reset link if there is an account with this verified email. Always returns
+/// success, so that it cant be used to find out which addresses are registered.
+#[debug_handler]
+pub(crate) async fn request_password_reset(
+    context: Data<IbisContext>,
+    Form(params): Form<RequestPasswordResetParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    if !context.config.options.password_login {
+        return Err(BackendError::forbidden("Password login is disabled"));
+    }
+    email_config(&context)?;
+    let email = validate_email(&params.email)?;
+    if let Ok(user) = LocalUser::read_from_email(&email, &context) {
+        send_password_reset_email(&user, &email, &context)?;
+    }
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// Set a new password with the token from the reset email, log out all sessions and revoke all
+/// api tokens.
+#[debug_handler]
+pub(crate) async fn reset_password(
+    context: Data<IbisContext>,
+    Form(params): Form<ResetPasswordParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    validate_not_empty(&params.new_password)?;
+    let local_user_id = EmailToken::consume(
+        &params.token,
+        EmailTokenKind::ResetPassword,
+        RESET_PASSWORD_EXPIRY,
+        &context,
+    )?;
+    LocalUser::update_password(local_user_id, &params.new_password, &context)?;
+    Session::delete_all(local_user_id, None, &context)?;
+    ApiToken::delete_all(local_user_id, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// Change the password of the current user, log out all other sessions and revoke all api
+/// tokens. Accounts created through an OAuth provider can set a password here without knowing
+/// the old one.
+#[debug_handler]
+pub(crate) async fn change_password(
+    context: Data<IbisContext>,
+    user: UserExt,
+    auth: Option<Extension<Auth>>,
+    Form(params): Form<ChangePasswordParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    user.check_not_api_token()?;
+    check_password(&user, &params.old_password)?;
+    validate_not_empty(&params.new_password)?;
+    LocalUser::update_password(user.local_user.id, &params.new_password, &context)?;
+    let current = current_session(auth, &context);
+    Session::delete_all(user.local_user.id, current, &context)?;
+    ApiToken::delete_all(user.local_user.id, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// Delete the account of the current user. The deletion is federated so that other instances
+/// also remove their copy of the user.
+#[debug_handler]
+pub(crate) async fn delete_account(
+    context: Data<IbisContext>,
+    user: UserExt,
+    jar: CookieJar,
+    Form(params): Form<DeleteAccountParams>,
+) -> BackendResult<(CookieJar, Json<SuccessResponse>)> {
+    user.check_not_api_token()?;
+    check_password(&user, &params.password)?;
+    if user.local_user.admin {
+        return Err(BackendError::forbidden("Admin account cannot be deleted"));
+    }
+    Delete::send_user(&user.person.clone().into(), &context).await?;
+    Person::delete(user.person.id, &context)?;
+    let jar = jar.remove(create_cookie(String::new(), &context));
+    Ok((jar, Json(SuccessResponse::default())))
+}
+
+/// Confirm the password before sensitive account changes. Accounts created through an OAuth
+/// provider have no password which could be checked, until the user sets one with
+/// [change_password].
+pub(super) fn check_password(user: &LocalUserView, password: &str) -> BackendResult<()> {
+    let Some(password_encrypted) = &user.local_user.password_encrypted else {
+        return Ok(());
+    };
+    if !verify(password, password_encrypted)? {
+        return Err(BackendError::forbidden("Invalid password"));
+    }
+    Ok(())
+}
+
+#[debug_handler]
+pub(crate) async fn export_user_data(
+    context: Data<IbisContext>,
+    user: UserExt,
+) -> BackendResult<Json<UserExport>> {
+    user.check_not_api_token()?;
+    let person_id = user.person.id;
+    let edits = Edit::view(
+        ViewEditParams::PersonId(person_id),
+        false,
+        &Some(user.local_user_view.clone()),
+        i64::MAX,
+        0,
+        &context,
+    )?;
+    Ok(Json(UserExport {
+        edits,
+        comments: Comment::read_for_creator(person_id, &context)?,
+        followed_instances: Person::read_following(person_id, &context)?,
+        followed_articles: Article::list_followed(user.local_user.id, &context)?,
+        person: user.local_user_view.person,
+    }))
+}
+
 #[debug_handler]
 pub(crate) async fn list_notifications(
+    Query(query): Query<PaginationParams>,
     user: UserExt,
     context: Data<IbisContext>,
 ) -> BackendResult<Json<Vec<ApiNotification>>> {
-    Ok(Json(Notification::list(&user, &context).await?))
+    let (limit, offset) = limit_and_offset(query.page, query.limit)?;
+    Ok(Json(
+        Notification::list(&user, limit, offset, &context).await?,
+    ))
 }
 
 #[debug_handler]
@@ -193,6 +595,121 @@ pub(crate) async fn article_notif_mark_as_read(
     context: Data<IbisContext>,
     Form(params): Form<ArticleNotifMarkAsReadParams>,
 ) -> BackendResult<Json<SuccessResponse>> {
+    user.check_scope(ApiTokenScope::Edit)?;
     Notification::mark_as_read(params.id, &user, &context)?;
     Ok(Json(SuccessResponse::default()))
 }
+
+/// Ban or unban a local or remote user. Banned users cant edit articles or write comments, and
+/// their edits and comments from other instances are rejected.
+#[debug_handler]
+pub(crate) async fn ban_user(
+    user: UserExt,
+    context: Data<IbisContext>,
+    Form(params): Form<BanUserParams>,
+) -> BackendResult<Json<Person>> {
+    check_is_admin(&user)?;
+    if params.person_id == user.person.id {
+        return Err(BackendError::validation("Cannot ban yourself"));
+    }
+    let person = Person::update_banned(params.person_id, params.ban, &context)?;
+    if params.ban && params.remove_content {
+        remove_user_content(&person, &user, &context).await?;
+    }
+    Ok(Json(person))
+}
+
+/// Delete all comments of the user and revert their edits. Edits are undone one by one with a
+/// three-way merge, so that later changes from other users are kept. Edits which conflict with
+/// later changes are left in place. Articles which only contain edits from the user are removed.
+async fn remove_user_content(
+    person: &Person,
+    admin: &LocalUserView,
+    context: &Data<IbisContext>,
+) -> BackendResult<()> {
+    for comment in Comment::read_for_creator(person.id, context)? {
+        let form = DbCommentUpdateForm {
+            deleted: Some(true),
+            updated: Some(Utc::now()),
+            ..Default::default()
+        };
+        let comment = Comment::update(form, comment.id, context)?.comment;
+        if comment.local {
+            DeleteComment::send(&comment.into(), context).await?;
+        }
+    }
+
+    for article_id in Edit::list_article_ids_for_creator(person.id, context)? {
+        let Ok(article) = Article::read_view(article_id, Some(admin), context) else {
+            continue;
+        };
+        let edits = Edit::list_for_article(article_id, None, None, context)?;
+        if edits
+            .iter()
+            .filter(|e| !e.pending)
+            .all(|e| e.creator_id == person.id)
+        {
+            remove_article(&article.article, context).await?;
+            continue;
+        }
+        let new_text = revert_edits_of_user(&edits, &article.article.text, person.id)?;
+        if new_text == article.article.text {
+            continue;
+        }
+        submit_article_update(
+            new_text,
+            format!("Revert edits by banned user {}", person.username),
+            article.latest_version,
+            &article.article,
+            admin.person.id,
+            context,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Undo all accepted edits of the given user in the current article text, starting with the
+/// most recent one.
+fn revert_edits_of_user(
+    edits: &[Edit],
+    current_text: &str,
+    person_id: PersonId,
+) -> BackendResult<String> {
+    let mut text = current_text.to_string();
+    for (i, edit) in edits.iter().enumerate().rev() {
+        if edit.pending || edit.creator_id != person_id {
+            continue;
+        }
+        let before = match i.checked_sub(1) {
+            Some(previous) => generate_article_version_at(edits, previous)?,
+            None => String::new(),
+        };
+        let after = generate_article_version_at(edits, i)?;
+        if let Ok(merged) = merge(&after, &text, &before) {
+            text = merged;
+        }
+    }
+    Ok(text)
+}
+
+#[debug_handler]
+pub(crate) async fn list_banned_users(
+    user: UserExt,
+    context: Data<IbisContext>,
+) -> BackendResult<Json<Vec<Person>>> {
+    check_is_admin(&user)?;
+    Ok(Json(Person::list_banned(&context)?))
+}
+
+/// Accept or reject the registration application of a new user.
+#[debug_handler]
+pub(crate) async fn approve_registration(
+    user: UserExt,
+    context: Data<IbisContext>,
+    Form(params): Form<ApproveRegistrationParams>,
+) -> BackendResult<Json<SuccessResponse>> {
+    check_is_admin(&user)?;
+    RegistrationApplication::handle(params.id, params.approve, &context)?;
+    Ok(Json(SuccessResponse::default()))
+}
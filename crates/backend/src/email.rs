@@ -0,0 +1,232 @@
+use crate::utils::generate_secret;
+use chrono::{TimeDelta, Utc};
+use ibis_database::{
+    common::{
+        notifications::ApiNotification,
+        user::{LocalUser, LocalUserView},
+        utils::http_protocol_str,
+    },
+    config::{EmailTls, IbisConfigEmail},
+    error::{BackendError, BackendResult},
+    impls::{
+        IbisContext,
+        notifications::Notification,
+        user::{EmailToken, EmailTokenKind},
+    },
+};
+use lettre::{
+    AsyncSmtpTransport,
+    AsyncTransport,
+    Message,
+    Tokio1Executor,
+    message::header::ContentType,
+    transport::smtp::authentication::Credentials,
+};
+use log::{error, info};
+use std::time::Duration;
+use tokio::time::interval;
+
+pub(crate) const VERIFY_EMAIL_EXPIRY: TimeDelta = TimeDelta::days(7);
+pub(crate) const RESET_PASSWORD_EXPIRY: TimeDelta = TimeDelta::hours(1);
+/// Timeout for connecting to the SMTP server and for each command
+const SMTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) fn email_config(context: &IbisContext) -> BackendResult<&IbisConfigEmail> {
+    context
+        .config
+        .email
+        .as_ref()
+        .ok_or_else(|| BackendError::validation("Email is not configured on this instance"))
+}
+
+fn base_url(context: &IbisContext) -> String {
+    format!(
+        "{}://{}",
+        http_protocol_str(),
+        context.config.federation.domain
+    )
+}
+
+pub(crate) fn send_verification_email(
+    user: &LocalUserView,
+    email: &str,
+    context: &IbisContext,
+) -> BackendResult<()> {
+    let token = generate_secret();
+    EmailToken::create(
+        user.local_user.id,
+        EmailTokenKind::VerifyEmail,
+        &token,
+        context,
+    )?;
+    let body = format!(
+        "Hello {},\n\nplease confirm your email address by opening this link:\n\n{}/verify_email?token={token}\n",
+        user.person.username,
+        base_url(context),
+    );
+    send_email_background(email, "Verify your email address", body, context);
+    Ok(())
+}
+
+pub(crate) fn send_password_reset_email(
+    user: &LocalUserView,
+    email: &str,
+    context: &IbisContext,
+) -> BackendResult<()> {
+    let token = generate_secret();
+    EmailToken::create(
+        user.local_user.id,
+        EmailTokenKind::ResetPassword,
+        &token,
+        context,
+    )?;
+    let body = format!(
+        "Hello {},\n\nyou can set a new password with the following link, it is valid for one hour:\n\n{}/reset_password?token={token}\n\nIf you didnt request a password reset, you can ignore this email.\n",
+        user.person.username,
+        base_url(context),
+    );
+    send_email_background(email, "Reset your password", body, context);
+    Ok(())
+}
+
+/// Minimum time between two notification emails to the same user.
+const DIGEST_INTERVAL: TimeDelta = TimeDelta::days(1);
+
+/// Send an email with new notifications to users who enabled it, at most once per day. The time
+/// of the last email is stored per user, so it doesnt depend on when the server was started.
+pub(crate) async fn notification_digest_task(context: IbisContext) {
+    let mut interval = interval(Duration::from_secs(60 * 60));
+    loop {
+        interval.tick().await;
+        send_notification_digests(&context)
+            .await
+            .inspect_err(|e| error!("Failed to send notification emails: {e}"))
+            .ok();
+    }
+}
+
+async fn send_notification_digests(context: &IbisContext) -> BackendResult<()> {
+    info!("Sending notification emails");
+    for user in LocalUser::list_email_notifications(DIGEST_INTERVAL, context)? {
+        let Some(email) = &user.local_user.email else {
+            continue;
+        };
+        let now = Utc::now();
+        let since = user
+            .local_user
+            .last_digest_sent
+            .unwrap_or(now - DIGEST_INTERVAL);
+        let notifications: Vec<_> = Notification::list(&user, 50, 0, context)
+            .await?
+            .into_iter()
+            .filter(|n| n.published() > &since)
+            .collect();
+        if notifications.is_empty() {
+            continue;
+        }
+        let body = digest_body(&user, &notifications, &base_url(context));
+        let subject = format!("New notifications on {}", context.config.federation.domain);
+        match send_email(email, &subject, &body, context).await {
+            Ok(()) => LocalUser::set_last_digest_sent(user.local_user.id, now, context)?,
+            Err(e) => error!("Failed to send notification email: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn digest_body(user: &LocalUserView, notifications: &[ApiNotification], base_url: &str) -> String {
+    use ApiNotification::*;
+    let mut body = format!(
+        "Hello {},\n\nthere are new notifications for you:\n\n",
+        user.person.username
+    );
+    for n in notifications {
+        let line = match n {
+            EditConflict(_, a) => format!("Edit conflict in {}", a.title),
+            ArticleApprovalRequired(a) => format!("Article {} needs approval", a.title),
+            Comment(_, _, p, a) => format!("New comment by {} on {}", p.username, a.title),
+            Edit(_, e, p, a) => format!("Edit by {} on {}: {}", p.username, a.title, e.summary),
+            RegistrationApplication(_, p) => format!("New registration from {}", p.username),
+        };
+        body.push_str(&format!("- {line}\n"));
+    }
+    body.push_str(&format!(
+        "\nSee all notifications at {base_url}/notifications\n\nYou can disable these emails in your profile settings.\n"
+    ));
+    body
+}
+
+/// Send the email in a background task, so that a slow or unreachable SMTP server doesnt
+/// delay the request. Errors are only logged.
+fn send_email_background(to: &str, subject: &str, body: String, context: &IbisContext) {
+    let to = to.to_string();
+    let subject = subject.to_string();
+    let context = context.clone();
+    tokio::spawn(async move {
+        send_email(&to, &subject, &body, &context)
+            .await
+            .inspect_err(|e| error!("Failed to send email to {to}: {e}"))
+            .ok();
+    });
+}
+
+/// Send a plain text email with the configured SMTP server.
+async fn send_email(
+    to: &str,
+    subject: &str,
+    body: &str,
+    context: &IbisContext,
+) -> BackendResult<()> {
+    let config = email_config(context)?;
+    let message = build_message(&config.smtp_from_address, to, subject, body)?;
+    smtp_transport(config)?.send(message).await?;
+    Ok(())
+}
+
+fn build_message(from: &str, to: &str, subject: &str, body: &str) -> BackendResult<Message> {
+    Ok(Message::builder()
+        .from(from.parse()?)
+        .to(to.parse()?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(body.to_string())?)
+}
+
+fn smtp_transport(config: &IbisConfigEmail) -> BackendResult<AsyncSmtpTransport<Tokio1Executor>> {
+    let (host, port) = match config.smtp_server.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port.parse()?)),
+        None => (config.smtp_server.as_str(), None),
+    };
+    let mut builder = match config.tls_type {
+        EmailTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        EmailTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+        EmailTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+    };
+    if let Some(port) = port {
+        builder = builder.port(port);
+    }
+    if let (Some(login), Some(password)) = (&config.smtp_login, &config.smtp_password) {
+        builder = builder.credentials(Credentials::new(login.clone(), password.clone()));
+    }
+    Ok(builder.timeout(Some(SMTP_TIMEOUT)).build())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_message() -> BackendResult<()> {
+        let message = build_message(
+            "ibis@example.com",
+            "alice@example.com",
+            "Hello",
+            "first line\n.second line\n",
+        )?;
+        let message = String::from_utf8(message.formatted())?;
+        assert!(message.contains("From: ibis@example.com\r\n"));
+        assert!(message.contains("To: alice@example.com\r\n"));
+        assert!(message.ends_with("\r\n\r\nfirst line\r\n.second line\r\n"));
+        Ok(())
+    }
+}
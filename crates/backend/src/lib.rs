@@ -1,4 +1,5 @@
 use activitypub_federation::config::FederationConfig;
+use email::notification_digest_task;
 use ibis_database::{
     common::instance::Instance,
     config::IbisConfig,
@@ -9,11 +10,14 @@ use ibis_database::{
 use ibis_federate::VerifyUrlData;
 use log::info;
 use server::{setup::setup, start_server};
-use std::{net::SocketAddr, thread};
+use std::{net::SocketAddr, ops::Deref, thread};
 use tokio::sync::oneshot;
 
 pub mod api;
+mod email;
 mod server;
+mod storage;
+mod thumbnail;
 pub mod utils;
 
 pub async fn start(
@@ -24,7 +28,7 @@ pub async fn start(
     let context = IbisContext::init(config, override_hostname.is_some())?;
     let data = FederationConfig::builder()
         .domain(context.config.federation.domain.clone())
-        .url_verifier(Box::new(VerifyUrlData(context.config.clone())))
+        .url_verifier(Box::new(VerifyUrlData(context.clone())))
         .app_data(context)
         .http_fetch_limit(1000)
         .debug(cfg!(debug_assertions))
@@ -40,6 +44,9 @@ pub async fn start(
     thread::spawn(move || {
         scheduled_tasks::start(db_pool);
     });
+    if data.config.email.is_some() {
+        tokio::spawn(notification_digest_task(data.deref().clone()));
+    }
 
     start_server(data, override_hostname, notify_start).await?;
 
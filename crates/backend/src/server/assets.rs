@@ -1,4 +1,3 @@
-use anyhow::anyhow;
 use axum::{
     body::Body,
     extract::{Request, State},
@@ -6,7 +5,7 @@ use axum::{
 };
 use axum_macros::debug_handler;
 use http::{HeaderMap, HeaderName, HeaderValue};
-use ibis_database::error::BackendResult;
+use ibis_database::error::{BackendError, BackendResult};
 use include_dir::include_dir;
 use leptos::prelude::*;
 use mime_guess::mime::APPLICATION_OCTET_STREAM;
@@ -31,7 +30,9 @@ pub async fn file_and_error_handler(
         let mut headers = HeaderMap::new();
         let dir = include_dir!("target/site/");
         let path = request.uri().path().replacen('/', "", 1);
-        let content = dir.get_file(&path).ok_or(anyhow!("not found"))?;
+        let content = dir
+            .get_file(&path)
+            .ok_or(BackendError::not_found("not found"))?;
         headers.insert(
             HeaderName::from_static("content-type"),
             HeaderValue::from_str(
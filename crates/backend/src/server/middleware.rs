@@ -1,9 +1,24 @@
 use crate::api::user::validate;
-use axum::{body::Body, extract::State, http::Request, middleware::Next, response::Response};
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use axum_macros::debug_middleware;
-use http::{HeaderValue, header::COOKIE};
+use http::{
+    HeaderValue,
+    Method,
+    header::{AUTHORIZATION, COOKIE},
+};
 use ibis_database::{
-    common::{AUTH_COOKIE, Auth},
+    common::{
+        AUTH_COOKIE,
+        Auth,
+        user::{ApiToken, ApiTokenScope},
+    },
+    error::{BackendError, ErrorType},
     impls::IbisContext,
 };
 use std::{collections::HashSet, sync::Arc};
@@ -13,12 +28,36 @@ pub(super) const FEDERATION_ROUTES_PREFIX: &str = "/federation_routes";
 /// Checks all headers and cookies (including duplicates) for first valid auth token.
 /// We need to extract cookies manually because CookieJar ignores duplicates.
 /// If user is authenticated sets extensions `Auth` and `LocalUserView`.
+///
+/// Alternatively an api token can be passed with `Authorization: Bearer` header, in this case
+/// the extensions `LocalUserView` and `ApiTokenScope` are set. Requests with an invalid api token
+/// are rejected, and read only tokens can only be used for GET requests.
 #[debug_middleware]
 pub(super) async fn auth_middleware(
     State(context): State<Arc<IbisContext>>,
     mut request: Request<Body>,
     next: Next,
 ) -> Response {
+    let headers = request.headers();
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string());
+    if let Some(bearer) = bearer {
+        let Ok((local_user, scope)) = ApiToken::authenticate(&bearer, &context) else {
+            return BackendError::new(ErrorType::NotLoggedIn, "Invalid api token").into_response();
+        };
+        // Handlers check the scope themselves, this is only a fallback in case a check is missing
+        if scope == ApiTokenScope::Read && request.method() != Method::GET {
+            return BackendError::forbidden("Read only api token cannot modify data")
+                .into_response();
+        }
+        request.extensions_mut().insert(local_user);
+        request.extensions_mut().insert(scope);
+        return next.run(request).await;
+    }
+
     let headers = request.headers();
     let cookies = headers
         .get(COOKIE)
@@ -1,4 +1,4 @@
-use crate::api::api_routes;
+use crate::api::{api_routes, upload::get_media};
 use activitypub_federation::config::{FederationConfig, FederationMiddleware};
 use assets::file_and_error_handler;
 use axum::{
@@ -12,13 +12,18 @@ use axum::{
     response::{IntoResponse, Response},
     routing::get,
 };
-use ibis_database::{common::Auth, error::BackendResult, impls::IbisContext};
-use ibis_federate::{nodeinfo, routes::federation_routes};
+use ibis_database::{
+    common::{Auth, ClientIp},
+    error::BackendResult,
+    impls::IbisContext,
+};
+use ibis_federate::{nodeinfo, routes::federation_routes, webfinger};
 use ibis_frontend::app::{App, shell};
 use leptos::prelude::*;
 use leptos_axum::{LeptosRoutes, generate_route_list};
 use log::info;
 use middleware::{FEDERATION_ROUTES_PREFIX, auth_middleware, federation_routes_middleware};
+use rate_limit::{RateLimiter, rate_limit_middleware};
 use std::{net::SocketAddr, ops::Deref, sync::Arc};
 use tokio::{net::TcpListener, sync::oneshot};
 use tower_http::{compression::CompressionLayer, cors::CorsLayer};
@@ -26,6 +31,7 @@ use tower_layer::Layer;
 
 mod assets;
 mod middleware;
+mod rate_limit;
 pub(super) mod setup;
 
 pub(super) async fn start_server(
@@ -41,16 +47,20 @@ pub(super) async fn start_server(
     let routes = generate_route_list(App);
 
     let arc_data = Arc::new(context.deref().clone());
+    let rate_limiter = Arc::new(RateLimiter::new(context.config.rate_limit.clone()));
     let app = Router::new()
         .leptos_routes_with_handler(routes, get(leptos_routes_handler))
         .fallback(file_and_error_handler)
         .with_state(leptos_options)
         .nest(FEDERATION_ROUTES_PREFIX, federation_routes())
         .nest("/api/v1", api_routes())
+        .route("/media/:filename", get(get_media))
         .nest("", nodeinfo::config())
+        .nest("", webfinger::config())
         .layer(FederationMiddleware::new(context))
         .layer(CorsLayer::permissive())
         .layer(CompressionLayer::new())
+        .route_layer(from_fn_with_state(rate_limiter, rate_limit_middleware))
         .route_layer(from_fn_with_state(arc_data, auth_middleware));
 
     // Rewrite federation routes
@@ -63,13 +73,18 @@ pub(super) async fn start_server(
     if let Some(notify_start) = notify_start {
         notify_start.send(()).expect("send oneshot");
     }
-    axum::serve(listener, app_with_middleware.into_make_service()).await?;
+    axum::serve(
+        listener,
+        app_with_middleware.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
 
-/// Make auth token available in hydrate mode
+/// Make auth token and client IP available for api requests during server side rendering
 async fn leptos_routes_handler(
     auth: Option<Extension<Auth>>,
+    client_ip: Option<Extension<ClientIp>>,
     State(leptos_options): State<LeptosOptions>,
     request: Request<Body>,
 ) -> Response {
@@ -80,6 +95,9 @@ async fn leptos_routes_handler(
             if let Some(auth) = &auth {
                 provide_context(auth.0.clone());
             }
+            if let Some(client_ip) = &client_ip {
+                provide_context(client_ip.0);
+            }
         },
         move || shell(leptos_options.clone()),
     );
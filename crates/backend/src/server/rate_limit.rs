@@ -0,0 +1,317 @@
+use super::middleware::FEDERATION_ROUTES_PREFIX;
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_macros::debug_middleware;
+use http::{HeaderValue, Method, header::RETRY_AFTER};
+use ibis_database::{
+    common::{ClientIp, newtypes::LocalUserId, user::LocalUserView},
+    config::IbisConfigRateLimit,
+    error::{BackendError, ErrorType},
+};
+use moka::sync::Cache;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Each type of request has its own limits, so that eg many edits don't prevent login.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum RateLimitType {
+    Login,
+    Register,
+    Edit,
+    Search,
+    Inbox,
+}
+
+impl RateLimitType {
+    fn from_request(method: &Method, path: &str) -> Option<Self> {
+        use RateLimitType::*;
+        let path = path.strip_prefix(FEDERATION_ROUTES_PREFIX).unwrap_or(path);
+        if method == Method::GET {
+            return match path {
+                "/api/v1/search" => Some(Search),
+                // limited to prevent evicting the pending captchas of other users
+                "/api/v1/account/captcha" => Some(Register),
+                _ => None,
+            };
+        }
+        match path {
+            // may fetch remote articles like search
+            "/api/v1/article/templates" => Some(Search),
+            "/api/v1/account/login" | "/api/v1/account/oauth/login" => Some(Login),
+            // also limits how many emails can be sent
+            "/api/v1/account/register" | "/api/v1/account/request_password_reset" => Some(Register),
+            "/api/v1/article"
+            | "/api/v1/article/revert"
+            | "/api/v1/article/fork"
+            | "/api/v1/article/move"
+            | "/api/v1/comment"
+            | "/api/v1/upload" => Some(Edit),
+            "/inbox" => Some(Inbox),
+            _ => None,
+        }
+    }
+}
+
+/// Logged in users are limited individually, otherwise the limit applies per IP.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum RateLimitKey {
+    User(LocalUserId),
+    Ip(IpAddr),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    tokens: f64,
+    last_checked: Instant,
+}
+
+/// Token bucket for each type and user or IP. Buckets which haven't been used for longer than
+/// the longest interval are full again, so they are evicted from the cache.
+pub(super) struct RateLimiter {
+    config: IbisConfigRateLimit,
+    buckets: Cache<(RateLimitType, RateLimitKey), Bucket>,
+}
+
+impl RateLimiter {
+    pub(super) fn new(config: IbisConfigRateLimit) -> Self {
+        let max_interval = [
+            config.login_interval,
+            config.register_interval,
+            config.edit_interval,
+            config.search_interval,
+            config.inbox_interval,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default();
+        let buckets = Cache::builder()
+            .time_to_idle(Duration::from_secs(max_interval.into()))
+            .max_capacity(100_000)
+            .build();
+        RateLimiter { config, buckets }
+    }
+
+    fn limit(&self, type_: RateLimitType) -> (u32, u32) {
+        use RateLimitType::*;
+        let c = &self.config;
+        match type_ {
+            Login => (c.login, c.login_interval),
+            Register => (c.register, c.register_interval),
+            Edit => (c.edit, c.edit_interval),
+            Search => (c.search, c.search_interval),
+            Inbox => (c.inbox, c.inbox_interval),
+        }
+    }
+
+    /// Consumes one token from the bucket. If it is empty returns the time after which the
+    /// next request is allowed.
+    fn check(&self, type_: RateLimitType, key: RateLimitKey, now: Instant) -> Result<(), Duration> {
+        let (max_requests, interval) = self.limit(type_);
+        let capacity = f64::from(max_requests);
+        let refill_per_sec = capacity / f64::from(interval.max(1));
+        let mut res = Ok(());
+        self.buckets.entry((type_, key)).and_upsert_with(|bucket| {
+            let mut bucket = bucket.map(|b| b.into_value()).unwrap_or(Bucket {
+                tokens: capacity,
+                last_checked: now,
+            });
+            let elapsed = now.saturating_duration_since(bucket.last_checked);
+            bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * refill_per_sec).min(capacity);
+            bucket.last_checked = now;
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+            } else if refill_per_sec > 0.0 {
+                let wait = (1.0 - bucket.tokens) / refill_per_sec;
+                res = Err(Duration::from_secs_f64(wait));
+            } else {
+                res = Err(Duration::from_secs(interval.into()));
+            }
+            bucket
+        });
+        res
+    }
+}
+
+/// Returns the IP of the client. `X-Forwarded-For` is only used if the request comes from a
+/// trusted proxy, or from localhost which includes server side rendering. Proxies append the
+/// IP which they received the request from, so the header is read from the end, skipping
+/// other trusted proxies.
+fn client_ip(request: &Request<Body>, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let is_trusted = |ip: &IpAddr| ip.is_loopback() || trusted_proxies.contains(ip);
+    let connect_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|c| c.0.ip())?;
+    if !is_trusted(&connect_ip) {
+        return Some(connect_ip);
+    }
+    let forwarded: Vec<IpAddr> = request
+        .headers()
+        .get_all("X-Forwarded-For")
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split(','))
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+    let client_ip = forwarded.into_iter().rev().find(|ip| !is_trusted(ip));
+    Some(client_ip.unwrap_or(connect_ip))
+}
+
+/// Returns 429 with `Retry-After` header if the rate limit for the request type is exceeded.
+/// Needs to run after `auth_middleware`. Also makes the client IP available to server side
+/// rendering.
+#[debug_middleware]
+pub(super) async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let ip = client_ip(&request, &limiter.config.trusted_proxies);
+    if let Some(ip) = ip {
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+    if !limiter.config.enabled {
+        return next.run(request).await;
+    }
+    let Some(type_) = RateLimitType::from_request(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let user = request.extensions().get::<LocalUserView>();
+    let key = match (user, ip) {
+        (Some(user), _) => RateLimitKey::User(user.local_user.id),
+        (None, Some(ip)) => RateLimitKey::Ip(ip),
+        (None, None) => return next.run(request).await,
+    };
+
+    match limiter.check(type_, key, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let mut res = BackendError::new(
+                ErrorType::RateLimited,
+                "Rate limit exceeded, try again later",
+            )
+            .into_response();
+            let secs = retry_after.as_secs_f64().ceil() as u64;
+            res.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(secs.max(1)));
+            res
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit() {
+        let config = IbisConfigRateLimit {
+            login: 2,
+            login_interval: 10,
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config);
+        let ip = RateLimitKey::Ip(IpAddr::from([127, 0, 0, 1]));
+        let now = Instant::now();
+
+        assert!(limiter.check(RateLimitType::Login, ip, now).is_ok());
+        assert!(limiter.check(RateLimitType::Login, ip, now).is_ok());
+        assert_eq!(
+            Err(Duration::from_secs(5)),
+            limiter.check(RateLimitType::Login, ip, now)
+        );
+
+        // other types, users and IPs have separate limits
+        assert!(limiter.check(RateLimitType::Register, ip, now).is_ok());
+        let other_ip = RateLimitKey::Ip(IpAddr::from([127, 0, 0, 2]));
+        assert!(limiter.check(RateLimitType::Login, other_ip, now).is_ok());
+        let user = RateLimitKey::User(LocalUserId(1));
+        assert!(limiter.check(RateLimitType::Login, user, now).is_ok());
+
+        // one token is refilled after 5 seconds
+        let later = now + Duration::from_secs(5);
+        assert!(limiter.check(RateLimitType::Login, ip, later).is_ok());
+        assert!(limiter.check(RateLimitType::Login, ip, later).is_err());
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let request = |connect_ip: [u8; 4], forwarded: Option<&str>| {
+            let mut request = Request::builder();
+            if let Some(forwarded) = forwarded {
+                request = request.header("X-Forwarded-For", forwarded);
+            }
+            let mut request = request.body(Body::empty()).unwrap();
+            let addr = SocketAddr::from((connect_ip, 8000));
+            request.extensions_mut().insert(ConnectInfo(addr));
+            request
+        };
+        let proxy = IpAddr::from([10, 0, 0, 1]);
+        let client = IpAddr::from([1, 2, 3, 4]);
+
+        // header from untrusted clients is ignored
+        let req = request([5, 6, 7, 8], Some("1.2.3.4"));
+        assert_eq!(Some(IpAddr::from([5, 6, 7, 8])), client_ip(&req, &[proxy]));
+
+        // trusted proxy appends the client IP, values set by the client itself are skipped
+        let req = request([10, 0, 0, 1], Some("9.9.9.9, 1.2.3.4"));
+        assert_eq!(Some(client), client_ip(&req, &[proxy]));
+        let req = request([10, 0, 0, 1], None);
+        assert_eq!(Some(proxy), client_ip(&req, &[proxy]));
+
+        // localhost is always trusted, for server side rendering behind a trusted proxy
+        let req = request([127, 0, 0, 1], Some("1.2.3.4, 10.0.0.1"));
+        assert_eq!(Some(client), client_ip(&req, &[proxy]));
+    }
+
+    #[test]
+    fn test_rate_limit_type() {
+        use RateLimitType::*;
+        let from_request = RateLimitType::from_request;
+        assert_eq!(
+            Some(Login),
+            from_request(&Method::POST, "/api/v1/account/login")
+        );
+        assert_eq!(
+            Some(Register),
+            from_request(&Method::POST, "/api/v1/account/request_password_reset")
+        );
+        assert_eq!(
+            Some(Register),
+            from_request(&Method::GET, "/api/v1/account/captcha")
+        );
+        assert_eq!(Some(Edit), from_request(&Method::PATCH, "/api/v1/article"));
+        assert_eq!(Some(Edit), from_request(&Method::POST, "/api/v1/comment"));
+        assert_eq!(Some(Edit), from_request(&Method::POST, "/api/v1/upload"));
+        assert_eq!(
+            Some(Edit),
+            from_request(&Method::POST, "/api/v1/article/revert")
+        );
+        assert_eq!(
+            Some(Edit),
+            from_request(&Method::POST, "/api/v1/article/fork")
+        );
+        assert_eq!(
+            Some(Edit),
+            from_request(&Method::POST, "/api/v1/article/move")
+        );
+        assert_eq!(None, from_request(&Method::GET, "/api/v1/article"));
+        assert_eq!(Some(Search), from_request(&Method::GET, "/api/v1/search"));
+        assert_eq!(
+            Some(Search),
+            from_request(&Method::POST, "/api/v1/article/templates")
+        );
+        assert_eq!(
+            Some(Inbox),
+            from_request(&Method::POST, "/federation_routes/inbox")
+        );
+    }
+}
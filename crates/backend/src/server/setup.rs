@@ -46,7 +46,7 @@ pub async fn setup(context: &Data<IbisContext>) -> Result<(), BackendError> {
 
     let person = Person::create_local(
         context.config.setup.admin_username.clone(),
-        context.config.setup.admin_password.clone(),
+        Some(context.config.setup.admin_password.clone()),
         true,
         context,
     )?;
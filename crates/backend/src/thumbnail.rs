@@ -0,0 +1,78 @@
+use image::{ImageFormat, ImageReader, Limits, imageops::FilterType};
+use std::io::Cursor;
+
+/// Larger images are not decoded, to limit memory usage
+const MAX_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// Returns a PNG which fits into a square of the given size, or `None` if the image cant be
+/// decoded or is already small enough. This is slow for large images, so it needs to run with
+/// `spawn_blocking`.
+pub(crate) fn generate_thumbnail(data: &[u8], content_type: &str, size: u32) -> Option<Vec<u8>> {
+    if !content_type.starts_with("image/") {
+        return None;
+    }
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?;
+    let mut limits = Limits::default();
+    limits.max_alloc = Some(MAX_ALLOC);
+    reader.limits(limits);
+    let image = reader.decode().ok()?;
+    let size = size.max(1);
+    if image.width() <= size && image.height() <= size {
+        return None;
+    }
+    let mut png = vec![];
+    image
+        .resize(size, size, FilterType::Triangle)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+    fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut data = vec![];
+        image.write_to(&mut Cursor::new(&mut data), format).unwrap();
+        data
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn test_thumbnail() {
+        // left half red, right half transparent blue
+        let image = RgbaImage::from_fn(600, 300, |x, _| {
+            if x < 300 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 0])
+            }
+        });
+        let png = encode(image.into(), ImageFormat::Png);
+
+        let thumbnail = generate_thumbnail(&png, "image/png", 100).unwrap();
+        let decoded = image::load_from_memory_with_format(&thumbnail, ImageFormat::Png).unwrap();
+        assert_eq!((100, 50), decoded.dimensions());
+        assert_eq!(Rgba([255, 0, 0, 255]), decoded.get_pixel(0, 0));
+        assert_eq!(Rgba([0, 0, 255, 0]), decoded.get_pixel(99, 0));
+
+        // already small enough
+        assert!(generate_thumbnail(&png, "image/png", 600).is_none());
+        assert!(generate_thumbnail(&png, "application/pdf", 100).is_none());
+        assert!(generate_thumbnail(b"invalid", "image/png", 100).is_none());
+    }
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn test_thumbnail_jpeg() {
+        let image = DynamicImage::new_rgb8(300, 600);
+        let jpeg = encode(image, ImageFormat::Jpeg);
+        let thumbnail = generate_thumbnail(&jpeg, "image/jpeg", 100).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((50, 100), decoded.dimensions());
+    }
+}
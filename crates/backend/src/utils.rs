@@ -1,31 +1,90 @@
 use anyhow::anyhow;
 use diffy::{Patch, apply};
 use ibis_database::{
+    DbUrl,
     common::article::{Edit, EditVersion},
     error::BackendResult,
 };
+use moka::sync::Cache;
+use rand::{Rng, distributions::Alphanumeric, thread_rng};
+use reqwest::Client;
+use std::{sync::LazyLock, time::Duration};
+
+/// While generating a version, every n-th intermediate version is also stored in the cache. This
+/// way later versions of the same article don't need to replay all edits from the beginning.
+const SNAPSHOT_INTERVAL: usize = 100;
+
+/// Generated article versions, keyed by the ap_id of the edit which produced them. Version hashes
+/// can't be used here because identical diffs result in the same hash, and database ids are not
+/// unique if multiple instances run in the same process, like in tests.
+static VERSION_CACHE: LazyLock<Cache<DbUrl, String>> = LazyLock::new(|| {
+    Cache::builder()
+        .weigher(|_, text: &String| text.len().try_into().unwrap_or(u32::MAX))
+        .max_capacity(100 * 1024 * 1024)
+        .build()
+});
+
+/// Client for requests which the backend makes itself, outside of federation. Each request sets
+/// a timeout which fits its purpose.
+pub(crate) static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .expect("init http client")
+});
+
+/// Random alphanumeric string, used for tokens which are given to users.
+pub(crate) fn generate_secret() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect()
+}
 
 /// Starting from empty string, apply edits until the specified version is reached. If no version is
 /// given, apply all edits up to latest version.
 ///
-/// TODO: testing
-/// TODO: should cache all these generated versions
+/// Generation starts from the closest previous version which is cached, so usually only a few
+/// edits need to be applied.
+///
+/// Version hashes are not unique, as an edit which restores an earlier text has the same diff as
+/// a previous one. In that case the latest edit with the given hash is used, which is the one that
+/// new edits are based on.
 pub(super) fn generate_article_version(
-    edits: &Vec<Edit>,
+    edits: &[Edit],
     version: &EditVersion,
 ) -> BackendResult<String> {
-    let mut generated = String::new();
     if version == &EditVersion::default() {
-        return Ok(generated);
+        return Ok(String::new());
+    }
+    let Some(target) = edits.iter().rposition(|e| &e.hash == version) else {
+        return Err(anyhow!("failed to generate article version").into());
+    };
+    generate_article_version_at(edits, target)
+}
+
+/// Article text after applying all edits up to and including the one at index `target`.
+pub(super) fn generate_article_version_at(edits: &[Edit], target: usize) -> BackendResult<String> {
+    // find the closest cached version to start from
+    let mut start = 0;
+    let mut generated = String::new();
+    for (i, e) in edits.iter().enumerate().take(target + 1).rev() {
+        if let Some(cached) = VERSION_CACHE.get(&e.ap_id) {
+            start = i + 1;
+            generated = cached;
+            break;
+        }
     }
-    for e in edits {
+
+    for (i, e) in edits.iter().enumerate().take(target + 1).skip(start) {
         let patch = Patch::from_str(&e.diff)?;
         generated = apply(&generated, &patch)?;
-        if &e.hash == version {
-            return Ok(generated);
+        if i % SNAPSHOT_INTERVAL == 0 || i == target {
+            VERSION_CACHE.insert(e.ap_id.clone(), generated.clone());
         }
     }
-    Err(anyhow!("failed to generate article version").into())
+    Ok(generated)
 }
 
 #[cfg(test)]
@@ -37,13 +96,13 @@ mod test {
     use url::Url;
 
     fn create_edits() -> BackendResult<Vec<Edit>> {
-        let generate_edit = |a, b| -> BackendResult<Edit> {
+        let generate_edit = |id, a, b| -> BackendResult<Edit> {
             let diff = create_patch(a, b).to_string();
             Ok(Edit {
-                id: EditId(0),
+                id: EditId(id),
                 creator_id: PersonId(0),
                 hash: EditVersion::new(&diff),
-                ap_id: Url::parse("http://example.com")?.into(),
+                ap_id: Url::parse(&format!("http://example.com/edit/{id}"))?.into(),
                 diff,
                 summary: String::new(),
                 article_id: ArticleId(0),
@@ -53,9 +112,9 @@ mod test {
             })
         };
         Ok([
-            generate_edit("", "test\n")?,
-            generate_edit("test\n", "sda\n")?,
-            generate_edit("sda\n", "123\n")?,
+            generate_edit(1, "", "test\n")?,
+            generate_edit(2, "test\n", "sda\n")?,
+            generate_edit(3, "sda\n", "123\n")?,
         ]
         .to_vec())
     }
@@ -76,6 +135,32 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_generate_many_versions() -> BackendResult<()> {
+        let mut edits = vec![];
+        let mut previous = String::new();
+        for i in 0..250 {
+            let text = format!("{i}\n");
+            let diff = create_patch(&previous, &text).to_string();
+            edits.push(Edit {
+                id: EditId(1000 + i),
+                ap_id: Url::parse(&format!("http://example.com/edit/{}", 1000 + i))?.into(),
+                hash: EditVersion::new(&diff),
+                diff,
+                article_id: ArticleId(1),
+                ..create_edits()?[0].clone()
+            });
+            previous = text;
+        }
+        // first call replays edits from the beginning, the following ones start from cached
+        // versions
+        assert_eq!("230\n", generate_article_version(&edits, &edits[230].hash)?);
+        assert_eq!("249\n", generate_article_version(&edits, &edits[249].hash)?);
+        assert_eq!("150\n", generate_article_version(&edits, &edits[150].hash)?);
+        assert_eq!("230\n", generate_article_version(&edits, &edits[230].hash)?);
+        Ok(())
+    }
+
     #[test]
     fn test_generate_first_version() -> BackendResult<()> {
         let edits = create_edits()?;
@@ -1,30 +1,86 @@
 #![expect(clippy::unwrap_used)]
 
 use anyhow::Result;
-use ibis_api_client::{ApiClient, user::RegisterUserParams};
+use axum::{
+    Form,
+    Json,
+    Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+};
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use ibis_api_client::{
+    ApiClient,
+    user::{OAuthLoginParams, RegisterUserParams},
+};
 use ibis_backend::start;
 use ibis_database::{
     common::instance::Options,
-    config::{IbisConfig, IbisConfigDatabase, IbisConfigFederation},
+    config::{
+        EmailTls,
+        IbisConfig,
+        IbisConfigDatabase,
+        IbisConfigEmail,
+        IbisConfigFederation,
+        IbisConfigOAuthProvider,
+        IbisConfigRateLimit,
+        IbisConfigUpload,
+    },
 };
+use image::{GrayImage, ImageFormat, Luma};
 use log::LevelFilter;
+use reqwest::{header::LOCATION, redirect::Policy};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::{
+    collections::HashMap,
     env::current_dir,
     fs::{create_dir_all, remove_dir_all},
+    io::Cursor,
     ops::Deref,
     process::{Command, Stdio},
     sync::{
+        Arc,
+        Mutex,
         Once,
         atomic::{AtomicI32, Ordering},
     },
     thread::spawn,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    join,
+    net::TcpListener,
+    sync::oneshot,
+    task::JoinHandle,
+    time::sleep,
 };
-use tokio::{join, sync::oneshot, task::JoinHandle};
 
 pub struct TestData(pub IbisInstance, pub IbisInstance, pub IbisInstance);
 
 impl TestData {
     pub async fn start(article_approval: bool) -> Self {
+        Self::start_with_options(Options {
+            registration_open: true,
+            article_approval,
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn start_with_options(options: Options) -> Self {
+        Self::start_with_config(IbisConfig {
+            options,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Database, federation and rate limit settings are overwritten for each instance.
+    pub async fn start_with_config(config: IbisConfig) -> Self {
         static INIT: Once = Once::new();
         INIT.call_once(|| {
             env_logger::builder()
@@ -57,9 +113,9 @@ impl TestData {
         }
 
         let (alpha, beta, gamma) = join!(
-            IbisInstance::start(alpha_db_path, port_alpha, "alpha", article_approval),
-            IbisInstance::start(beta_db_path, port_beta, "beta", article_approval),
-            IbisInstance::start(gamma_db_path, port_gamma, "gamma", article_approval)
+            IbisInstance::start(alpha_db_path, port_alpha, "alpha", config.clone()),
+            IbisInstance::start(beta_db_path, port_beta, "beta", config.clone()),
+            IbisInstance::start(gamma_db_path, port_gamma, "gamma", config)
         );
 
         Self(alpha, beta, gamma)
@@ -106,7 +162,7 @@ impl IbisInstance {
         })
     }
 
-    async fn start(db_path: String, port: i32, username: &str, article_approval: bool) -> Self {
+    async fn start(db_path: String, port: i32, username: &str, config: IbisConfig) -> Self {
         let connection_url = format!("postgresql://ibis:password@/ibis?host={db_path}");
 
         let hostname = format!("localhost:{port}");
@@ -119,12 +175,17 @@ impl IbisInstance {
                 domain: hostname.clone(),
                 ..Default::default()
             },
-            options: Options {
-                registration_open: true,
-                article_approval,
+            rate_limit: IbisConfigRateLimit {
+                enabled: false,
+                ..Default::default()
+            },
+            upload: IbisConfigUpload {
+                local_path: format!("{db_path}-uploads"),
+                ..config.upload
             },
-            ..Default::default()
+            ..config
         };
+        let password_login = config.options.password_login;
         let api_client = ApiClient::new(Some(hostname.clone()));
         let (tx, rx) = oneshot::channel::<()>();
         let db_handle = tokio::task::spawn(async move {
@@ -138,8 +199,12 @@ impl IbisInstance {
         let params = RegisterUserParams {
             username: username.to_string(),
             password: "hunter2".to_string(),
+            answer: Some("I want to test".to_string()),
+            ..Default::default()
         };
-        api_client.register(params).await.unwrap();
+        if password_login {
+            api_client.register(params).await.unwrap();
+        }
         Self {
             api_client,
             db_path,
@@ -173,4 +238,242 @@ impl Deref for IbisInstance {
     }
 }
 
+/// Fake SMTP server which accepts all emails and keeps them in memory.
+#[derive(Clone)]
+pub struct SmtpStub {
+    pub config: IbisConfigEmail,
+    emails: Arc<Mutex<Vec<String>>>,
+}
+
+impl SmtpStub {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = IbisConfigEmail {
+            smtp_server: listener.local_addr().unwrap().to_string(),
+            smtp_from_address: "ibis@localhost".to_string(),
+            tls_type: EmailTls::None,
+            ..Default::default()
+        };
+        let emails = Arc::new(Mutex::new(vec![]));
+        let emails_ = emails.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let emails = emails_.clone();
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    stream.write_all(b"220 localhost\r\n").await.unwrap();
+                    let mut line = String::new();
+                    let mut data = None::<String>;
+                    while stream.read_line(&mut line).await.unwrap() > 0 {
+                        let response: &[u8] = match &mut data {
+                            Some(d) if line == ".\r\n" => {
+                                emails.lock().unwrap().push(std::mem::take(d));
+                                data = None;
+                                b"250 OK\r\n"
+                            }
+                            Some(d) => {
+                                d.push_str(&line);
+                                line.clear();
+                                continue;
+                            }
+                            None if line.starts_with("DATA") => {
+                                data = Some(String::new());
+                                b"354 Go ahead\r\n"
+                            }
+                            None if line.starts_with("QUIT") => b"221 Bye\r\n",
+                            None => b"250 OK\r\n",
+                        };
+                        stream.write_all(response).await.unwrap();
+                        line.clear();
+                    }
+                });
+            }
+        });
+        Self { config, emails }
+    }
+
+    /// Returns the emails received so far and removes them. Emails are sent in the background,
+    /// so this waits until `count` emails arrived or a timeout is reached.
+    pub async fn take_emails(&self, count: usize) -> Vec<String> {
+        for _ in 0..50 {
+            sleep(Duration::from_millis(100)).await;
+            if self.emails.lock().unwrap().len() >= count {
+                break;
+            }
+        }
+        std::mem::take(&mut self.emails.lock().unwrap())
+    }
+}
+
+/// Fake OpenID Connect provider, which immediately logs in as `user` without asking.
+#[derive(Clone)]
+pub struct OidcStub {
+    pub provider: IbisConfigOAuthProvider,
+    pub user: Arc<Mutex<OidcStubUser>>,
+    /// Authorization codes with the user and PKCE challenge, and later access tokens
+    codes: Arc<Mutex<HashMap<String, (OidcStubUser, String)>>>,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct OidcStubUser {
+    pub sub: String,
+    pub preferred_username: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: bool,
+}
+
+#[derive(Serialize)]
+struct OidcStubMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Serialize)]
+struct OidcStubToken {
+    access_token: String,
+    token_type: &'static str,
+}
+
+impl OidcStub {
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let stub = Self {
+            provider: IbisConfigOAuthProvider {
+                id: "stub".to_string(),
+                display_name: "Stub".to_string(),
+                issuer,
+                client_id: "ibis".to_string(),
+                client_secret: "secret".to_string(),
+                ..Default::default()
+            },
+            user: Default::default(),
+            codes: Default::default(),
+        };
+        let router = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(Self::handle_metadata),
+            )
+            .route("/authorize", get(Self::handle_authorize))
+            .route("/token", post(Self::handle_token))
+            .route("/userinfo", get(Self::handle_userinfo))
+            .with_state(stub.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        stub
+    }
+
+    pub fn set_user(&self, sub: &str, username: &str, email: Option<&str>) {
+        *self.user.lock().unwrap() = OidcStubUser {
+            sub: sub.to_string(),
+            preferred_username: Some(username.to_string()),
+            email: email.map(ToString::to_string),
+            email_verified: true,
+        };
+    }
+
+    /// Go through the provider login like a browser, and return the parameters which the
+    /// provider passes to the callback page.
+    pub async fn authorize(client: &ApiClient, provider: &str) -> OAuthLoginParams {
+        let url = client
+            .oauth_authorize(provider.to_string())
+            .await
+            .unwrap()
+            .url;
+        let http = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .build()
+            .unwrap();
+        let res = http.get(url).send().await.unwrap();
+        let location = res.headers()[LOCATION].to_str().unwrap();
+        let location = url::Url::parse(location).unwrap();
+        assert_eq!("/oauth/callback", location.path());
+        let query: HashMap<_, _> = location.query_pairs().into_owned().collect();
+        OAuthLoginParams {
+            code: query["code"].clone(),
+            state: query["state"].clone(),
+            totp_code: None,
+        }
+    }
+
+    async fn handle_metadata(State(stub): State<Self>) -> Json<OidcStubMetadata> {
+        let issuer = stub.provider.issuer;
+        Json(OidcStubMetadata {
+            authorization_endpoint: format!("{issuer}/authorize"),
+            token_endpoint: format!("{issuer}/token"),
+            userinfo_endpoint: format!("{issuer}/userinfo"),
+            issuer,
+        })
+    }
+
+    async fn handle_authorize(
+        State(stub): State<Self>,
+        Query(params): Query<HashMap<String, String>>,
+    ) -> Response {
+        if params["client_id"] != stub.provider.client_id
+            || params["code_challenge_method"] != "S256"
+        {
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+        let code = format!("code{}", rand::random::<u64>());
+        let user = stub.user.lock().unwrap().clone();
+        stub.codes
+            .lock()
+            .unwrap()
+            .insert(code.clone(), (user, params["code_challenge"].clone()));
+        let redirect = format!(
+            "{}?code={code}&state={}",
+            params["redirect_uri"], params["state"]
+        );
+        Redirect::to(&redirect).into_response()
+    }
+
+    async fn handle_token(
+        State(stub): State<Self>,
+        Form(params): Form<HashMap<String, String>>,
+    ) -> Response {
+        let mut codes = stub.codes.lock().unwrap();
+        let Some((user, challenge)) = codes.remove(&params["code"]) else {
+            return StatusCode::BAD_REQUEST.into_response();
+        };
+        let verifier_hash =
+            BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(params["code_verifier"].as_bytes()));
+        if params["client_secret"] != stub.provider.client_secret || verifier_hash != challenge {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+        let access_token = format!("token-{}", params["code"]);
+        codes.insert(access_token.clone(), (user, String::new()));
+        Json(OidcStubToken {
+            access_token,
+            token_type: "Bearer",
+        })
+        .into_response()
+    }
+
+    async fn handle_userinfo(State(stub): State<Self>, headers: HeaderMap) -> Response {
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        match stub.codes.lock().unwrap().get(token) {
+            Some((user, _)) => Json(user.clone()).into_response(),
+            None => StatusCode::UNAUTHORIZED.into_response(),
+        }
+    }
+}
+
 pub const TEST_ARTICLE_DEFAULT_TEXT: &str = "some example text\n";
+
+/// Grayscale PNG image with the given size.
+pub fn test_png(width: u32, height: u32) -> Vec<u8> {
+    let image = GrayImage::from_fn(width, height, |_, y| Luma([y as u8]));
+    let mut png = vec![];
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+    png
+}
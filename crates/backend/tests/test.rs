@@ -2,26 +2,55 @@
 
 mod common;
 
-use crate::common::{TEST_ARTICLE_DEFAULT_TEXT, TestData};
+use crate::common::{OidcStub, SmtpStub, TEST_ARTICLE_DEFAULT_TEXT, TestData, test_png};
 use anyhow::Result;
+use chrono::Utc;
 use ibis_api_client::{
+    ApiClient,
     article::{
         CreateArticleParams,
         EditArticleParams,
+        FindMissingLinksParams,
         ForkArticleParams,
         GetArticleParams,
+        GetEditList,
         ListArticlesParams,
+        ListCategoriesParams,
+        MoveArticleParams,
         ProtectArticleParams,
+        ResolveTemplatesParams,
+        RevertArticleParams,
     },
     comment::{CreateCommentParams, EditCommentParams},
-    instance::SearchArticleParams,
-    user::{GetUserParams, LoginUserParams, RegisterUserParams},
+    instance::{BlockInstanceParams, SearchArticleParams},
+    upload::UploadFileParams,
+    user::{
+        BanUserParams,
+        ChangePasswordParams,
+        CreateApiTokenParams,
+        DeleteAccountParams,
+        DisableTotpParams,
+        GetUserParams,
+        LoginUserParams,
+        RegisterUserParams,
+        ResetPasswordParams,
+        UpdateUserParams,
+    },
 };
-use ibis_database::common::{
-    article::ArticleView,
-    notifications::ApiNotification,
-    utils::extract_domain,
+use ibis_backend::api::totp::generate_totp_code;
+use ibis_database::{
+    common::{
+        PaginationParams,
+        article::ArticleView,
+        instance::Options,
+        notifications::ApiNotification,
+        user::ApiTokenScope,
+        utils::extract_domain,
+    },
+    config::{IbisConfig, IbisConfigUpload},
+    error::ErrorType,
 };
+use ibis_federate::objects::article::ApubArticle;
 use pretty_assertions::assert_eq;
 use retry_future::{LinearRetryStrategy, RetryFuture, RetryPolicy};
 use std::time::Duration;
@@ -77,21 +106,63 @@ async fn api_test_create_read_and_edit_local_article() -> Result<()> {
 
     let search_params = SearchArticleParams {
         query: create_params.title.clone(),
+        ..Default::default()
+    };
+    let search_res = alpha.search(&search_params).await.unwrap();
+    assert_eq!(1, search_res.len());
+    assert_eq!(edit_res.article.id, search_res[0].id);
+    assert_eq!(edit_res.article.ap_id, search_res[0].ap_id);
+
+    // search also matches article text, and highlights the matched terms
+    let search_params = SearchArticleParams {
+        query: "ipsum".to_string(),
+        ..Default::default()
     };
     let search_res = alpha.search(&search_params).await.unwrap();
     assert_eq!(1, search_res.len());
-    assert_eq!(edit_res.article, search_res[0]);
+    assert!(search_res[0].snippet.contains("<mark>Ipsum</mark>"));
 
     let list_articles = alpha
         .list_articles(ListArticlesParams {
             only_local: Some(false),
             instance_id: None,
+            ..Default::default()
         })
         .await
         .unwrap();
     assert_eq!(2, list_articles.len());
     assert_eq!(edit_res.article, list_articles[0]);
 
+    // second page only contains the article which was edited least recently
+    let list_articles = alpha
+        .list_articles(ListArticlesParams {
+            page: Some(2),
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(1, list_articles.len());
+    assert_ne!(edit_res.article.id, list_articles[0].id);
+
+    // first page of edits with limit contains the most recent edit
+    let edit_list_params = GetEditList {
+        article_id: Some(edit_res.article.id),
+        limit: Some(1),
+        ..Default::default()
+    };
+    let edits = alpha.list_edits(&edit_list_params).await.unwrap();
+    assert_eq!(1, edits.len());
+    assert_eq!(edit_params.summary, edits[0].edit.summary);
+
+    // invalid pagination params are rejected
+    let edit_list_params = GetEditList {
+        article_id: Some(edit_res.article.id),
+        page: Some(0),
+        ..Default::default()
+    };
+    assert!(alpha.list_edits(&edit_list_params).await.is_err());
+
     TestData::stop(alpha, beta, gamma)
 }
 
@@ -423,7 +494,7 @@ async fn api_test_local_edit_conflict() -> Result<()> {
         edit_res.three_way_merge
     );
 
-    let notifications = alpha.notifications_list().await.unwrap();
+    let notifications = alpha.notifications_list(Default::default()).await.unwrap();
     assert_eq!(1, notifications.len());
     let ApiNotification::EditConflict(conflict, _) = &notifications[0] else {
         panic!()
@@ -531,7 +602,7 @@ async fn api_test_federated_edit_conflict() -> Result<()> {
     assert!(!edit_res.article.local);
 
     assert_eq!(1, gamma.notifications_count().await.unwrap());
-    let notifications = gamma.notifications_list().await.unwrap();
+    let notifications = gamma.notifications_list(Default::default()).await.unwrap();
     assert_eq!(1, notifications.len());
     let ApiNotification::EditConflict(conflict, _) = &notifications[0] else {
         panic!()
@@ -557,7 +628,7 @@ async fn api_test_federated_edit_conflict() -> Result<()> {
     assert!(gamma_edits.iter().all(|e| !e.edit.pending));
 
     assert_eq!(0, gamma.notifications_count().await.unwrap());
-    let notifications = gamma.notifications_list().await.unwrap();
+    let notifications = gamma.notifications_list(Default::default()).await.unwrap();
     assert_eq!(0, notifications.len());
 
     TestData::stop(alpha, beta, gamma)
@@ -657,7 +728,7 @@ async fn api_test_fork_article() -> Result<()> {
 
     // fetch on beta
     let resolve_res = beta
-        .resolve_article(create_res.article.ap_id.into())
+        .resolve_article(create_res.article.ap_id.inner().clone())
         .await
         .unwrap();
     let resolved_article = resolve_res.article;
@@ -686,12 +757,29 @@ async fn api_test_fork_article() -> Result<()> {
     assert_eq!(forked_article.instance_id, beta_site.instance.id);
 
     // now search returns two articles for this title (original and forked)
-    let search_params = SearchArticleParams {
+    let mut search_params = SearchArticleParams {
         query: create_params.title.clone(),
+        ..Default::default()
     };
     let search_res = beta.search(&search_params).await.unwrap();
     assert_eq!(2, search_res.len());
 
+    // paginate through results
+    search_params.limit = Some(1);
+    search_params.page = Some(2);
+    let search_res = beta.search(&search_params).await.unwrap();
+    assert_eq!(1, search_res.len());
+
+    // only return the forked article
+    let search_params = SearchArticleParams {
+        query: create_params.title.clone(),
+        instance_id: Some(beta_site.instance.id),
+        ..Default::default()
+    };
+    let search_res = beta.search(&search_params).await.unwrap();
+    assert_eq!(1, search_res.len());
+    assert_eq!(forked_article.ap_id, search_res[0].ap_id);
+
     TestData::stop(alpha, beta, gamma)
 }
 
@@ -703,19 +791,32 @@ async fn api_test_user_registration_login() -> Result<()> {
     let register_data = RegisterUserParams {
         username: username.to_string(),
         password: password.to_string(),
+        ..Default::default()
     };
     alpha.register(register_data).await.unwrap();
 
     let login_data = LoginUserParams {
         username: username.to_string(),
         password: "asd123".to_string(),
+        ..Default::default()
+    };
+    let invalid_login = alpha.login(login_data).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, invalid_login.error_type());
+
+    // unknown username gives the same error as wrong password
+    let login_data = LoginUserParams {
+        username: "unknown_user".to_string(),
+        password: password.to_string(),
+        ..Default::default()
     };
-    let invalid_login = alpha.login(login_data).await;
-    assert!(invalid_login.is_err());
+    let unknown_user = alpha.login(login_data).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, unknown_user.error_type());
+    assert_eq!(invalid_login.to_string(), unknown_user.to_string());
 
     let login_data = LoginUserParams {
         username: username.to_string(),
         password: password.to_string(),
+        ..Default::default()
     };
     alpha.login(login_data).await.unwrap();
 
@@ -741,7 +842,7 @@ async fn api_test_user_profile() -> Result<()> {
         summary: "create article".to_string(),
     };
     let create_res = alpha.create_article(&create_params).await.unwrap();
-    beta.resolve_article(create_res.article.ap_id.into())
+    beta.resolve_article(create_res.article.ap_id.inner().clone())
         .await
         .unwrap();
     let domain = extract_domain(
@@ -793,6 +894,7 @@ async fn api_test_lock_article() -> Result<()> {
     let params = LoginUserParams {
         username: "ibis".to_string(),
         password: "ibis".to_string(),
+        ..Default::default()
     };
     alpha.login(params).await.unwrap();
     let lock_res = alpha.protect_article(&lock_params).await.unwrap();
@@ -823,7 +925,7 @@ async fn api_test_synchronize_instances() -> Result<()> {
     beta.resolve_instance(Url::parse(&format!("http://{}", &alpha.hostname))?)
         .await
         .unwrap();
-    let beta_instances = beta.list_instances().await.unwrap();
+    let beta_instances = beta.list_instances(Default::default()).await.unwrap();
     assert_eq!(2, beta_instances.len());
 
     // fetch beta instance on gamma
@@ -835,7 +937,7 @@ async fn api_test_synchronize_instances() -> Result<()> {
     // wait until instance collection is fetched
     let gamma_instances = RetryFuture::new(
         || async {
-            let res = gamma.list_instances().await;
+            let res = gamma.list_instances(Default::default()).await;
             match res {
                 Err(_) => Err(RetryPolicy::<String>::Retry(None)),
                 Ok(i) if i.len() < 3 => Err(RetryPolicy::Retry(None)),
@@ -878,11 +980,12 @@ async fn api_test_article_approval_required() -> Result<()> {
     let params = LoginUserParams {
         username: "ibis".to_string(),
         password: "ibis".to_string(),
+        ..Default::default()
     };
     alpha.login(params).await.unwrap();
 
     assert_eq!(1, alpha.notifications_count().await.unwrap());
-    let notifications = alpha.notifications_list().await.unwrap();
+    let notifications = alpha.notifications_list(Default::default()).await.unwrap();
     assert_eq!(1, notifications.len());
     let ApiNotification::ArticleApprovalRequired(notif) = &notifications[0] else {
         panic!()
@@ -1056,3 +1159,1771 @@ async fn api_test_comment_delete_restore() -> Result<()> {
 
     TestData::stop(alpha, beta, gamma)
 }
+
+#[tokio::test]
+async fn api_test_resolve_webfinger() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // create article on alpha
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = alpha.create_article(&create_params).await.unwrap();
+
+    // resolve article on beta by handle
+    let resolve_res = beta
+        .resolve_article(Url::parse(&format!(
+            "acct:{}@{}",
+            create_res.article.title, alpha.hostname
+        ))?)
+        .await
+        .unwrap();
+    assert_eq!(create_res.article.ap_id, resolve_res.article.ap_id);
+    assert_eq!(create_res.article.text, resolve_res.article.text);
+    assert!(!resolve_res.article.local);
+
+    // resolve alpha instance on gamma by handle
+    let instance = gamma
+        .resolve_instance(Url::parse(&format!(
+            "acct:{}@{}",
+            alpha.hostname, alpha.hostname
+        ))?)
+        .await
+        .unwrap();
+    assert_eq!(create_res.instance.ap_id, instance.ap_id);
+
+    // unknown names cant be resolved
+    let resolve_res = beta
+        .resolve_article(Url::parse(&format!(
+            "acct:Unknown_Article@{}",
+            alpha.hostname
+        ))?)
+        .await;
+    assert!(resolve_res.is_err());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_revert_article() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    let beta_instance = alpha
+        .follow_instance_with_resolve(&beta.hostname)
+        .await
+        .unwrap();
+
+    // create article and edit it
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = beta.create_article(&create_params).await.unwrap();
+    let edit_params = EditArticleParams {
+        article_id: create_res.article.id,
+        new_text: "vandalism\n".to_string(),
+        summary: "summary".to_string(),
+        previous_version_id: create_res.latest_version.clone(),
+        resolve_conflict_id: None,
+    };
+    let edit_res = beta
+        .edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+    assert_eq!(edit_params.new_text, edit_res.article.text);
+
+    // cant revert to the current version
+    let revert_params = RevertArticleParams {
+        article_id: create_res.article.id,
+        version: edit_res.latest_version,
+    };
+    assert!(beta.revert_article(&revert_params).await.is_err());
+
+    // revert to the first version
+    let revert_params = RevertArticleParams {
+        article_id: create_res.article.id,
+        version: create_res.latest_version,
+    };
+    let revert_res = beta.revert_article(&revert_params).await.unwrap();
+    assert_eq!(create_res.article.text, revert_res.article.text);
+    let edits = beta.get_article_edits(create_res.article.id).await.unwrap();
+    assert_eq!(3, edits.len());
+    assert!(
+        edits[2]
+            .edit
+            .summary
+            .starts_with("Revert to \"create article\"")
+    );
+
+    // revert should be federated to alpha
+    let get_article_data = GetArticleParams {
+        title: Some(create_res.article.title.clone()),
+        domain: Some(beta_instance.domain),
+        id: None,
+    };
+    let get_res = alpha.get_article(get_article_data).await.unwrap();
+    assert_eq!(create_res.article.text, get_res.article.text);
+    let edits = alpha.get_article_edits(get_res.article.id).await.unwrap();
+    assert_eq!(3, edits.len());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_delete_article() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    beta.follow_instance_with_resolve(&alpha.hostname)
+        .await
+        .unwrap();
+
+    // create article
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = alpha.create_article(&create_params).await.unwrap();
+    let get_params = GetArticleParams {
+        title: Some(create_res.article.title.clone()),
+        domain: Some(alpha.hostname.clone()),
+        ..Default::default()
+    };
+    assert!(beta.get_article(get_params.clone()).await.is_ok());
+
+    // delete from normal user fails
+    assert!(alpha.delete_article(create_res.article.id).await.is_err());
+
+    // login as admin to delete article
+    let params = LoginUserParams {
+        username: "ibis".to_string(),
+        password: "ibis".to_string(),
+        ..Default::default()
+    };
+    alpha.login(params).await.unwrap();
+    alpha.delete_article(create_res.article.id).await.unwrap();
+    assert!(alpha.get_article(get_params.clone()).await.is_err());
+    let list_articles = alpha
+        .list_articles(ListArticlesParams {
+            only_local: Some(true),
+            instance_id: None,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(
+        list_articles
+            .iter()
+            .all(|a| a.ap_id != create_res.article.ap_id)
+    );
+    sleep(Duration::from_secs(1)).await;
+
+    // deletion is federated to beta
+    assert!(beta.get_article(get_params).await.is_err());
+
+    // article is served as tombstone, so it cant be resolved anymore
+    let resolve_res = gamma
+        .resolve_article(create_res.article.ap_id.inner().clone())
+        .await;
+    assert!(resolve_res.is_err());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_move_article() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    beta.follow_instance_with_resolve(&alpha.hostname)
+        .await
+        .unwrap();
+
+    // create articles
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = alpha.create_article(&create_params).await.unwrap();
+    let create_params = CreateArticleParams {
+        title: "Mano_Negra".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    alpha.create_article(&create_params).await.unwrap();
+
+    // cant move to title of existing article
+    let mut move_params = MoveArticleParams {
+        article_id: create_res.article.id,
+        new_title: "Mano_Negra".to_string(),
+    };
+    assert!(alpha.move_article(&move_params).await.is_err());
+
+    // move article
+    move_params.new_title = "Manu_Chao_Musician".to_string();
+    let move_res = alpha.move_article(&move_params).await.unwrap();
+    assert_eq!(move_params.new_title, move_res.article.title);
+    assert_eq!(create_res.article.ap_id, move_res.article.ap_id);
+
+    // article can be read with new and old title
+    let mut get_params = GetArticleParams {
+        title: Some(move_params.new_title.clone()),
+        domain: Some(alpha.hostname.clone()),
+        id: None,
+    };
+    let get_res = alpha.get_article(get_params.clone()).await.unwrap();
+    assert_eq!(create_res.article.id, get_res.article.id);
+    get_params.title = Some(create_res.article.title.clone());
+    let get_res = alpha.get_article(get_params.clone()).await.unwrap();
+    assert_eq!(create_res.article.id, get_res.article.id);
+    assert_eq!(move_params.new_title, get_res.article.title);
+    sleep(Duration::from_secs(1)).await;
+
+    // move is federated to beta, where old title also redirects
+    let get_res = beta.get_article(get_params.clone()).await.unwrap();
+    assert_eq!(move_params.new_title, get_res.article.title);
+    get_params.title = Some(move_params.new_title.clone());
+    let get_res = beta.get_article(get_params).await.unwrap();
+    assert_eq!(create_res.article.ap_id, get_res.article.ap_id);
+
+    // article can still be fetched with original ap_id
+    let resolve_res = gamma
+        .resolve_article(create_res.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    assert_eq!(move_params.new_title, resolve_res.article.title);
+
+    // previous title cant be used for a new article
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let err = alpha.create_article(&create_params).await.unwrap_err();
+    assert_eq!(ErrorType::Validation, err.error_type());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_synchronize_paged_edits() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // create article with more edits than fit in a single collection page
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let mut article = alpha.create_article(&create_params).await.unwrap();
+    for i in 0..54 {
+        let edit_params = EditArticleParams {
+            article_id: article.article.id,
+            new_text: format!("Lorem Ipsum {i}\n"),
+            summary: format!("edit {i}"),
+            previous_version_id: article.latest_version,
+            resolve_conflict_id: None,
+        };
+        article = alpha
+            .edit_article_without_conflict(&edit_params)
+            .await
+            .unwrap();
+    }
+
+    // all edits are fetched on beta by walking through the collection pages
+    let resolve_res = beta
+        .resolve_article(article.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    assert_eq!(article.latest_version, resolve_res.latest_version);
+    assert_eq!(article.article.text, resolve_res.article.text);
+    let edit_list_params = GetEditList {
+        article_id: Some(resolve_res.article.id),
+        page: Some(3),
+        limit: Some(20),
+        ..Default::default()
+    };
+    let edits = beta.list_edits(&edit_list_params).await.unwrap();
+    assert_eq!(15, edits.len());
+    assert_eq!(create_params.summary, edits[0].edit.summary);
+    let edits = beta
+        .get_article_edits(resolve_res.article.id)
+        .await
+        .unwrap();
+    // without pagination params all edits are returned
+    assert_eq!(55, edits.len());
+    assert_eq!("edit 53", edits[54].edit.summary);
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_block_instance() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // fetch article from beta
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = beta.create_article(&create_params).await.unwrap();
+    let resolve_res = alpha
+        .resolve_article(create_res.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    let get_params = GetArticleParams {
+        id: Some(resolve_res.article.id),
+        ..Default::default()
+    };
+    assert!(alpha.get_article(get_params.clone()).await.is_ok());
+
+    // block from normal user fails
+    let mut block_params = BlockInstanceParams {
+        domain: beta.hostname.clone(),
+        block: true,
+        purge: true,
+    };
+    assert!(alpha.block_instance(&block_params).await.is_err());
+
+    // block as admin, existing content from beta is removed
+    let params = LoginUserParams {
+        username: "ibis".to_string(),
+        password: "ibis".to_string(),
+        ..Default::default()
+    };
+    alpha.login(params).await.unwrap();
+    alpha.block_instance(&block_params).await.unwrap();
+    let blocks = alpha.list_instance_blocks().await.unwrap();
+    assert_eq!(1, blocks.len());
+    assert_eq!(beta.hostname, blocks[0].domain);
+    assert!(alpha.get_article(get_params).await.is_err());
+
+    // federation with beta is not possible anymore
+    let beta_url = Url::parse(&format!("http://{}", beta.hostname))?;
+    assert!(alpha.resolve_instance(beta_url.clone()).await.is_err());
+    assert!(
+        alpha
+            .resolve_article(create_res.article.ap_id.inner().clone())
+            .await
+            .is_err()
+    );
+
+    // unblock takes effect immediately
+    block_params.block = false;
+    alpha.block_instance(&block_params).await.unwrap();
+    assert!(alpha.list_instance_blocks().await.unwrap().is_empty());
+    assert!(alpha.resolve_instance(beta_url).await.is_ok());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_ban_user() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+    let admin_login = LoginUserParams {
+        username: "ibis".to_string(),
+        password: "ibis".to_string(),
+        ..Default::default()
+    };
+    let user_login = LoginUserParams {
+        username: "alpha".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+
+    // admin creates and edits article, then normal user edits and comments
+    alpha.login(admin_login.clone()).await.unwrap();
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: "first\n\nsecond\n\nthird\n".to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = alpha.create_article(&create_params).await.unwrap();
+    let admin_edit_params = EditArticleParams {
+        article_id: create_res.article.id,
+        new_text: "first edited\n\nsecond\n\nthird\n".to_string(),
+        summary: "edit first".to_string(),
+        previous_version_id: create_res.latest_version,
+        resolve_conflict_id: None,
+    };
+    let edit_res = alpha
+        .edit_article_without_conflict(&admin_edit_params)
+        .await
+        .unwrap();
+    alpha.login(user_login.clone()).await.unwrap();
+    let mut edit_params = EditArticleParams {
+        article_id: create_res.article.id,
+        new_text: "first edited\n\nspam\n\nthird\n".to_string(),
+        summary: "spam".to_string(),
+        previous_version_id: edit_res.latest_version,
+        resolve_conflict_id: None,
+    };
+    let edit_res = alpha
+        .edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+    assert_eq!(edit_params.new_text, edit_res.article.text);
+    let comment_params = CreateCommentParams {
+        content: "spam comment".to_string(),
+        article_id: create_res.article.id,
+        parent_id: None,
+    };
+    alpha.create_comment(&comment_params).await.unwrap();
+
+    // article which only contains edits from the user
+    let spam_create_params = CreateArticleParams {
+        title: "Spam".to_string(),
+        text: "spam\n".to_string(),
+        summary: "spam".to_string(),
+    };
+    let spam_article = alpha.create_article(&spam_create_params).await.unwrap();
+
+    // admin edits after the spam, this change is kept when reverting
+    alpha.login(admin_login.clone()).await.unwrap();
+    let admin_edit_params = EditArticleParams {
+        article_id: create_res.article.id,
+        new_text: "first edited\n\nspam\n\nthird edited\n".to_string(),
+        summary: "edit third".to_string(),
+        previous_version_id: edit_res.latest_version.clone(),
+        resolve_conflict_id: None,
+    };
+    alpha
+        .edit_article_without_conflict(&admin_edit_params)
+        .await
+        .unwrap();
+    alpha.login(user_login.clone()).await.unwrap();
+
+    // only admin can ban
+    let person = alpha
+        .get_user(GetUserParams {
+            name: "alpha".to_string(),
+            domain: None,
+        })
+        .await
+        .unwrap();
+    let mut ban_params = BanUserParams {
+        person_id: person.id,
+        ban: true,
+        remove_content: true,
+    };
+    assert!(alpha.ban_user(&ban_params).await.is_err());
+
+    // ban user and remove content
+    alpha.login(admin_login.clone()).await.unwrap();
+    let banned = alpha.ban_user(&ban_params).await.unwrap();
+    assert!(banned.banned);
+    let banned_list = alpha.list_banned_users().await.unwrap();
+    assert_eq!(vec![banned], banned_list);
+    let get_params = GetArticleParams {
+        id: Some(create_res.article.id),
+        ..Default::default()
+    };
+    let article = alpha.get_article(get_params.clone()).await.unwrap();
+    assert_eq!(
+        "first edited\n\nsecond\n\nthird edited\n",
+        article.article.text
+    );
+    assert_eq!(1, article.comments.len());
+    assert!(article.comments[0].comment.deleted);
+    let spam_get_params = GetArticleParams {
+        id: Some(spam_article.article.id),
+        ..Default::default()
+    };
+    assert!(alpha.get_article(spam_get_params).await.is_err());
+
+    // banned user cant edit, comment or upload anymore
+    alpha.login(user_login.clone()).await.unwrap();
+    edit_params.previous_version_id = article.latest_version;
+    assert!(alpha.edit_article(&edit_params).await.is_err());
+    assert!(alpha.create_comment(&comment_params).await.is_err());
+    let upload_params = UploadFileParams {
+        filename: "image.png".to_string(),
+        data: test_png(10, 10),
+    };
+    assert!(alpha.upload_file(upload_params).await.is_err());
+
+    // edits work again after unban
+    alpha.login(admin_login).await.unwrap();
+    ban_params.ban = false;
+    alpha.ban_user(&ban_params).await.unwrap();
+    assert!(alpha.list_banned_users().await.unwrap().is_empty());
+    alpha.login(user_login).await.unwrap();
+    alpha
+        .edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_error_types() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // missing article
+    let get_params = GetArticleParams {
+        title: Some("Does_Not_Exist".to_string()),
+        ..Default::default()
+    };
+    let err = alpha.get_article(get_params).await.unwrap_err();
+    assert_eq!(ErrorType::NotFound, err.error_type());
+
+    // create article with existing title
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = alpha.create_article(&create_params).await.unwrap();
+    let err = alpha.create_article(&create_params).await.unwrap_err();
+    assert_eq!(ErrorType::Conflict, err.error_type());
+
+    // deletion requires admin
+    let err = alpha
+        .delete_article(create_res.article.id)
+        .await
+        .unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    // notifications require login
+    alpha.logout().await.unwrap();
+    let err = alpha
+        .notifications_list(Default::default())
+        .await
+        .unwrap_err();
+    assert_eq!(ErrorType::NotLoggedIn, err.error_type());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_account_management() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // alpha user edits and comments on article from beta
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = beta.create_article(&create_params).await.unwrap();
+    let resolve_res = alpha
+        .resolve_article(create_res.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    let edit_params = EditArticleParams {
+        article_id: resolve_res.article.id,
+        new_text: "Lorem Ipsum 2\n".to_string(),
+        summary: "summary".to_string(),
+        previous_version_id: resolve_res.latest_version,
+        resolve_conflict_id: None,
+    };
+    alpha
+        .edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+    let comment_params = CreateCommentParams {
+        content: "my comment".to_string(),
+        article_id: resolve_res.article.id,
+        parent_id: None,
+    };
+    alpha.create_comment(&comment_params).await.unwrap();
+
+    // change password
+    let mut password_params = ChangePasswordParams {
+        old_password: "wrong".to_string(),
+        new_password: "hunter3".to_string(),
+    };
+    let err = alpha.change_password(&password_params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    password_params.old_password = "hunter2".to_string();
+    alpha.change_password(&password_params).await.unwrap();
+    let mut login_params = LoginUserParams {
+        username: "alpha".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    assert!(alpha.login(login_params.clone()).await.is_err());
+    login_params.password = "hunter3".to_string();
+    alpha.login(login_params.clone()).await.unwrap();
+
+    // export data
+    let export = alpha.export_user_data().await.unwrap();
+    assert_eq!("alpha", export.person.username);
+    assert_eq!(1, export.edits.len());
+    assert_eq!(edit_params.summary, export.edits[0].edit.summary);
+    assert_eq!(1, export.comments.len());
+    assert_eq!(comment_params.content, export.comments[0].content);
+
+    // delete account
+    let mut delete_params = DeleteAccountParams {
+        password: "hunter2".to_string(),
+    };
+    assert!(alpha.delete_account(&delete_params).await.is_err());
+    delete_params.password = "hunter3".to_string();
+    alpha.delete_account(&delete_params).await.unwrap();
+    assert!(alpha.login(login_params).await.is_err());
+    let get_user_params = GetUserParams {
+        name: "alpha".to_string(),
+        domain: None,
+    };
+    assert!(alpha.get_user(get_user_params).await.is_err());
+
+    // on beta the edit belongs to ghost user and the comment is deleted
+    let get_user_params = GetUserParams {
+        name: "alpha".to_string(),
+        domain: Some(alpha.hostname.clone()),
+    };
+    assert!(beta.get_user(get_user_params).await.is_err());
+    let edits = beta.get_article_edits(create_res.article.id).await.unwrap();
+    assert_eq!(2, edits.len());
+    assert_eq!("ghost", edits[1].creator.username);
+    let get_params = GetArticleParams {
+        id: Some(create_res.article.id),
+        ..Default::default()
+    };
+    let article = beta.get_article(get_params).await.unwrap();
+    assert_eq!(1, article.comments.len());
+    assert!(article.comments[0].comment.deleted);
+    assert_eq!("ghost", article.comments[0].creator.username);
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_sessions() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // login from another device
+    let other_client = ApiClient::new(Some(alpha.hostname.clone()));
+    let login_params = LoginUserParams {
+        username: "alpha".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    other_client.login(login_params.clone()).await.unwrap();
+    let sessions = alpha.list_sessions().await.unwrap();
+    assert_eq!(2, sessions.len());
+    assert_eq!(1, sessions.iter().filter(|s| s.current).count());
+
+    // revoke session of the other device
+    let other = sessions.iter().find(|s| !s.current).unwrap();
+    alpha.delete_session(other.session.id).await.unwrap();
+    assert!(other_client.site().await.unwrap().my_profile.is_none());
+
+    // logout removes the session
+    other_client.login(login_params.clone()).await.unwrap();
+    assert_eq!(2, alpha.list_sessions().await.unwrap().len());
+    other_client.logout().await.unwrap();
+    assert_eq!(1, alpha.list_sessions().await.unwrap().len());
+
+    // log out everywhere
+    other_client.login(login_params).await.unwrap();
+    alpha.logout_all().await.unwrap();
+    assert!(alpha.site().await.unwrap().my_profile.is_none());
+    let err = other_client.list_sessions().await.unwrap_err();
+    assert_eq!(ErrorType::NotLoggedIn, err.error_type());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_api_tokens() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // read only token can view but not edit
+    let mut token_params = CreateApiTokenParams {
+        name: "my bot".to_string(),
+        scope: ApiTokenScope::Read,
+    };
+    let read_token = alpha.create_api_token(&token_params).await.unwrap();
+    assert_eq!(token_params.name, read_token.api_token.name);
+    let read_client = ApiClient::new(Some(alpha.hostname.clone())).with_api_token(read_token.token);
+    let my_profile = read_client.site().await.unwrap().my_profile.unwrap();
+    assert_eq!("alpha", my_profile.person.username);
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let err = read_client
+        .create_article(&create_params)
+        .await
+        .unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    let err = read_client.export_user_data().await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    // edit token can create articles, but not manage tokens
+    token_params.scope = ApiTokenScope::Edit;
+    let edit_token = alpha.create_api_token(&token_params).await.unwrap();
+    let edit_client = ApiClient::new(Some(alpha.hostname.clone())).with_api_token(edit_token.token);
+    let create_res = edit_client.create_article(&create_params).await.unwrap();
+    let err = edit_client.list_api_tokens().await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    // only admins can create tokens with admin scope
+    token_params.scope = ApiTokenScope::Admin;
+    assert!(alpha.create_api_token(&token_params).await.is_err());
+
+    // admin action requires admin scope
+    let admin_login = LoginUserParams {
+        username: "ibis".to_string(),
+        password: "ibis".to_string(),
+        ..Default::default()
+    };
+    alpha.login(admin_login).await.unwrap();
+    let admin_token = alpha.create_api_token(&token_params).await.unwrap();
+    token_params.scope = ApiTokenScope::Edit;
+    let admin_edit_token = alpha.create_api_token(&token_params).await.unwrap();
+    let protect_params = ProtectArticleParams {
+        article_id: create_res.article.id,
+        protected: true,
+    };
+    let admin_edit_client =
+        ApiClient::new(Some(alpha.hostname.clone())).with_api_token(admin_edit_token.token);
+    let err = admin_edit_client
+        .protect_article(&protect_params)
+        .await
+        .unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    let admin_client =
+        ApiClient::new(Some(alpha.hostname.clone())).with_api_token(admin_token.token);
+    let article = admin_client.protect_article(&protect_params).await.unwrap();
+    assert!(article.protected);
+
+    // revoked token cant be used anymore
+    let tokens = alpha.list_api_tokens().await.unwrap();
+    assert_eq!(2, tokens.len());
+    assert!(tokens.iter().all(|t| t.last_used.is_some()));
+    alpha
+        .delete_api_token(admin_token.api_token.id)
+        .await
+        .unwrap();
+    let err = admin_client.site().await.unwrap_err();
+    assert_eq!(ErrorType::NotLoggedIn, err.error_type());
+
+    // changing the password revokes all tokens
+    let password_params = ChangePasswordParams {
+        old_password: "ibis".to_string(),
+        new_password: "ibis2".to_string(),
+    };
+    alpha.change_password(&password_params).await.unwrap();
+    assert!(alpha.list_api_tokens().await.unwrap().is_empty());
+    let err = admin_edit_client.site().await.unwrap_err();
+    assert_eq!(ErrorType::NotLoggedIn, err.error_type());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_bot_edits() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // alpha creates an article, which automatically follows it, and hides bot edits
+    let create_params = CreateArticleParams {
+        title: "Manu_Chao".to_string(),
+        text: TEST_ARTICLE_DEFAULT_TEXT.to_string(),
+        summary: "create article".to_string(),
+    };
+    let create_res = alpha.create_article(&create_params).await.unwrap();
+    let profile_params = UpdateUserParams {
+        hide_bot_edits: Some(true),
+        ..Default::default()
+    };
+    alpha.update_user_profile(profile_params).await.unwrap();
+
+    // register a bot account which edits the article
+    let bot = ApiClient::new(Some(alpha.hostname.clone()));
+    let register_params = RegisterUserParams {
+        username: "my_bot".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    bot.register(register_params).await.unwrap();
+    let profile_params = UpdateUserParams {
+        bot: Some(true),
+        ..Default::default()
+    };
+    bot.update_user_profile(profile_params).await.unwrap();
+    let edit_params = EditArticleParams {
+        article_id: create_res.article.id,
+        new_text: "Lorem Ipsum\n".to_string(),
+        summary: "automated edit".to_string(),
+        previous_version_id: create_res.latest_version,
+        resolve_conflict_id: None,
+    };
+    bot.edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+
+    // no notification for bot edit, and it is hidden from history by default
+    assert_eq!(0, alpha.notifications_count().await.unwrap());
+    let edits = alpha
+        .get_article_edits(create_res.article.id)
+        .await
+        .unwrap();
+    assert_eq!(1, edits.len());
+    let edit_list_params = GetEditList {
+        article_id: Some(create_res.article.id),
+        hide_bots: Some(false),
+        ..Default::default()
+    };
+    let edits = alpha.list_edits(&edit_list_params).await.unwrap();
+    assert_eq!(2, edits.len());
+    assert!(edits[1].creator.bot);
+
+    // bot flag is federated
+    let resolve_res = beta
+        .resolve_article(create_res.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    let edit_list_params = GetEditList {
+        article_id: Some(resolve_res.article.id),
+        hide_bots: Some(true),
+        ..Default::default()
+    };
+    let edits = beta.list_edits(&edit_list_params).await.unwrap();
+    assert_eq!(1, edits.len());
+    assert!(!edits[0].creator.bot);
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_registration_approval() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start_with_options(Options {
+        registration_open: true,
+        registration_approval: true,
+        registration_question: Some("Why do you want to join?".to_string()),
+        ..Default::default()
+    })
+    .await;
+
+    // user registered during test setup cant login before approval
+    let login_params = LoginUserParams {
+        username: "alpha".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    let err = alpha.login(login_params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    // answer to the registration question is required
+    let mut register_params = RegisterUserParams {
+        username: "spammer".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    let err = alpha.register(register_params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::Validation, err.error_type());
+    register_params.answer = Some("cheap pills".to_string());
+    let spammer = alpha.register(register_params).await.unwrap();
+    assert!(!spammer.local_user.approved);
+
+    // admin gets notified about both applications
+    let admin_login = LoginUserParams {
+        username: "ibis".to_string(),
+        password: "ibis".to_string(),
+        ..Default::default()
+    };
+    alpha.login(admin_login).await.unwrap();
+    assert_eq!(2, alpha.notifications_count().await.unwrap());
+    let notifications = alpha.notifications_list(Default::default()).await.unwrap();
+    let ApiNotification::RegistrationApplication(spam_application, person) = &notifications[0]
+    else {
+        panic!()
+    };
+    assert_eq!("spammer", person.username);
+    assert_eq!(Some("cheap pills"), spam_application.answer.as_deref());
+    let ApiNotification::RegistrationApplication(application, person) = &notifications[1] else {
+        panic!()
+    };
+    assert_eq!("alpha", person.username);
+    alpha
+        .approve_registration(application.id, true)
+        .await
+        .unwrap();
+    alpha
+        .approve_registration(spam_application.id, false)
+        .await
+        .unwrap();
+    assert_eq!(0, alpha.notifications_count().await.unwrap());
+
+    // approved user can login, rejected account is deleted
+    alpha.login(login_params).await.unwrap();
+    let get_user_params = GetUserParams {
+        name: "spammer".to_string(),
+        domain: None,
+    };
+    let err = alpha.get_user(get_user_params).await.unwrap_err();
+    assert_eq!(ErrorType::NotFound, err.error_type());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+/// Extract the token from the link in an email
+fn email_token(email: &str) -> String {
+    // undo quoted-printable encoding of the link
+    let email = email.replace("=\r\n", "").replace("=3D", "=");
+    let (_, token) = email.split_once("token=").unwrap();
+    token
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+#[tokio::test]
+async fn api_test_email() -> Result<()> {
+    let smtp = SmtpStub::start().await;
+    let options = Options {
+        registration_open: true,
+        ..Default::default()
+    };
+    let TestData(alpha, beta, gamma) = TestData::start_with_config(IbisConfig {
+        options,
+        email: Some(smtp.config.clone()),
+        ..Default::default()
+    })
+    .await;
+    assert!(alpha.site().await.unwrap().email_enabled);
+
+    // register with email, a verification link is sent
+    let delta = ApiClient::new(Some(alpha.hostname.clone()));
+    let mut register_params = RegisterUserParams {
+        username: "delta".to_string(),
+        password: "hunter2".to_string(),
+        email: Some(" Delta@Example.com".to_string()),
+        ..Default::default()
+    };
+    let user = delta.register(register_params.clone()).await.unwrap();
+    assert_eq!(Some("delta@example.com"), user.local_user.email.as_deref());
+    assert!(!user.local_user.email_verified);
+    let emails = smtp.take_emails(1).await;
+    assert_eq!(1, emails.len());
+    assert!(emails[0].contains("To: delta@example.com"));
+    let verify_token = email_token(&emails[0]);
+
+    // unverified email can be claimed by another account
+    let delta2 = ApiClient::new(Some(alpha.hostname.clone()));
+    register_params.username = "delta2".to_string();
+    delta2.register(register_params.clone()).await.unwrap();
+    let delta2_token = email_token(&smtp.take_emails(1).await[0]);
+
+    // no password reset for unverified email
+    delta
+        .request_password_reset("delta@example.com".to_string())
+        .await
+        .unwrap();
+    assert!(smtp.take_emails(0).await.is_empty());
+
+    // verify email, token can only be used once
+    delta.verify_email(verify_token.clone()).await.unwrap();
+    let my_profile = delta.site().await.unwrap().my_profile.unwrap();
+    assert!(my_profile.local_user.email_verified);
+    let err = delta.verify_email(verify_token).await.unwrap_err();
+    assert_eq!(ErrorType::NotFound, err.error_type());
+
+    // once verified, other claims are removed and the email cant be used again
+    let err = delta2.verify_email(delta2_token).await.unwrap_err();
+    assert_eq!(ErrorType::NotFound, err.error_type());
+    let delta2_user = delta2.site().await.unwrap().my_profile.unwrap().local_user;
+    assert_eq!(None, delta2_user.email);
+    register_params.username = "delta3".to_string();
+    let err = alpha.register(register_params).await.unwrap_err();
+    assert_eq!(ErrorType::Conflict, err.error_type());
+
+    // unknown email doesnt return an error, but nothing is sent
+    delta
+        .request_password_reset("unknown@example.com".to_string())
+        .await
+        .unwrap();
+    assert!(smtp.take_emails(0).await.is_empty());
+
+    // reset password
+    delta
+        .request_password_reset("delta@example.com".to_string())
+        .await
+        .unwrap();
+    let emails = smtp.take_emails(1).await;
+    assert_eq!(1, emails.len());
+    let reset_token = email_token(&emails[0]);
+    let mut reset_params = ResetPasswordParams {
+        token: "invalid".to_string(),
+        new_password: "new password".to_string(),
+    };
+    let err = delta.reset_password(&reset_params).await.unwrap_err();
+    assert_eq!(ErrorType::NotFound, err.error_type());
+    reset_params.token = reset_token;
+    delta.reset_password(&reset_params).await.unwrap();
+    let err = delta.reset_password(&reset_params).await.unwrap_err();
+    assert_eq!(ErrorType::NotFound, err.error_type());
+
+    // all sessions are logged out, only the new password works
+    assert!(delta.site().await.unwrap().my_profile.is_none());
+    let mut login_params = LoginUserParams {
+        username: "delta".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    let err = delta.login(login_params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    login_params.password = "new password".to_string();
+    delta.login(login_params).await.unwrap();
+
+    // changing the email needs the password, and another verification
+    let mut params = UpdateUserParams {
+        email: Some("delta@example.org".to_string()),
+        email_notifications: Some(true),
+        ..Default::default()
+    };
+    let err = delta.update_user_profile(params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    params.password = Some("new password".to_string());
+    delta.update_user_profile(params).await.unwrap();
+    assert_eq!(1, smtp.take_emails(1).await.len());
+    let local_user = delta.site().await.unwrap().my_profile.unwrap().local_user;
+    assert_eq!(Some("delta@example.org"), local_user.email.as_deref());
+    assert!(!local_user.email_verified);
+    assert!(local_user.email_notifications);
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_totp() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // code is needed to enable two factor authentication
+    let setup = alpha.setup_totp().await.unwrap();
+    assert!(setup.uri.starts_with("otpauth://totp/"));
+    assert!(setup.uri.contains(&setup.secret));
+    let err = alpha.enable_totp("000000".to_string()).await.unwrap_err();
+    assert_eq!(ErrorType::Validation, err.error_type());
+    let code = generate_totp_code(&setup.secret, Utc::now().timestamp()).unwrap();
+    let recovery_codes = alpha.enable_totp(code).await.unwrap().recovery_codes;
+    assert_eq!(10, recovery_codes.len());
+    let my_profile = alpha.site().await.unwrap().my_profile.unwrap();
+    assert!(my_profile.local_user.totp_enabled);
+    let err = alpha.setup_totp().await.unwrap_err();
+    assert_eq!(ErrorType::Conflict, err.error_type());
+
+    // login needs a second step
+    let client = ApiClient::new(Some(alpha.hostname.clone()));
+    let mut login_params = LoginUserParams {
+        username: "alpha".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    let err = client.login(login_params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::TotpRequired, err.error_type());
+    login_params.totp_code = Some("000000".to_string());
+    let err = client.login(login_params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    // use code of the next step, as the current one was already used to enable
+    login_params.totp_code =
+        Some(generate_totp_code(&setup.secret, Utc::now().timestamp() + 30).unwrap());
+    client.login(login_params.clone()).await.unwrap();
+
+    // same code cant be used twice
+    let err = client.login(login_params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    // recovery codes can be used once
+    login_params.totp_code = Some(recovery_codes[0].to_uppercase());
+    client.login(login_params.clone()).await.unwrap();
+    let err = client.login(login_params.clone()).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    // disable needs password and code
+    let mut disable_params = DisableTotpParams {
+        password: "wrong".to_string(),
+        code: recovery_codes[1].clone(),
+    };
+    let err = alpha.disable_totp(&disable_params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    disable_params.password = "hunter2".to_string();
+    alpha.disable_totp(&disable_params).await.unwrap();
+    login_params.totp_code = None;
+    client.login(login_params).await.unwrap();
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_oauth_login() -> Result<()> {
+    let oidc = OidcStub::start().await;
+    let TestData(alpha, beta, gamma) = TestData::start_with_config(IbisConfig {
+        options: Options {
+            password_login: false,
+            ..Default::default()
+        },
+        email: Some(SmtpStub::start().await.config),
+        oauth_providers: vec![oidc.provider.clone()],
+        ..Default::default()
+    })
+    .await;
+
+    // password login and registration are disabled
+    let site = alpha.site().await.unwrap();
+    assert!(!site.config.password_login);
+    assert_eq!("stub", site.oauth_providers[0].id);
+    let login_params = LoginUserParams {
+        username: "ibis".to_string(),
+        password: "ibis".to_string(),
+        ..Default::default()
+    };
+    let err = alpha.login(login_params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    let register_params = RegisterUserParams {
+        username: "delta".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    let err = alpha.register(register_params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    let err = alpha
+        .oauth_authorize("unknown".to_string())
+        .await
+        .unwrap_err();
+    assert_eq!(ErrorType::NotFound, err.error_type());
+
+    // first login creates a new account with verified email
+    oidc.set_user("1", "Oauth User", Some("oauth@example.com"));
+    let params = OidcStub::authorize(&alpha, "stub").await;
+    let user = alpha.oauth_login(&params).await.unwrap();
+    assert_eq!("Oauth_User", user.person.username);
+    assert_eq!(Some("oauth@example.com"), user.local_user.email.as_deref());
+    assert!(user.local_user.email_verified);
+    let my_profile = alpha.site().await.unwrap().my_profile.unwrap();
+    assert_eq!(user.person.id, my_profile.person.id);
+
+    // code and state can only be used once
+    let err = alpha.oauth_login(&params).await.unwrap_err();
+    assert_eq!(ErrorType::Validation, err.error_type());
+
+    // another login with the same provider account uses the existing user
+    let client = ApiClient::new(Some(alpha.hostname.clone()));
+    let params = OidcStub::authorize(&client, "stub").await;
+    assert_eq!(
+        user.person.id,
+        client.oauth_login(&params).await.unwrap().person.id
+    );
+
+    // login needs to be completed by the same user who started it
+    oidc.set_user("2", "Second Account", None);
+    let client = ApiClient::new(Some(alpha.hostname.clone()));
+    let params = OidcStub::authorize(&client, "stub").await;
+    let err = alpha.oauth_login(&params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    let params = OidcStub::authorize(&alpha, "stub").await;
+    let err = client.oauth_login(&params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    // second provider account is linked to the logged in user
+    let params = OidcStub::authorize(&alpha, "stub").await;
+    assert_eq!(
+        user.person.id,
+        alpha.oauth_login(&params).await.unwrap().person.id
+    );
+    let client = ApiClient::new(Some(alpha.hostname.clone()));
+    let params = OidcStub::authorize(&client, "stub").await;
+    assert_eq!(
+        user.person.id,
+        client.oauth_login(&params).await.unwrap().person.id
+    );
+
+    // new account gets a different username if it is already taken
+    oidc.set_user("3", "Oauth User", Some("oauth@example.com"));
+    let client = ApiClient::new(Some(alpha.hostname.clone()));
+    let params = OidcStub::authorize(&client, "stub").await;
+    let other = client.oauth_login(&params).await.unwrap();
+    assert_ne!(user.person.id, other.person.id);
+    assert!(other.person.username.starts_with("Oauth_User_"));
+    // email is already used by the other account
+    assert_eq!(None, other.local_user.email);
+
+    // with two factor authentication, login through the provider needs the code as well
+    let setup = alpha.setup_totp().await.unwrap();
+    let code = generate_totp_code(&setup.secret, Utc::now().timestamp()).unwrap();
+    let recovery_codes = alpha.enable_totp(code).await.unwrap().recovery_codes;
+    oidc.set_user("1", "Oauth User", Some("oauth@example.com"));
+    let totp_client = ApiClient::new(Some(alpha.hostname.clone()));
+    let mut params = OidcStub::authorize(&totp_client, "stub").await;
+    let err = totp_client.oauth_login(&params).await.unwrap_err();
+    assert_eq!(ErrorType::TotpRequired, err.error_type());
+    assert!(totp_client.site().await.unwrap().my_profile.is_none());
+    // the login can be completed with the same state, without going through the provider again
+    params.totp_code = Some(recovery_codes[0].clone());
+    let totp_user = totp_client.oauth_login(&params).await.unwrap();
+    assert_eq!(user.person.id, totp_user.person.id);
+    // a wrong code needs a new login through the provider
+    let totp_client = ApiClient::new(Some(alpha.hostname.clone()));
+    let mut params = OidcStub::authorize(&totp_client, "stub").await;
+    params.totp_code = Some("000000".to_string());
+    let err = totp_client.oauth_login(&params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    let err = totp_client.oauth_login(&params).await.unwrap_err();
+    assert_eq!(ErrorType::Validation, err.error_type());
+
+    // accounts created through the provider have no password which could be confirmed
+    let delete_params = DeleteAccountParams {
+        password: String::new(),
+    };
+    alpha.delete_account(&delete_params).await.unwrap();
+    assert!(alpha.site().await.unwrap().my_profile.is_none());
+
+    // after setting a password it is required again
+    let password_params = ChangePasswordParams {
+        old_password: String::new(),
+        new_password: "hunter2".to_string(),
+    };
+    client.change_password(&password_params).await.unwrap();
+    let err = client.delete_account(&delete_params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_oauth_registration_approval() -> Result<()> {
+    let oidc = OidcStub::start().await;
+    let TestData(alpha, beta, gamma) = TestData::start_with_config(IbisConfig {
+        options: Options {
+            registration_approval: true,
+            ..Default::default()
+        },
+        oauth_providers: vec![oidc.provider.clone()],
+        ..Default::default()
+    })
+    .await;
+
+    // new account needs to be approved first
+    oidc.set_user("1", "Oauth User", None);
+    let client = ApiClient::new(Some(alpha.hostname.clone()));
+    let params = OidcStub::authorize(&client, "stub").await;
+    let err = client.oauth_login(&params).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+
+    let admin_login = LoginUserParams {
+        username: "ibis".to_string(),
+        password: "ibis".to_string(),
+        ..Default::default()
+    };
+    alpha.login(admin_login).await.unwrap();
+    let notifications = alpha.notifications_list(Default::default()).await.unwrap();
+    let application = notifications
+        .iter()
+        .find_map(|n| match n {
+            ApiNotification::RegistrationApplication(a, p) if p.username == "Oauth_User" => Some(a),
+            _ => None,
+        })
+        .unwrap();
+    alpha
+        .approve_registration(application.id, true)
+        .await
+        .unwrap();
+
+    let params = OidcStub::authorize(&client, "stub").await;
+    let user = client.oauth_login(&params).await.unwrap();
+    assert_eq!("Oauth_User", user.person.username);
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_upload() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start_with_config(IbisConfig {
+        upload: IbisConfigUpload {
+            max_size: 100_000,
+            thumbnail_size: 100,
+            ..Default::default()
+        },
+        options: Options {
+            registration_open: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let http = reqwest::Client::new();
+
+    // upload image, a thumbnail is generated
+    let png = test_png(300, 200);
+    let params = UploadFileParams {
+        filename: "image.png".to_string(),
+        data: png.clone(),
+    };
+    let image = alpha.upload_file(params).await.unwrap();
+    assert_eq!("image/png", image.upload.content_type);
+    assert_eq!(png.len() as i64, image.upload.size);
+    assert!(image.upload.thumbnail.is_some());
+    assert_ne!(image.url, image.thumbnail_url);
+    assert_eq!(format!("![image.png]({})", image.url), image.markdown());
+
+    let res = http.get(&image.url).send().await?;
+    assert_eq!("image/png", res.headers()["content-type"]);
+    assert_eq!(png, res.bytes().await?.to_vec());
+    let thumbnail = http.get(&image.thumbnail_url).send().await?;
+    assert_eq!("image/png", thumbnail.headers()["content-type"]);
+    assert!(thumbnail.bytes().await?.len() < png.len());
+
+    // other files are downloaded instead of being shown in the browser
+    let params = UploadFileParams {
+        filename: "notes.txt".to_string(),
+        data: b"some notes".to_vec(),
+    };
+    let text = alpha.upload_file(params).await.unwrap();
+    assert_eq!("text/plain", text.upload.content_type);
+    assert_eq!(None, text.upload.thumbnail);
+    assert_eq!(text.url, text.thumbnail_url);
+    let res = http.get(&text.url).send().await?;
+    assert_eq!(
+        "attachment; filename=\"notes.txt\"",
+        res.headers()["content-disposition"]
+    );
+
+    // unsupported type, too large file and upload without login are rejected
+    let params = UploadFileParams {
+        filename: "image.svg".to_string(),
+        data: b"<svg></svg>".to_vec(),
+    };
+    let err = alpha.upload_file(params).await.unwrap_err();
+    assert_eq!(ErrorType::Validation, err.error_type());
+    let params = UploadFileParams {
+        filename: "large.txt".to_string(),
+        data: vec![b'a'; 200_000],
+    };
+    let err = alpha.upload_file(params).await.unwrap_err();
+    assert_eq!(ErrorType::Validation, err.error_type());
+    let client = ApiClient::new(Some(alpha.hostname.clone()));
+    let params = UploadFileParams {
+        filename: "notes.txt".to_string(),
+        data: b"some notes".to_vec(),
+    };
+    let err = client.upload_file(params).await.unwrap_err();
+    assert_eq!(ErrorType::NotLoggedIn, err.error_type());
+
+    // media library, newest first
+    let uploads = client.list_uploads(PaginationParams::default()).await?;
+    assert_eq!(2, uploads.len());
+    assert_eq!(text.upload.id, uploads[0].upload.id);
+    assert_eq!(image.upload.id, uploads[1].upload.id);
+    assert_eq!("alpha", uploads[0].uploader.username);
+
+    // embedded image is federated as attachment
+    let create_params = CreateArticleParams {
+        title: "Gallery".to_string(),
+        text: format!("an image: {}\n", image.markdown()),
+        summary: "create article".to_string(),
+    };
+    let article = alpha.create_article(&create_params).await.unwrap();
+    let json: ApubArticle = http
+        .get(article.article.ap_id.inner().clone())
+        .header("accept", "application/activity+json")
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(1, json.attachment.len());
+    assert_eq!(image.url, json.attachment[0].url.to_string());
+    assert_eq!("image/png", json.attachment[0].media_type);
+    let beta_article = beta
+        .resolve_article(article.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    assert!(beta_article.article.text.contains(&image.url));
+
+    // only the uploader can delete a file
+    let delta = ApiClient::new(Some(alpha.hostname.clone()));
+    let register_params = RegisterUserParams {
+        username: "delta".to_string(),
+        password: "hunter2".to_string(),
+        ..Default::default()
+    };
+    delta.register(register_params).await.unwrap();
+    let err = delta.delete_upload(image.upload.id).await.unwrap_err();
+    assert_eq!(ErrorType::Forbidden, err.error_type());
+    alpha.delete_upload(image.upload.id).await.unwrap();
+    let res = http.get(&image.url).send().await?;
+    assert_eq!(404, res.status().as_u16());
+    let res = http.get(&image.thumbnail_url).send().await?;
+    assert_eq!(404, res.status().as_u16());
+    assert_eq!(
+        1,
+        alpha.list_uploads(PaginationParams::default()).await?.len()
+    );
+
+    // uploads are kept when the account is deleted
+    let params = UploadFileParams {
+        filename: "notes.txt".to_string(),
+        data: b"some notes".to_vec(),
+    };
+    let upload = delta.upload_file(params).await.unwrap();
+    let params = DeleteAccountParams {
+        password: "hunter2".to_string(),
+    };
+    delta.delete_account(&params).await.unwrap();
+    let uploads = alpha.list_uploads(PaginationParams::default()).await?;
+    assert_eq!(upload.upload.id, uploads[0].upload.id);
+    assert_eq!("ghost", uploads[0].uploader.username);
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_templates() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    // create template and article which uses it
+    let create_params = CreateArticleParams {
+        title: "Infobox".to_string(),
+        text: "Name: {{{name|unknown}}} {{Notice}}".to_string(),
+        summary: "create template".to_string(),
+    };
+    let infobox = alpha.create_article(&create_params).await.unwrap();
+    let create_params = CreateArticleParams {
+        title: "Notice".to_string(),
+        text: "This is a notice {{Notice}}".to_string(),
+        summary: "create template".to_string(),
+    };
+    alpha.create_article(&create_params).await.unwrap();
+    let create_params = CreateArticleParams {
+        title: "Ibis".to_string(),
+        text: "{{Infobox|name=Ibis}} {{Missing}}".to_string(),
+        summary: "create article".to_string(),
+    };
+    let article = alpha.create_article(&create_params).await.unwrap();
+
+    // nested templates are included, recursion and missing templates are ignored
+    let params = ResolveTemplatesParams {
+        text: article.article.text.clone(),
+        domain: None,
+    };
+    let mut templates = alpha.resolve_templates(params).await.unwrap();
+    templates.sort_by(|a, b| a.title.cmp(&b.title));
+    assert_eq!(2, templates.len());
+    assert_eq!("Infobox", templates[0].title);
+    assert_eq!(None, templates[0].domain);
+    assert_eq!(infobox.article.text, templates[0].text);
+    assert_eq!("Notice", templates[1].title);
+
+    let usage = alpha.list_template_usage(infobox.article.id).await.unwrap();
+    assert_eq!(1, usage.len());
+    assert_eq!(article.article.id, usage[0].id);
+
+    // usage is still found after the template is moved
+    let move_params = MoveArticleParams {
+        article_id: infobox.article.id,
+        new_title: "Infobox_Software".to_string(),
+    };
+    alpha.move_article(&move_params).await.unwrap();
+    let usage = alpha.list_template_usage(infobox.article.id).await.unwrap();
+    assert_eq!(1, usage.len());
+
+    // remote template is fetched from its instance in the background
+    let text = format!("{{{{Notice@{}}}}}", alpha.hostname);
+    let params = ResolveTemplatesParams { text, domain: None };
+    let templates = beta.resolve_templates(params.clone()).await.unwrap();
+    assert!(templates.is_empty());
+    let templates = RetryFuture::new(
+        || async {
+            match beta.resolve_templates(params.clone()).await {
+                Ok(t) if !t.is_empty() => Ok(t),
+                _ => Err(RetryPolicy::<String>::Retry(None)),
+            }
+        },
+        LinearRetryStrategy::new(),
+    )
+    .await?;
+    assert_eq!(1, templates.len());
+    assert_eq!(Some(alpha.hostname.clone()), templates[0].domain);
+    assert!(templates[0].text.starts_with("This is a notice"));
+
+    // usage is removed when the template call is removed from the text
+    let edit_params = EditArticleParams {
+        article_id: article.article.id,
+        new_text: "No templates here".to_string(),
+        summary: "remove template".to_string(),
+        previous_version_id: article.latest_version,
+        resolve_conflict_id: None,
+    };
+    alpha.edit_article(&edit_params).await.unwrap();
+    let usage = alpha.list_template_usage(infobox.article.id).await.unwrap();
+    assert!(usage.is_empty());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_backlinks_and_wanted_articles() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    let create_params = CreateArticleParams {
+        title: "Target".to_string(),
+        text: "Nothing to see".to_string(),
+        summary: "create article".to_string(),
+    };
+    let target = alpha.create_article(&create_params).await.unwrap();
+    let create_params = CreateArticleParams {
+        title: "Source".to_string(),
+        text: format!(
+            "See [[Target@{0}]], [[Missing@{0}|Label]] and `[[Code@{0}]]`",
+            alpha.hostname
+        ),
+        summary: "create article".to_string(),
+    };
+    let source = alpha.create_article(&create_params).await.unwrap();
+
+    let backlinks = alpha.list_backlinks(target.article.id).await.unwrap();
+    assert_eq!(1, backlinks.len());
+    assert_eq!(source.article.id, backlinks[0].id);
+
+    // missing article is wanted, links inside code are ignored
+    let wanted = alpha
+        .list_wanted_articles(PaginationParams::default())
+        .await
+        .unwrap();
+    assert_eq!(1, wanted.len());
+    assert_eq!("Missing", wanted[0].title);
+    assert_eq!(1, wanted[0].link_count);
+
+    // backlinks are still found after the target is moved
+    let move_params = MoveArticleParams {
+        article_id: target.article.id,
+        new_title: "Moved_Target".to_string(),
+    };
+    alpha.move_article(&move_params).await.unwrap();
+    let backlinks = alpha.list_backlinks(target.article.id).await.unwrap();
+    assert_eq!(1, backlinks.len());
+
+    // article is not wanted anymore once it is created
+    let create_params = CreateArticleParams {
+        title: "Missing".to_string(),
+        text: "Now it exists".to_string(),
+        summary: "create article".to_string(),
+    };
+    alpha.create_article(&create_params).await.unwrap();
+    let wanted = alpha
+        .list_wanted_articles(PaginationParams::default())
+        .await
+        .unwrap();
+    assert!(wanted.is_empty());
+
+    // links are removed when the text is edited
+    let edit_params = EditArticleParams {
+        article_id: source.article.id,
+        new_text: "No links here".to_string(),
+        summary: "remove links".to_string(),
+        previous_version_id: source.latest_version,
+        resolve_conflict_id: None,
+    };
+    let source = alpha
+        .edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+    let backlinks = alpha.list_backlinks(target.article.id).await.unwrap();
+    assert!(backlinks.is_empty());
+
+    // repeated links are only stored once, even if there are too many for a single query
+    let edit_params = EditArticleParams {
+        article_id: source.article.id,
+        new_text: format!("[[Moved_Target]] {}", "[[X]] ".repeat(25_000)),
+        summary: "many links".to_string(),
+        previous_version_id: source.latest_version,
+        resolve_conflict_id: None,
+    };
+    alpha.edit_article(&edit_params).await.unwrap();
+    let backlinks = alpha.list_backlinks(target.article.id).await.unwrap();
+    assert_eq!(1, backlinks.len());
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_local_links_and_missing_links() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    let create_params = CreateArticleParams {
+        title: "Existing".to_string(),
+        text: "Nothing to see".to_string(),
+        summary: "create article".to_string(),
+    };
+    let existing = alpha.create_article(&create_params).await.unwrap();
+    let create_params = CreateArticleParams {
+        title: "Source".to_string(),
+        text: "Links to [[Existing]] and [[Not existing|missing article]]".to_string(),
+        summary: "create article".to_string(),
+    };
+    let source = alpha.create_article(&create_params).await.unwrap();
+
+    // links without domain point to the same instance
+    let backlinks = alpha.list_backlinks(existing.article.id).await.unwrap();
+    assert_eq!(1, backlinks.len());
+    assert_eq!(source.article.id, backlinks[0].id);
+    let wanted = alpha
+        .list_wanted_articles(PaginationParams::default())
+        .await
+        .unwrap();
+    assert_eq!(1, wanted.len());
+    assert_eq!("Not_existing", wanted[0].title);
+
+    let params = FindMissingLinksParams {
+        text: source.article.text.clone(),
+        domain: None,
+    };
+    let missing = alpha.find_missing_links(params).await.unwrap();
+    assert_eq!(1, missing.len());
+    assert_eq!("Not_existing", missing[0].title);
+    assert_eq!(None, missing[0].domain);
+
+    // on another instance links resolve against the domain of the article
+    let params = FindMissingLinksParams {
+        text: source.article.text.clone(),
+        domain: Some(alpha.hostname.clone()),
+    };
+    let missing = beta.find_missing_links(params).await.unwrap();
+    assert_eq!(2, missing.len());
+    beta.resolve_article(source.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    beta.resolve_article(existing.article.ap_id.inner().clone())
+        .await
+        .unwrap();
+    let params = FindMissingLinksParams {
+        text: source.article.text.clone(),
+        domain: Some(alpha.hostname.clone()),
+    };
+    let missing = beta.find_missing_links(params).await.unwrap();
+    assert_eq!(1, missing.len());
+    assert_eq!(Some(alpha.hostname.clone()), missing[0].domain);
+
+    // previous titles of moved articles and links with explicit local domain are not missing
+    let move_params = MoveArticleParams {
+        article_id: existing.article.id,
+        new_title: "Renamed".to_string(),
+    };
+    alpha.move_article(&move_params).await.unwrap();
+    let params = FindMissingLinksParams {
+        text: format!(
+            "[[Existing]] [[Renamed]] [[Renamed@{}]] [[Other]]",
+            alpha.hostname
+        ),
+        domain: None,
+    };
+    let missing = alpha.find_missing_links(params).await.unwrap();
+    assert_eq!(1, missing.len());
+    assert_eq!("Other", missing[0].title);
+
+    // markdown links to local articles are stored as article links
+    let edit_params = EditArticleParams {
+        article_id: source.article.id,
+        new_text: format!(
+            "See [renamed article](http://{}/article/Renamed)",
+            alpha.hostname
+        ),
+        summary: "add link".to_string(),
+        previous_version_id: source.latest_version,
+        resolve_conflict_id: None,
+    };
+    let edited = alpha
+        .edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+    assert_eq!("See [[Renamed|renamed article]]\n", edited.article.text);
+
+    TestData::stop(alpha, beta, gamma)
+}
+
+#[tokio::test]
+async fn api_test_categories() -> Result<()> {
+    let TestData(alpha, beta, gamma) = TestData::start(false).await;
+
+    let create_params = CreateArticleParams {
+        title: "Ibis".to_string(),
+        text: "Wiki software\n\n[[Category:Software/Wiki software]]".to_string(),
+        summary: "create article".to_string(),
+    };
+    let ibis = alpha.create_article(&create_params).await.unwrap();
+    let create_params = CreateArticleParams {
+        title: "Rust".to_string(),
+        text: "Programming language [[Category:Software]] `[[Category:Code]]`".to_string(),
+        summary: "create article".to_string(),
+    };
+    let rust = alpha.create_article(&create_params).await.unwrap();
+
+    // top level category counts articles in subcategories
+    let categories = alpha
+        .list_categories(ListCategoriesParams::default())
+        .await
+        .unwrap();
+    assert_eq!(1, categories.len());
+    assert_eq!("Software", categories[0].name);
+    assert_eq!(2, categories[0].article_count);
+    let params = ListCategoriesParams {
+        parent: Some("Software".to_string()),
+    };
+    let categories = alpha.list_categories(params).await.unwrap();
+    assert_eq!(1, categories.len());
+    assert_eq!("Software/Wiki_software", categories[0].name);
+    assert_eq!(1, categories[0].article_count);
+
+    // filter articles and search results by category, including subcategories
+    let list_params = ListArticlesParams {
+        category: Some("Software".to_string()),
+        ..Default::default()
+    };
+    let articles = alpha.list_articles(list_params).await.unwrap();
+    assert_eq!(2, articles.len());
+    let list_params = ListArticlesParams {
+        category: Some("Software/Wiki software".to_string()),
+        ..Default::default()
+    };
+    let articles = alpha.list_articles(list_params).await.unwrap();
+    assert_eq!(1, articles.len());
+    assert_eq!(ibis.article.id, articles[0].id);
+    let search_params = SearchArticleParams {
+        query: "software".to_string(),
+        category: Some("Software/Wiki_software".to_string()),
+        ..Default::default()
+    };
+    let search_res = alpha.search(&search_params).await.unwrap();
+    assert_eq!(1, search_res.len());
+    assert_eq!(ibis.article.id, search_res[0].id);
+
+    // categories are federated as hashtags
+    let json: ApubArticle = reqwest::Client::new()
+        .get(rust.article.ap_id.inner().clone())
+        .header("accept", "application/activity+json")
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(1, json.tag.len());
+    assert_eq!("#Software", json.tag[0].name);
+
+    // category is removed when the text is edited
+    let edit_params = EditArticleParams {
+        article_id: rust.article.id,
+        new_text: "Programming language".to_string(),
+        summary: "remove category".to_string(),
+        previous_version_id: rust.latest_version,
+        resolve_conflict_id: None,
+    };
+    alpha
+        .edit_article_without_conflict(&edit_params)
+        .await
+        .unwrap();
+    let list_params = ListArticlesParams {
+        category: Some("Software".to_string()),
+        ..Default::default()
+    };
+    let articles = alpha.list_articles(list_params).await.unwrap();
+    assert_eq!(1, articles.len());
+
+    TestData::stop(alpha, beta, gamma)
+}
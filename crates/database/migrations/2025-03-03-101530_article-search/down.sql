@@ -0,0 +1 @@
+drop index article_search_idx;
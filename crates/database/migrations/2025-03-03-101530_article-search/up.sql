@@ -0,0 +1,5 @@
+-- expression must be identical to SEARCH_VECTOR in impls/article.rs, otherwise the index is not used
+create index article_search_idx on article using gin ((
+    setweight(to_tsvector('english', replace(title, '_', ' ')), 'A') ||
+    setweight(to_tsvector('english', text), 'B')
+));
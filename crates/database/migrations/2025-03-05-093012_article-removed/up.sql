@@ -0,0 +1 @@
+alter table article add column removed bool not null default false;
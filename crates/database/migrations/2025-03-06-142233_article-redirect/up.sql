@@ -0,0 +1,7 @@
+-- previous titles of moved articles, so that old links keep working
+create table article_redirect(
+    article_id int references article ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    title text NOT NULL,
+    primary key(article_id, title));
+
+create index article_redirect_title_idx on article_redirect(title);
@@ -0,0 +1,4 @@
+-- domains which are blocked for federation, managed by admins at runtime
+create table instance_block(
+    domain text primary key,
+    published timestamptz not null default now());
@@ -0,0 +1 @@
+alter table person add column banned bool not null default false;
@@ -0,0 +1,34 @@
+CREATE OR REPLACE FUNCTION instance_stats_local_user_delete ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        instance_stats sa
+    SET
+        users = users - 1
+    FROM
+        instance s
+    WHERE
+        sa.instance_id = s.id;
+    RETURN NULL;
+END
+$$;
+
+CREATE OR REPLACE FUNCTION instance_stats_article_delete ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        instance_stats ia
+    SET
+        articles = articles - 1
+    FROM
+        instance i
+    WHERE
+        ia.instance_id = i.id;
+    RETURN NULL;
+END
+$$;
+
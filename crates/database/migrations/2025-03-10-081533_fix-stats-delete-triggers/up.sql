@@ -0,0 +1,28 @@
+-- The previous functions referenced a nonexistent column instance_stats.instance_id, so
+-- deleting local users or articles failed.
+CREATE OR REPLACE FUNCTION instance_stats_local_user_delete ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        instance_stats
+    SET
+        users = users - 1;
+    RETURN NULL;
+END
+$$;
+
+CREATE OR REPLACE FUNCTION instance_stats_article_delete ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        instance_stats
+    SET
+        articles = articles - 1;
+    RETURN NULL;
+END
+$$;
+
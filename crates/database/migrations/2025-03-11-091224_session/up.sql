@@ -0,0 +1,11 @@
+-- Login tokens reference a session, existing tokens without session become invalid.
+CREATE TABLE session (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    user_agent text,
+    published timestamptz NOT NULL DEFAULT now(),
+    last_used timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_session_local_user ON session (local_user_id);
+
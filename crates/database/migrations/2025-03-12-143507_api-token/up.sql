@@ -0,0 +1,12 @@
+CREATE TABLE api_token (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name text NOT NULL,
+    token_hash text NOT NULL UNIQUE,
+    scope text NOT NULL CHECK (scope IN ('read', 'edit', 'admin')),
+    published timestamptz NOT NULL DEFAULT now(),
+    last_used timestamptz
+);
+
+CREATE INDEX idx_api_token_local_user ON api_token (local_user_id);
+
@@ -0,0 +1,6 @@
+ALTER TABLE person
+    DROP COLUMN bot;
+
+ALTER TABLE local_user
+    DROP COLUMN hide_bot_edits;
+
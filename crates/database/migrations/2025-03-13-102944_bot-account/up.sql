@@ -0,0 +1,6 @@
+ALTER TABLE person
+    ADD COLUMN bot bool NOT NULL DEFAULT FALSE;
+
+ALTER TABLE local_user
+    ADD COLUMN hide_bot_edits bool NOT NULL DEFAULT FALSE;
+
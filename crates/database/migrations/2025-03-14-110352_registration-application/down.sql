@@ -0,0 +1,4 @@
+DROP TABLE registration_application;
+
+ALTER TABLE local_user
+    DROP COLUMN approved;
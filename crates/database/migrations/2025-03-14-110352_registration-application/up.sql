@@ -0,0 +1,9 @@
+ALTER TABLE local_user
+    ADD COLUMN approved bool NOT NULL DEFAULT TRUE;
+
+CREATE TABLE registration_application (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL UNIQUE,
+    answer text,
+    published timestamptz NOT NULL DEFAULT now()
+);
@@ -0,0 +1,7 @@
+DROP TABLE email_token;
+
+ALTER TABLE local_user
+    DROP COLUMN email,
+    DROP COLUMN email_verified,
+    DROP COLUMN email_notifications,
+    DROP COLUMN last_digest_sent;
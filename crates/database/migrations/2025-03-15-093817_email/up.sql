@@ -0,0 +1,19 @@
+ALTER TABLE local_user
+    ADD COLUMN email text,
+    ADD COLUMN email_verified bool NOT NULL DEFAULT FALSE,
+    ADD COLUMN email_notifications bool NOT NULL DEFAULT FALSE,
+    ADD COLUMN last_digest_sent timestamptz;
+
+-- Only verified addresses are unique, so that nobody can block an address by entering it
+-- without having access to it.
+CREATE UNIQUE INDEX idx_local_user_email_verified ON local_user (email)
+WHERE
+    email_verified;
+
+CREATE TABLE email_token (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    token_hash text NOT NULL UNIQUE,
+    kind text NOT NULL CHECK (kind IN ('verify_email', 'reset_password')),
+    published timestamptz NOT NULL DEFAULT now()
+);
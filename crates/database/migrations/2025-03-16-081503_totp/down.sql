@@ -0,0 +1,5 @@
+ALTER TABLE local_user
+    DROP COLUMN totp_secret,
+    DROP COLUMN totp_enabled,
+    DROP COLUMN totp_recovery_codes,
+    DROP COLUMN totp_last_step;
@@ -0,0 +1,5 @@
+ALTER TABLE local_user
+    ADD COLUMN totp_secret text,
+    ADD COLUMN totp_enabled bool NOT NULL DEFAULT FALSE,
+    ADD COLUMN totp_recovery_codes text[] NOT NULL DEFAULT '{}',
+    ADD COLUMN totp_last_step bigint;
@@ -0,0 +1,5 @@
+DROP TABLE oauth_account;
+
+UPDATE local_user SET password_encrypted = '' WHERE password_encrypted IS NULL;
+
+ALTER TABLE local_user ALTER COLUMN password_encrypted SET NOT NULL;
@@ -0,0 +1,11 @@
+CREATE TABLE oauth_account (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    provider text NOT NULL,
+    subject text NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    UNIQUE (provider, subject)
+);
+
+-- Accounts created through a provider have no password until the user sets one
+ALTER TABLE local_user ALTER COLUMN password_encrypted DROP NOT NULL;
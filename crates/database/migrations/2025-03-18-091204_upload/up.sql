@@ -0,0 +1,12 @@
+CREATE TABLE upload (
+    id serial PRIMARY KEY,
+    uploader_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    filename text NOT NULL UNIQUE,
+    original_name text NOT NULL,
+    content_type text NOT NULL,
+    size bigint NOT NULL,
+    thumbnail text UNIQUE,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_upload_published ON upload (published DESC);
@@ -0,0 +1,10 @@
+-- templates which are transcluded in each article, with the domain of the article instance
+-- if none is given in the template call
+CREATE TABLE template_usage (
+    article_id int REFERENCES article ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    template_title text NOT NULL,
+    template_domain text NOT NULL,
+    PRIMARY KEY (article_id, template_title, template_domain)
+);
+
+CREATE INDEX idx_template_usage_template ON template_usage (template_title, template_domain);
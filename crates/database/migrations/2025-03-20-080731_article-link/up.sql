@@ -0,0 +1,17 @@
+-- links to other articles in the text of each article, used for backlinks and wanted pages
+CREATE TABLE article_link (
+    article_id int REFERENCES article ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    target_title text NOT NULL,
+    target_domain text NOT NULL,
+    PRIMARY KEY (article_id, target_title, target_domain)
+);
+
+CREATE INDEX idx_article_link_target ON article_link (target_title, target_domain);
+
+-- Fill in links of existing articles. Links inside code are not excluded here, this is
+-- corrected with the next edit of the article.
+INSERT INTO article_link
+SELECT DISTINCT article.id, replace(trim(m[1]), ' ', '_'), trim(m[2])
+FROM article,
+    regexp_matches(article.text, '\[\[([a-zA-Z0-9_ ]+)@([^]|[:space:]]+)(\|[^]]*)?\]\]', 'g') AS m
+ON CONFLICT DO NOTHING;
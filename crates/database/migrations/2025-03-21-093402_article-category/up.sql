@@ -0,0 +1,9 @@
+-- categories of each article, subcategories are stored with the full name like `Science/Physics`
+CREATE TABLE article_category (
+    article_id int REFERENCES article ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name text NOT NULL,
+    PRIMARY KEY (article_id, name)
+);
+
+-- text_pattern_ops allows prefix search for subcategories with `like`
+CREATE INDEX idx_article_category_name ON article_category (name text_pattern_ops);
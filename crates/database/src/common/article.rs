@@ -4,10 +4,13 @@ use super::{
     newtypes::{ArticleId, ConflictId, EditId, InstanceId, PersonId},
     user::Person,
 };
-use crate::DbUrl;
-use anyhow::anyhow;
+use crate::{
+    DbUrl,
+    error::{ApiError, ErrorType},
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use uuid::Uuid;
 #[cfg(feature = "ssr")]
 use {
@@ -43,6 +46,31 @@ pub struct ArticleView {
     pub following: bool,
 }
 
+/// Search result which only contains a snippet of the article text around the matched terms.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(Queryable))]
+#[cfg_attr(feature = "ssr", diesel(check_for_backend(diesel::pg::Pg)))]
+pub struct ArticleSearchView {
+    pub id: ArticleId,
+    pub title: String,
+    pub ap_id: DbUrl,
+    pub local: bool,
+    /// Matched terms are wrapped in `<mark>` tags. Other html in the text is not escaped.
+    pub snippet: String,
+}
+
+impl From<Article> for ArticleSearchView {
+    fn from(article: Article) -> Self {
+        Self {
+            id: article.id,
+            title: article.title,
+            ap_id: article.ap_id,
+            local: article.local,
+            snippet: String::new(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "ssr", derive(Queryable, Selectable, Identifiable))]
 #[cfg_attr(feature = "ssr", diesel(table_name = article, check_for_backend(diesel::pg::Pg), belongs_to(DbInstance, foreign_key = instance_id)))]
@@ -56,6 +84,8 @@ pub struct Article {
     pub protected: bool,
     pub approved: bool,
     pub published: DateTime<Utc>,
+    /// Set when an admin deletes a local article, so that it can be served as tombstone
+    pub removed: bool,
 }
 
 /// Represents a single change to the article.
@@ -111,6 +141,14 @@ impl EditVersion {
     }
 }
 
+impl FromStr for EditVersion {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(EditVersion(Uuid::parse_str(s)?))
+    }
+}
+
 #[cfg(feature = "ssr")]
 impl Default for EditVersion {
     fn default() -> Self {
@@ -129,10 +167,11 @@ pub struct ApiConflict {
     pub published: DateTime<Utc>,
 }
 
-pub fn can_edit_article(article: &Article, is_admin: bool) -> Result<(), anyhow::Error> {
+pub fn can_edit_article(article: &Article, is_admin: bool) -> Result<(), ApiError> {
     if article.protected && !article.local && !is_admin {
-        return Err(anyhow!(
-            "Article is protected, only admins on origin instance can edit".to_string()
+        return Err(ApiError::new(
+            ErrorType::Forbidden,
+            "Article is protected, only admins on origin instance can edit",
         ));
     }
     Ok(())
@@ -0,0 +1,89 @@
+use super::template::code_ranges;
+use serde::{Deserialize, Serialize};
+
+/// Articles are added to a category with `[[Category:Name]]` anywhere in the text.
+/// Subcategories are separated by slash, like `[[Category:Science/Physics]]`.
+pub const CATEGORY_PREFIX: &str = "[[Category:";
+
+/// Maximum length of a category name, including parent categories.
+pub const MAX_CATEGORY_LENGTH: usize = 200;
+
+/// Maximum number of categories which are stored for a single article.
+pub const MAX_CATEGORIES: usize = 100;
+
+/// Category with the number of articles in it, including articles in subcategories.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CategoryView {
+    /// Full name including parent categories, like `Science/Physics`
+    pub name: String,
+    pub article_count: i64,
+}
+
+/// Bring category name into the form in which it is stored: spaces are replaced by underscores
+/// and surrounding whitespace of each level is removed. Returns `None` for invalid names.
+pub fn normalize_category(name: &str) -> Option<String> {
+    let levels: Vec<_> = name
+        .split('/')
+        .map(|level| level.trim().replace(' ', "_"))
+        .collect();
+    let valid = levels.iter().all(|level| {
+        !level.is_empty()
+            && level
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-'))
+    });
+    let name = levels.join("/");
+    (valid && name.len() <= MAX_CATEGORY_LENGTH).then_some(name)
+}
+
+/// Parent of a subcategory, or `None` for top level categories.
+pub fn parent_category(name: &str) -> Option<&str> {
+    name.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// Find all categories of the article, in the order in which they appear in the text. Anything
+/// inside code is skipped.
+pub fn parse_categories(text: &str) -> Vec<String> {
+    let code = code_ranges(text);
+    let mut categories: Vec<String> = vec![];
+    let mut i = 0;
+    while let Some(offset) = text[i..].find(CATEGORY_PREFIX) {
+        let start = i + offset;
+        if let Some(code) = code.iter().find(|c| c.contains(&start)) {
+            i = code.end;
+            continue;
+        }
+        let content_start = start + CATEGORY_PREFIX.len();
+        let Some(length) = text[content_start..].find("]]") else {
+            break;
+        };
+        i = content_start + length + 2;
+        if let Some(name) = normalize_category(&text[content_start..content_start + length]) {
+            if !categories.contains(&name) {
+                categories.push(name);
+            }
+        }
+    }
+    categories
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_categories() {
+        let text = "Text [[Category:Science / Physics]]\n\n[[Category:Wiki software]] [[Category:Science/Physics]]";
+        assert_eq!(
+            vec!["Science/Physics".to_string(), "Wiki_software".to_string()],
+            parse_categories(text)
+        );
+
+        // invalid names and categories inside code are ignored
+        let text = "[[Category:]] [[Category:a//b]] [[Category:<b>]] `[[Category:Code]]` [[Category:Real]]";
+        assert_eq!(vec!["Real".to_string()], parse_categories(text));
+
+        assert_eq!(Some("Science"), parent_category("Science/Physics"));
+        assert_eq!(None, parent_category("Science"));
+    }
+}
@@ -10,7 +10,7 @@ use smart_default::SmartDefault;
 use url::Url;
 #[cfg(feature = "ssr")]
 use {
-    crate::schema::instance,
+    crate::schema::{instance, instance_block},
     diesel::{Identifiable, Queryable, Selectable},
     doku::Document,
 };
@@ -56,6 +56,15 @@ pub struct InstanceFollow {
     pub pending: bool,
 }
 
+/// Domain which is blocked for federation by the local admin.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(Queryable, Selectable))]
+#[cfg_attr(feature = "ssr", diesel(table_name = instance_block, check_for_backend(diesel::pg::Pg)))]
+pub struct InstanceBlock {
+    pub domain: String,
+    pub published: DateTime<Utc>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct InstanceWithArticles {
     pub instance: Instance,
@@ -76,6 +85,23 @@ pub struct Options {
     #[default = false]
     #[cfg_attr(feature = "ssr", doku(example = "false"))]
     pub article_approval: bool,
+    /// Whether new users need to be approved by admins before they can login
+    #[default = false]
+    #[cfg_attr(feature = "ssr", doku(example = "false"))]
+    pub registration_approval: bool,
+    /// Question which new users need to answer when registration approval is enabled
+    #[default(None)]
+    #[cfg_attr(feature = "ssr", doku(example = "Why do you want to join this wiki?"))]
+    pub registration_question: Option<String>,
+    /// Whether new users need to solve a captcha
+    #[default = false]
+    #[cfg_attr(feature = "ssr", doku(example = "false"))]
+    pub registration_captcha: bool,
+    /// Whether users can login with username and password. If disabled, only login with
+    /// `oauth_providers` is possible.
+    #[default = true]
+    #[cfg_attr(feature = "ssr", doku(example = "true"))]
+    pub password_login: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -86,4 +112,14 @@ pub struct SiteView {
     pub config: Options,
     pub admin: Person,
     pub instance: Instance,
+    /// Whether an SMTP server is configured, needed for email verification and password reset
+    pub email_enabled: bool,
+    pub oauth_providers: Vec<OAuthProvider>,
+}
+
+/// Public information about an OpenID Connect provider which can be used for login.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OAuthProvider {
+    pub id: String,
+    pub display_name: String,
 }
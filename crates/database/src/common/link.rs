@@ -0,0 +1,214 @@
+use super::template::code_ranges;
+#[cfg(feature = "ssr")]
+use diesel::Queryable;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of different links which are checked for a single text.
+pub const MAX_CHECKED_LINKS: usize = 500;
+
+/// Link to another article in markdown text, like `[[Title]]`, `[[Title@example.com]]` or
+/// `[[Title@example.com|Label]]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArticleLink {
+    /// Title with spaces replaced by underscores, as it is stored for articles
+    pub title: String,
+    /// Links without domain refer to the instance of the article which contains them
+    pub domain: Option<String>,
+    /// Custom label if given, otherwise the title as written in the link
+    pub label: String,
+}
+
+impl ArticleLink {
+    /// Parse the content between the double square brackets of a link.
+    pub fn parse(content: &str) -> Option<Self> {
+        let (target, label) = match content.split_once('|') {
+            Some((target, label)) => (target, Some(label)),
+            None => (content, None),
+        };
+        let (title, domain) = match target.split_once('@') {
+            Some((title, domain)) => (title, Some(domain.trim().to_string())),
+            None => (target, None),
+        };
+        let label = label.unwrap_or(title).trim();
+        let title = title.trim().replace(' ', "_");
+        let valid_title =
+            !title.is_empty() && title.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let valid_domain = domain
+            .as_ref()
+            .is_none_or(|d| !d.is_empty() && !d.contains(char::is_whitespace));
+        if !valid_title || !valid_domain || label.is_empty() {
+            return None;
+        }
+        Some(ArticleLink {
+            title,
+            domain,
+            label: label.to_string(),
+        })
+    }
+
+    /// Target of the link, where links without domain point to the instance of the article
+    /// which contains them.
+    pub fn target(&self, parent_domain: &Option<String>) -> LinkTarget {
+        LinkTarget {
+            title: self.title.clone(),
+            domain: self.domain.clone().or_else(|| parent_domain.clone()),
+        }
+    }
+}
+
+/// Article which a link points to, with `None` domain meaning the local instance.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinkTarget {
+    pub title: String,
+    pub domain: Option<String>,
+}
+
+/// Find all article links in the text. Links inside code are skipped, just like they are
+/// not rendered.
+pub fn parse_article_links(text: &str) -> Vec<ArticleLink> {
+    let code = code_ranges(text);
+    let mut links = vec![];
+    let mut i = 0;
+    while let Some(offset) = text[i..].find("[[") {
+        let start = i + offset;
+        if let Some(code) = code.iter().find(|c| c.contains(&start)) {
+            i = code.end;
+            continue;
+        }
+        let Some(length) = text[start + 2..].find("]]") else {
+            break;
+        };
+        let end = start + 2 + length;
+        match ArticleLink::parse(&text[start + 2..end]) {
+            Some(link) => {
+                links.push(link);
+                i = end + 2;
+            }
+            None => i = start + 2,
+        }
+    }
+    links
+}
+
+/// Rewrite markdown links to articles on the local instance, like
+/// `[Label](https://example.com/article/Title)`, into article links like `[[Title|Label]]`. Full
+/// urls break if the instance changes its domain, and cant be checked for missing articles.
+/// Other links and links inside code are kept as they are.
+///
+/// If the edited article belongs to another instance, the local domain is added to the link so
+/// that it still points here.
+pub fn rewrite_local_links(text: &str, local_domain: &str, article_local: bool) -> String {
+    let code = code_ranges(text);
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut i = 0;
+    while let Some(offset) = text[i..].find("](") {
+        let start = i + offset;
+        i = start + 2;
+        if code.iter().any(|c| c.contains(&start)) {
+            continue;
+        }
+        let Some(url_length) = text[i..].find(')') else {
+            break;
+        };
+        let url = &text[i..i + url_length];
+        let Some(label_start) = text[last..start].rfind('[').map(|p| last + p) else {
+            continue;
+        };
+        let label = &text[label_start + 1..start];
+        let is_image = text[..label_start].ends_with('!');
+        let target = ["http://", "https://"]
+            .iter()
+            .find_map(|protocol| url.strip_prefix(protocol))
+            .and_then(|url| url.strip_prefix(local_domain))
+            .and_then(|url| url.strip_prefix("/article/"));
+        let Some(target) = target.filter(|_| !is_image && !label.contains(['[', ']', '\n'])) else {
+            continue;
+        };
+        let target = if article_local || target.contains('@') {
+            target.to_string()
+        } else {
+            format!("{target}@{local_domain}")
+        };
+        let Some(link) = ArticleLink::parse(&format!("{target}|{label}")) else {
+            continue;
+        };
+        out.push_str(&text[last..label_start]);
+        if link.label.replace(' ', "_") == link.title {
+            out.push_str(&format!("[[{target}]]"));
+        } else {
+            out.push_str(&format!("[[{target}|{}]]", link.label));
+        }
+        last = i + url_length + 1;
+        i = last;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Local article which doesn't exist yet, but is linked from other articles.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(Queryable))]
+#[cfg_attr(feature = "ssr", diesel(check_for_backend(diesel::pg::Pg)))]
+pub struct WantedArticle {
+    pub title: String,
+    /// Number of articles which link to this title
+    pub link_count: i64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_article_links() {
+        let text = "See [[Title@example.com]] and [[Other title@ibis.wiki|Label]].";
+        let links = parse_article_links(text);
+        assert_eq!(2, links.len());
+        assert_eq!("Title", links[0].title);
+        assert_eq!(Some("example.com".to_string()), links[0].domain);
+        assert_eq!("Title", links[0].label);
+        assert_eq!("Other_title", links[1].title);
+        assert_eq!(Some("ibis.wiki".to_string()), links[1].domain);
+        assert_eq!("Label", links[1].label);
+
+        // links without domain point to the instance of the article
+        let links = parse_article_links("[[Local title]] and [[Local|Label with @]]");
+        assert_eq!(2, links.len());
+        assert_eq!("Local_title", links[0].title);
+        assert_eq!("Local title", links[0].label);
+        assert_eq!(None, links[0].domain);
+        assert_eq!("Label with @", links[1].label);
+        let parent = Some("example.com".to_string());
+        assert_eq!(parent, links[1].target(&parent).domain);
+        assert_eq!(None, links[1].target(&None).domain);
+
+        // invalid links and links inside code are ignored
+        let text = "[[not/valid@example.com]] [[ ]] `[[Inline@example.com]]`\n```\n[[Fenced@example.com]]\n```\n[[Real@example.com]] [[Unclosed@example.com";
+        let links = parse_article_links(text);
+        assert_eq!(1, links.len());
+        assert_eq!("Real", links[0].title);
+    }
+
+    #[test]
+    fn test_rewrite_local_links() {
+        let text = "[Title](https://ibis.wiki/article/Title) [Other label](http://ibis.wiki/article/Other_title) [Remote](https://ibis.wiki/article/Remote@example.com)";
+        assert_eq!(
+            "[[Title]] [[Other_title|Other label]] [[Remote@example.com]]",
+            rewrite_local_links(text, "ibis.wiki", true)
+        );
+        // article on another instance needs the local domain in links
+        assert_eq!(
+            "see [[Title@ibis.wiki|here]]",
+            rewrite_local_links(
+                "see [here](https://ibis.wiki/article/Title)",
+                "ibis.wiki",
+                false
+            )
+        );
+
+        // other links, images and code are kept
+        let text = "[Other](https://example.com/article/Title) [History](https://ibis.wiki/article/Title/history) ![Image](https://ibis.wiki/article/Title) `[Code](https://ibis.wiki/article/Title)` [Unclosed](https://ibis.wiki/article/Title";
+        assert_eq!(text, rewrite_local_links(text, "ibis.wiki", true));
+    }
+}
@@ -1,21 +1,33 @@
 pub mod article;
+pub mod category;
 pub mod comment;
 pub mod instance;
+pub mod link;
 pub mod newtypes;
 pub mod notifications;
+pub mod template;
+pub mod upload;
 pub mod user;
 pub mod utils;
 
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use url::Url;
 
 pub const MAIN_PAGE_NAME: &str = "Main_Page";
 
+/// Default number of items per page for paginated lists.
+pub const DEFAULT_PAGE_LIMIT: i64 = 20;
+
 pub static AUTH_COOKIE: &str = "auth";
 
 #[derive(Clone, Debug)]
 pub struct Auth(pub Option<String>);
 
+/// IP of the client, which is passed on with api requests during server side rendering.
+#[derive(Clone, Copy, Debug)]
+pub struct ClientIp(pub IpAddr);
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SuccessResponse {
     success: bool,
@@ -27,7 +39,15 @@ impl Default for SuccessResponse {
     }
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default)]
+pub struct PaginationParams {
+    /// Page number starting at 1
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ResolveObjectParams {
+    /// Either the ap_id of the object, or a webfinger handle like `acct:name@example.com`
     pub id: Url,
 }
@@ -2,7 +2,7 @@ use super::{
     article::{Article, Conflict, Edit},
     comment::Comment,
     newtypes::ArticleNotifId,
-    user::Person,
+    user::{Person, RegistrationApplication},
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,7 @@ pub enum ApiNotification {
     ArticleApprovalRequired(Article),
     Comment(ArticleNotifId, Comment, Person, Article),
     Edit(ArticleNotifId, Edit, Person, Article),
+    RegistrationApplication(RegistrationApplication, Person),
 }
 
 impl ApiNotification {
@@ -24,6 +25,7 @@ impl ApiNotification {
             ArticleApprovalRequired(a) => &a.published,
             Comment(_, c, _, _) => &c.published,
             Edit(_, e, _, _) => &e.published,
+            RegistrationApplication(r, _) => &r.published,
         }
     }
 }
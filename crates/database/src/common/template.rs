@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, ops::Range};
+
+/// Templates can include other templates up to this depth, deeper calls are not expanded.
+pub const MAX_TEMPLATE_DEPTH: usize = 10;
+
+/// Maximum number of different templates which are resolved for a single article.
+pub const MAX_TEMPLATES: usize = 50;
+
+/// Text of an article which is transcluded by other articles. Title and domain are exactly as
+/// in the template call, with `None` domain meaning the local instance.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Template {
+    pub title: String,
+    pub domain: Option<String>,
+    pub text: String,
+}
+
+/// Usage of a template in article text, like `{{Infobox|name=Ibis|Wiki software}}` or
+/// `{{Notice@example.com}}`. Parameters without name are numbered starting from 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateCall {
+    /// Position of the call in the text, including braces
+    pub range: Range<usize>,
+    pub title: String,
+    pub domain: Option<String>,
+    pub params: HashMap<String, String>,
+}
+
+impl TemplateCall {
+    /// Calls without domain refer to the same instance as the article which contains them.
+    pub fn domain_or(&self, parent_domain: &Option<String>) -> Option<String> {
+        self.domain.clone().or_else(|| parent_domain.clone())
+    }
+
+    fn parse(content: &str, range: Range<usize>) -> Option<Self> {
+        let mut parts = split_params(content).into_iter();
+        let name = parts.next()?.trim();
+        let (title, domain) = match name.split_once('@') {
+            Some((title, domain)) => (title, Some(domain.trim().to_string())),
+            None => (name, None),
+        };
+        let title = title.trim().replace(' ', "_");
+        let valid_title =
+            !title.is_empty() && title.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let valid_domain = domain
+            .as_ref()
+            .is_none_or(|d| !d.is_empty() && !d.contains(char::is_whitespace));
+        if !valid_title || !valid_domain {
+            return None;
+        }
+
+        let mut params = HashMap::new();
+        let mut position = 1;
+        for part in parts {
+            match part.split_once('=') {
+                Some((name, value)) if is_param_name(name.trim()) => {
+                    params.insert(name.trim().to_string(), value.trim().to_string());
+                }
+                _ => {
+                    params.insert(position.to_string(), part.trim().to_string());
+                    position += 1;
+                }
+            }
+        }
+        Some(TemplateCall {
+            range,
+            title,
+            domain,
+            params,
+        })
+    }
+}
+
+/// Find all template calls in the text, without those nested in parameters of other calls.
+/// Parameter placeholders like `{{{name}}}` and anything inside code are skipped.
+pub fn parse_template_calls(text: &str) -> Vec<TemplateCall> {
+    let bytes = text.as_bytes();
+    let code = code_ranges(text);
+    let mut calls = vec![];
+    let mut i = 0;
+    while let Some(offset) = text[i..].find("{{") {
+        let start = i + offset;
+        if let Some(code) = code.iter().find(|c| c.contains(&start)) {
+            i = code.end;
+            continue;
+        }
+        if bytes[start..].starts_with(b"{{{") {
+            i = skip_placeholder(bytes, start).unwrap_or(text.len());
+            continue;
+        }
+        let Some(end) = closing_braces(bytes, start) else {
+            break;
+        };
+        match TemplateCall::parse(&text[start + 2..end - 2], start..end) {
+            Some(call) => {
+                calls.push(call);
+                i = end;
+            }
+            None => i = start + 2,
+        }
+    }
+    calls
+}
+
+/// Returns the position after the `}}` which closes the call starting at `start`.
+fn closing_braces(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest.starts_with(b"{{{") {
+            i = skip_placeholder(bytes, i)?;
+        } else if rest.starts_with(b"{{") {
+            depth += 1;
+            i += 2;
+        } else if rest.starts_with(b"}}") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+fn skip_placeholder(bytes: &[u8], start: usize) -> Option<usize> {
+    bytes[start + 3..]
+        .windows(3)
+        .position(|w| w == b"}}}")
+        .map(|p| start + 3 + p + 3)
+}
+
+/// Byte ranges of fenced code blocks and inline code spans.
+pub(crate) fn code_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut fence_start = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            match fence_start.take() {
+                Some(start) => ranges.push(start..offset + line.len()),
+                None => fence_start = Some(offset),
+            }
+        } else if fence_start.is_none() {
+            let bytes = line.as_bytes();
+            let backticks = |i: usize| bytes[i..].iter().take_while(|b| **b == b'`').count();
+            let mut i = 0;
+            while i < bytes.len() {
+                if bytes[i] != b'`' {
+                    i += 1;
+                    continue;
+                }
+                // code span ends with the same number of backticks as it starts
+                let length = backticks(i);
+                let mut j = i + length;
+                let mut end = None;
+                while j < bytes.len() {
+                    let closing = backticks(j);
+                    if closing == length {
+                        end = Some(j + closing);
+                        break;
+                    }
+                    j += closing.max(1);
+                }
+                match end {
+                    Some(end) => {
+                        ranges.push(offset + i..offset + end);
+                        i = end;
+                    }
+                    None => i += length,
+                }
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = fence_start {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
+
+/// Split on `|`, except inside nested template calls and article links.
+fn split_params(content: &str) -> Vec<&str> {
+    let bytes = content.as_bytes();
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut last = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest.starts_with(b"{{") || rest.starts_with(b"[[") {
+            depth += 1;
+            i += 2;
+        } else if rest.starts_with(b"}}") || rest.starts_with(b"]]") {
+            depth = depth.saturating_sub(1);
+            i += 2;
+        } else {
+            if bytes[i] == b'|' && depth == 0 {
+                parts.push(&content[last..i]);
+                last = i + 1;
+            }
+            i += 1;
+        }
+    }
+    parts.push(&content[last..]);
+    parts
+}
+
+fn is_param_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | ' '))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_template_calls() {
+        let text = "Intro {{Infobox|name=Ibis|Wiki software}} and {{Notice@example.com}}";
+        let calls = parse_template_calls(text);
+        assert_eq!(2, calls.len());
+        assert_eq!("Infobox", calls[0].title);
+        assert_eq!(None, calls[0].domain);
+        assert_eq!(
+            "{{Infobox|name=Ibis|Wiki software}}",
+            &text[calls[0].range.clone()]
+        );
+        assert_eq!(
+            Some("Ibis"),
+            calls[0].params.get("name").map(String::as_str)
+        );
+        assert_eq!(
+            Some("Wiki software"),
+            calls[0].params.get("1").map(String::as_str)
+        );
+        assert_eq!("Notice", calls[1].title);
+        assert_eq!(Some("example.com".to_string()), calls[1].domain);
+
+        // nested calls, links and placeholders in parameters
+        let text = "{{Box|content={{Inner|x={{{x}}}}}|link=[[Title@example.com|Label]]}}";
+        let calls = parse_template_calls(text);
+        assert_eq!(1, calls.len());
+        assert_eq!(text.len(), calls[0].range.end);
+        assert_eq!(
+            Some("{{Inner|x={{{x}}}}}"),
+            calls[0].params.get("content").map(String::as_str)
+        );
+        assert_eq!(
+            Some("[[Title@example.com|Label]]"),
+            calls[0].params.get("link").map(String::as_str)
+        );
+
+        // placeholders, invalid names and unclosed braces are not calls
+        assert!(parse_template_calls("{{{name|default}}}").is_empty());
+        assert!(parse_template_calls("{{ not/valid }} {{Unclosed").is_empty());
+        let calls = parse_template_calls("{{Long title}}");
+        assert_eq!("Long_title", calls[0].title);
+
+        // code is not expanded
+        let text = "`{{Inline}}` ``a ` {{Double}}``\n```\n{{Fenced}}\n```\n{{Real}}";
+        let calls = parse_template_calls(text);
+        assert_eq!(1, calls.len());
+        assert_eq!("Real", calls[0].title);
+    }
+}
@@ -0,0 +1,62 @@
+use super::{
+    newtypes::{PersonId, UploadId},
+    user::Person,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ssr")]
+use {
+    crate::schema::upload,
+    diesel::{Identifiable, Queryable, Selectable},
+};
+
+/// Image or other file which was uploaded to the local instance.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "ssr", diesel(table_name = upload, check_for_backend(diesel::pg::Pg)))]
+pub struct Upload {
+    pub id: UploadId,
+    pub uploader_id: PersonId,
+    /// Random name under which the file is stored and served
+    pub filename: String,
+    /// Name of the file on the device of the uploader
+    pub original_name: String,
+    pub content_type: String,
+    /// File size in bytes
+    pub size: i64,
+    /// Filename of the scaled down version, only available for some image formats
+    pub thumbnail: Option<String>,
+    pub published: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct UploadView {
+    pub upload: Upload,
+    pub uploader: Person,
+    pub url: String,
+    /// Falls back to the full file if there is no thumbnail
+    pub thumbnail_url: String,
+}
+
+impl Upload {
+    pub fn is_image(&self) -> bool {
+        self.content_type.starts_with("image/")
+    }
+}
+
+impl UploadView {
+    /// Markdown which embeds the upload in an article, as image or as link.
+    pub fn markdown(&self) -> String {
+        let name: String = self
+            .upload
+            .original_name
+            .chars()
+            .filter(|c| !matches!(c, '[' | ']' | '(' | ')'))
+            .collect();
+        if self.upload.is_image() {
+            format!("![{name}]({})", self.url)
+        } else {
+            format!("[{name}]({})", self.url)
+        }
+    }
+}
@@ -1,11 +1,16 @@
-use super::newtypes::{LocalUserId, PersonId};
+use super::{
+    article::{Article, EditView},
+    comment::Comment,
+    instance::InstanceFollow,
+    newtypes::{ApiTokenId, LocalUserId, PersonId, RegistrationApplicationId, SessionId},
+};
 use crate::DbUrl;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use url::Url;
 #[cfg(feature = "ssr")]
 use {
-    crate::schema::{local_user, person},
+    crate::schema::{api_token, local_user, person, registration_application, session},
     diesel::{Identifiable, Queryable, Selectable},
 };
 
@@ -23,10 +28,35 @@ pub struct LocalUserView {
 #[cfg_attr(feature = "ssr", diesel(table_name = local_user, check_for_backend(diesel::pg::Pg)))]
 pub struct LocalUser {
     pub id: LocalUserId,
+    /// None for accounts which were created through an OAuth provider, until the user sets a
+    /// password
     #[serde(skip)]
-    pub password_encrypted: String,
+    pub password_encrypted: Option<String>,
     pub person_id: PersonId,
     pub admin: bool,
+    /// Dont show edits from bot accounts in history and notifications
+    pub hide_bot_edits: bool,
+    /// False while the registration application is waiting for review by admins
+    pub approved: bool,
+    /// Optional email address, used for password reset and notifications
+    pub email: Option<String>,
+    pub email_verified: bool,
+    /// Send a daily email with new notifications
+    pub email_notifications: bool,
+    /// When the last notification email was sent
+    #[serde(skip)]
+    pub last_digest_sent: Option<DateTime<Utc>>,
+    /// Base32 encoded secret for two factor authentication, set during setup
+    #[serde(skip)]
+    pub totp_secret: Option<String>,
+    /// If true, login requires a code generated with `totp_secret`
+    pub totp_enabled: bool,
+    /// Hashes of recovery codes which can be used once instead of a totp code
+    #[serde(skip)]
+    pub totp_recovery_codes: Vec<String>,
+    /// Last time step for which a totp code was accepted, so that codes cant be reused
+    #[serde(skip)]
+    pub totp_last_step: Option<i64>,
 }
 
 /// Federation related data from a local or remote user.
@@ -47,6 +77,10 @@ pub struct Person {
     pub local: bool,
     pub display_name: Option<String>,
     pub bio: Option<String>,
+    /// Banned users cant edit articles or write comments
+    pub banned: bool,
+    /// Account is used for automated edits
+    pub bot: bool,
 }
 
 impl Person {
@@ -54,3 +88,112 @@ impl Person {
         Url::parse(&self.inbox_url).expect("can parse inbox url")
     }
 }
+
+/// Login session of a local user. Auth tokens reference a session, and are rejected once the
+/// session is deleted.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "ssr", diesel(table_name = session, check_for_backend(diesel::pg::Pg)))]
+pub struct Session {
+    pub id: SessionId,
+    pub local_user_id: LocalUserId,
+    pub user_agent: Option<String>,
+    pub published: DateTime<Utc>,
+    pub last_used: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SessionView {
+    pub session: Session,
+    /// Session which was used for this request
+    pub current: bool,
+}
+
+/// Permissions of an api token. Each scope includes the permissions of the previous ones.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "ssr",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow)
+)]
+#[cfg_attr(feature = "ssr", diesel(sql_type = diesel::sql_types::Text))]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    /// Read data which is visible to the user
+    Read,
+    /// Edit articles, write comments and follow instances
+    Edit,
+    /// Admin actions, only possible if the token belongs to an admin
+    Admin,
+}
+
+/// Token which lets bots and scripts authenticate as the user, without login and password.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "ssr", diesel(table_name = api_token, check_for_backend(diesel::pg::Pg)))]
+pub struct ApiToken {
+    pub id: ApiTokenId,
+    pub local_user_id: LocalUserId,
+    pub name: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub scope: ApiTokenScope,
+    pub published: DateTime<Utc>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// Answer to the registration question, which needs to be approved by an admin before the new
+/// user can login.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "ssr", diesel(table_name = registration_application, check_for_backend(diesel::pg::Pg)))]
+pub struct RegistrationApplication {
+    pub id: RegistrationApplicationId,
+    pub local_user_id: LocalUserId,
+    pub answer: Option<String>,
+    pub published: DateTime<Utc>,
+}
+
+/// Captcha image which needs to be solved for registration. The id needs to be passed back
+/// together with the answer.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CaptchaResponse {
+    pub id: String,
+    pub svg: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OAuthAuthorizeResponse {
+    /// Login page of the provider where the user needs to be redirected
+    pub url: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TotpSetupResponse {
+    /// Base32 encoded secret, for manual entry in the authenticator app
+    pub secret: String,
+    /// `otpauth://` uri which can be opened by authenticator apps or shown as QR code
+    pub uri: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EnableTotpResponse {
+    /// Each code can be used once instead of a totp code. They are only shown once.
+    pub recovery_codes: Vec<String>,
+}
+
+/// Only the hash of an api token is stored, so the token itself is returned once on creation.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CreateApiTokenResponse {
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+/// Data of a local user which can be downloaded from the account settings.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct UserExport {
+    pub person: Person,
+    pub edits: Vec<EditView>,
+    pub comments: Vec<Comment>,
+    pub followed_instances: Vec<InstanceFollow>,
+    pub followed_articles: Vec<Article>,
+}
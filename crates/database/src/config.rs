@@ -3,6 +3,7 @@ use config::Config;
 use doku::Document;
 use serde::Deserialize;
 use smart_default::SmartDefault;
+use std::net::IpAddr;
 
 #[derive(Debug, Deserialize, PartialEq, Eq, Clone, Document, SmartDefault)]
 #[serde(default)]
@@ -14,6 +15,16 @@ pub struct IbisConfig {
     pub setup: IbisConfigSetup,
     pub federation: IbisConfigFederation,
     pub options: Options,
+    /// Maximum number of requests per IP or per logged in user
+    pub rate_limit: IbisConfigRateLimit,
+    /// SMTP server for sending emails, needed for password reset and email notifications
+    #[default(None)]
+    pub email: Option<IbisConfigEmail>,
+    /// OpenID Connect providers which users can login with
+    #[default(Vec::new())]
+    pub oauth_providers: Vec<IbisConfigOAuthProvider>,
+    /// Storage for images and files which are uploaded by users
+    pub upload: IbisConfigUpload,
 }
 
 impl IbisConfig {
@@ -72,8 +83,172 @@ pub struct IbisConfigFederation {
     #[default(None)]
     #[doku(example = "good.com,friends.org")]
     pub allowlist: Option<String>,
-    /// Comma separated list of instances which are blocked for federation
+    /// Comma separated list of instances which are blocked for federation. Admins can also
+    /// block instances at runtime from the instance settings.
     #[default(None)]
     #[doku(example = "evil.com,bad.org")]
     pub blocklist: Option<String>,
 }
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Document, SmartDefault)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct IbisConfigRateLimit {
+    /// Set to false to disable all rate limits, eg if they are handled by a reverse proxy
+    #[default(true)]
+    #[doku(example = "true")]
+    pub enabled: bool,
+    /// IPs of reverse proxies which are allowed to set the client IP with `X-Forwarded-For`.
+    /// Requests from localhost are always trusted.
+    #[default(Vec::new())]
+    #[doku(example = "10.0.0.1")]
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Maximum number of login attempts in `login_interval` seconds
+    #[default(10)]
+    #[doku(example = "10")]
+    pub login: u32,
+    #[default(60)]
+    #[doku(example = "60")]
+    pub login_interval: u32,
+    /// Maximum number of registrations and captcha requests in `register_interval` seconds
+    #[default(3)]
+    #[doku(example = "3")]
+    pub register: u32,
+    #[default(3600)]
+    #[doku(example = "3600")]
+    pub register_interval: u32,
+    /// Maximum number of article edits and comments in `edit_interval` seconds
+    #[default(30)]
+    #[doku(example = "30")]
+    pub edit: u32,
+    #[default(60)]
+    #[doku(example = "60")]
+    pub edit_interval: u32,
+    /// Maximum number of searches and template lookups in `search_interval` seconds
+    #[default(60)]
+    #[doku(example = "60")]
+    pub search: u32,
+    #[default(60)]
+    #[doku(example = "60")]
+    pub search_interval: u32,
+    /// Maximum number of activities received in the federation inbox in `inbox_interval` seconds
+    #[default(600)]
+    #[doku(example = "600")]
+    pub inbox: u32,
+    #[default(60)]
+    #[doku(example = "60")]
+    pub inbox_interval: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Document, SmartDefault)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct IbisConfigEmail {
+    /// Hostname and port of the SMTP server
+    #[default("localhost:25")]
+    #[doku(example = "localhost:25")]
+    pub smtp_server: String,
+    /// Login name for the SMTP server, if authentication is required
+    #[default(None)]
+    #[doku(example = "ibis")]
+    pub smtp_login: Option<String>,
+    #[default(None)]
+    #[doku(example = "password")]
+    pub smtp_password: Option<String>,
+    /// Sender address for all emails
+    #[default("noreply@example.com")]
+    #[doku(example = "noreply@example.com")]
+    pub smtp_from_address: String,
+    /// Encryption of the SMTP connection, one of `none`, `starttls` or `tls`
+    #[doku(as = "String", example = "starttls")]
+    pub tls_type: EmailTls,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy, Document, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailTls {
+    /// Unencrypted connection, only use this for a local SMTP server
+    None,
+    /// Upgrade to encrypted connection with STARTTLS command
+    #[default]
+    Starttls,
+    /// Connect with TLS directly
+    Tls,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Document, SmartDefault)]
+#[serde(deny_unknown_fields)]
+pub struct IbisConfigOAuthProvider {
+    /// Unique identifier, used in urls and to link accounts. Must not be changed later.
+    #[doku(example = "example")]
+    pub id: String,
+    /// Name shown on the login button
+    #[doku(example = "Example Login")]
+    pub display_name: String,
+    /// Base url of the provider, endpoints are read from `/.well-known/openid-configuration`
+    #[doku(example = "https://login.example.com")]
+    pub issuer: String,
+    #[doku(example = "ibis")]
+    pub client_id: String,
+    #[doku(example = "secret")]
+    pub client_secret: String,
+    #[default("openid profile email")]
+    #[serde(default = "default_oauth_scopes")]
+    #[doku(example = "openid profile email")]
+    pub scopes: String,
+    /// Create a new account on first login if none is linked yet, even if registration is closed.
+    /// New accounts still need to be approved if `registration_approval` is enabled.
+    #[default(true)]
+    #[serde(default = "default_true")]
+    #[doku(example = "true")]
+    pub create_accounts: bool,
+}
+
+fn default_oauth_scopes() -> String {
+    IbisConfigOAuthProvider::default().scopes
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Document, SmartDefault)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct IbisConfigUpload {
+    /// Maximum size of a single upload in bytes
+    #[default(10_000_000)]
+    #[doku(example = "10000000")]
+    pub max_size: usize,
+    /// Maximum width and height of thumbnails in pixels
+    #[default(256)]
+    #[doku(example = "256")]
+    pub thumbnail_size: u32,
+    /// Directory where uploads are stored, unless S3 storage is configured
+    #[default("uploads")]
+    #[doku(example = "uploads")]
+    pub local_path: String,
+    /// Store uploads in an S3 compatible object storage instead of the local directory
+    #[default(None)]
+    pub s3: Option<IbisConfigS3>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Document, SmartDefault)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct IbisConfigS3 {
+    /// Base url of the storage service, buckets are addressed by path
+    #[default("https://s3.example.com")]
+    #[doku(example = "https://s3.example.com")]
+    pub endpoint: String,
+    #[default("ibis")]
+    #[doku(example = "ibis")]
+    pub bucket: String,
+    #[default("us-east-1")]
+    #[doku(example = "us-east-1")]
+    pub region: String,
+    #[doku(example = "access_key")]
+    pub access_key: String,
+    #[doku(example = "secret_key")]
+    pub secret_key: String,
+}
@@ -1,32 +1,154 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
+/// Kind of error returned by the API, so that clients can handle errors without parsing the
+/// message.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorType {
+    NotFound,
+    NotLoggedIn,
+    /// Login needs a code for two factor authentication, which was missing
+    TotpRequired,
+    Forbidden,
+    /// Invalid input, the message contains details
+    Validation,
+    Conflict,
+    RateLimited,
+    /// Failed to fetch or verify data from another instance
+    Federation,
+    Internal,
+}
+
+#[cfg(feature = "ssr")]
+impl ErrorType {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        use ErrorType::*;
+        use axum::http::StatusCode;
+        match self {
+            NotFound => StatusCode::NOT_FOUND,
+            NotLoggedIn | TotpRequired => StatusCode::UNAUTHORIZED,
+            Forbidden => StatusCode::FORBIDDEN,
+            Validation => StatusCode::BAD_REQUEST,
+            Conflict => StatusCode::CONFLICT,
+            RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Federation => StatusCode::BAD_GATEWAY,
+            Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Json body of error responses.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ApiError {
+    pub error: ErrorType,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(error: ErrorType, message: impl Into<String>) -> Self {
+        ApiError {
+            error,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+#[cfg(feature = "ssr")]
 pub type BackendResult<T> = Result<T, BackendError>;
 
+#[cfg(feature = "ssr")]
 #[derive(Debug)]
-pub struct BackendError(pub anyhow::Error);
+pub struct BackendError {
+    pub error_type: ErrorType,
+    pub inner: anyhow::Error,
+}
+
+#[cfg(feature = "ssr")]
+impl BackendError {
+    pub fn new(error_type: ErrorType, message: impl Display) -> Self {
+        BackendError {
+            error_type,
+            inner: anyhow::anyhow!("{message}"),
+        }
+    }
+
+    pub fn not_found(message: impl Display) -> Self {
+        Self::new(ErrorType::NotFound, message)
+    }
+
+    pub fn forbidden(message: impl Display) -> Self {
+        Self::new(ErrorType::Forbidden, message)
+    }
+
+    pub fn validation(message: impl Display) -> Self {
+        Self::new(ErrorType::Validation, message)
+    }
+
+    pub fn conflict(message: impl Display) -> Self {
+        Self::new(ErrorType::Conflict, message)
+    }
+}
 
+#[cfg(feature = "ssr")]
 impl Display for BackendError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.0, f)
+        std::fmt::Display::fmt(&self.inner, f)
     }
 }
 
+/// Untyped errors are internal errors, except for some known error types from libraries.
+#[cfg(feature = "ssr")]
 impl<T> From<T> for BackendError
 where
     T: Into<anyhow::Error>,
 {
     fn from(t: T) -> Self {
-        BackendError(t.into())
+        use activitypub_federation::error::Error as FederationError;
+        let inner: anyhow::Error = t.into();
+        let error_type = if let Some(e) = inner.downcast_ref::<ApiError>() {
+            e.error
+        } else if let Some(e) = inner.downcast_ref::<diesel::result::Error>() {
+            match e {
+                diesel::result::Error::NotFound => ErrorType::NotFound,
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => ErrorType::Conflict,
+                _ => ErrorType::Internal,
+            }
+        } else if let Some(e) = inner.downcast_ref::<FederationError>() {
+            match e {
+                FederationError::NotFound | FederationError::ObjectDeleted(_) => {
+                    ErrorType::NotFound
+                }
+                FederationError::UrlVerificationError(_)
+                | FederationError::ActivitySignatureInvalid
+                | FederationError::ActivityBodyDigestInvalid => ErrorType::Forbidden,
+                _ => ErrorType::Federation,
+            }
+        } else {
+            ErrorType::Internal
+        };
+        BackendError { error_type, inner }
     }
 }
 
 #[cfg(feature = "ssr")]
 impl axum::response::IntoResponse for BackendError {
     fn into_response(self) -> axum::response::Response {
-        (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            format!("{}", self.0),
-        )
-            .into_response()
+        let body = ApiError {
+            error: self.error_type,
+            message: self.inner.to_string(),
+        };
+        (self.error_type.status_code(), axum::Json(body)).into_response()
     }
 }
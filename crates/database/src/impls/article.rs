@@ -1,29 +1,48 @@
 use crate::{
     DbUrl,
     common::{
-        article::{Article, ArticleView, EditVersion},
+        article::{Article, ArticleSearchView, ArticleView, Edit, EditVersion},
+        category::{MAX_CATEGORIES, parse_categories},
         comment::Comment,
-        newtypes::{ArticleId, InstanceId},
+        link::{LinkTarget, MAX_CHECKED_LINKS, WantedArticle, parse_article_links},
+        newtypes::{ArticleId, InstanceId, LocalUserId},
+        template::{MAX_TEMPLATES, parse_template_calls},
         user::LocalUserView,
     },
-    error::BackendResult,
-    impls::IbisContext,
-    schema::{article, article_follow, edit, instance},
+    error::{BackendError, BackendResult},
+    impls::{
+        IbisContext,
+        category::category_articles,
+        edit::{DbEditForm, insert_edit},
+        notifications::Notification,
+    },
+    schema::{
+        article,
+        article_category,
+        article_follow,
+        article_link,
+        article_redirect,
+        edit,
+        instance,
+        template_usage,
+    },
 };
 use diesel::{
     AsChangeset,
     BoolExpressionMethods,
+    Connection,
     ExpressionMethods,
     Insertable,
     JoinOnDsl,
     NullableExpressionMethods,
-    PgTextExpressionMethods,
     QueryDsl,
     RunQueryDsl,
-    dsl::{delete, max},
+    dsl::{count, delete, max, not, sql},
     insert_into,
+    pg::PgConnection,
+    sql_types::{Bool, Float, Text},
 };
-use std::ops::DerefMut;
+use std::{collections::HashSet, ops::DerefMut};
 use url::Url;
 
 #[derive(Debug, Clone, Insertable, AsChangeset)]
@@ -38,6 +57,10 @@ pub struct DbArticleForm {
     pub approved: bool,
 }
 
+/// Weighted search vector over article title and text. Needs to be identical to the expression
+/// in the `article_search_idx` index.
+const SEARCH_VECTOR: &str = "(setweight(to_tsvector('english', replace(article.title, '_', ' ')), 'A') || setweight(to_tsvector('english', article.text), 'B'))";
+
 #[derive(Debug)]
 pub enum ArticleViewQuery<'a> {
     Id(ArticleId),
@@ -55,6 +78,96 @@ impl<'a> From<(&'a String, Option<String>)> for ArticleViewQuery<'a> {
     }
 }
 
+/// Ids of articles which were previously named with the given title.
+fn redirects(
+    title: &str,
+) -> article_redirect::BoxedQuery<'_, diesel::pg::Pg, diesel::sql_types::Integer> {
+    article_redirect::table
+        .filter(article_redirect::title.eq(title))
+        .select(article_redirect::article_id)
+        .into_boxed()
+}
+
+/// Store which templates, articles and categories are used in the article text, replacing the
+/// previous lists. Calls and links without domain refer to the instance of the article.
+///
+/// Duplicates are removed and each list is limited, so that a long text cant exceed the maximum
+/// number of query parameters. Needs to run in the same transaction as the text update.
+fn update_references(article: &Article, conn: &mut PgConnection) -> BackendResult<()> {
+    let domain: String = instance::table
+        .find(article.instance_id)
+        .select(instance::domain)
+        .get_result(conn)?;
+    let mut seen = HashSet::new();
+    let template_forms: Vec<_> = parse_template_calls(&article.text)
+        .into_iter()
+        .map(|call| (call.title, call.domain.unwrap_or(domain.clone())))
+        .filter(|key| seen.insert(key.clone()))
+        .take(MAX_TEMPLATES)
+        .map(|(title, domain)| {
+            (
+                template_usage::article_id.eq(article.id),
+                template_usage::template_title.eq(title),
+                template_usage::template_domain.eq(domain),
+            )
+        })
+        .collect();
+    let mut seen = HashSet::new();
+    let link_forms: Vec<_> = parse_article_links(&article.text)
+        .into_iter()
+        .map(|link| (link.title, link.domain.unwrap_or(domain.clone())))
+        .filter(|key| seen.insert(key.clone()))
+        .take(MAX_CHECKED_LINKS)
+        .map(|(title, domain)| {
+            (
+                article_link::article_id.eq(article.id),
+                article_link::target_title.eq(title),
+                article_link::target_domain.eq(domain),
+            )
+        })
+        .collect();
+    let category_forms: Vec<_> = parse_categories(&article.text)
+        .into_iter()
+        .take(MAX_CATEGORIES)
+        .map(|name| {
+            (
+                article_category::article_id.eq(article.id),
+                article_category::name.eq(name),
+            )
+        })
+        .collect();
+    delete(template_usage::table.filter(template_usage::article_id.eq(article.id)))
+        .execute(conn)?;
+    insert_into(template_usage::table)
+        .values(template_forms)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    delete(article_link::table.filter(article_link::article_id.eq(article.id))).execute(conn)?;
+    insert_into(article_link::table)
+        .values(link_forms)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    delete(article_category::table.filter(article_category::article_id.eq(article.id)))
+        .execute(conn)?;
+    insert_into(article_category::table)
+        .values(category_forms)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(())
+}
+
+fn update_article_text(
+    id: ArticleId,
+    text: &str,
+    conn: &mut PgConnection,
+) -> BackendResult<Article> {
+    let article = diesel::update(article::dsl::article.find(id))
+        .set(article::dsl::text.eq(text))
+        .get_result::<Article>(conn)?;
+    update_references(&article, conn)?;
+    Ok(article)
+}
+
 impl Article {
     pub fn edits_id(&self) -> BackendResult<DbUrl> {
         Ok(Url::parse(&format!("{}/edits", self.ap_id))?.into())
@@ -62,26 +175,75 @@ impl Article {
 
     pub fn create(form: DbArticleForm, context: &IbisContext) -> BackendResult<Self> {
         let mut conn = context.db_pool.get()?;
-        Ok(insert_into(article::table)
-            .values(form)
-            .get_result(conn.deref_mut())?)
+        conn.transaction(|conn| {
+            let article = insert_into(article::table).values(form).get_result(conn)?;
+            update_references(&article, conn)?;
+            Ok(article)
+        })
     }
 
     pub fn create_or_update(form: DbArticleForm, context: &IbisContext) -> BackendResult<Self> {
         let mut conn = context.db_pool.get()?;
-        Ok(insert_into(article::table)
-            .values(&form)
-            .on_conflict(article::dsl::ap_id)
-            .do_update()
-            .set(&form)
-            .get_result(conn.deref_mut())?)
+        conn.transaction(|conn| {
+            let article = insert_into(article::table)
+                .values(&form)
+                .on_conflict(article::dsl::ap_id)
+                .do_update()
+                .set(&form)
+                .get_result(conn)?;
+            update_references(&article, conn)?;
+            Ok(article)
+        })
     }
 
     pub fn update_text(id: ArticleId, text: &str, context: &IbisContext) -> BackendResult<Self> {
         let mut conn = context.db_pool.get()?;
-        Ok(diesel::update(article::dsl::article.find(id))
-            .set(article::dsl::text.eq(text))
-            .get_result::<Self>(conn.deref_mut())?)
+        conn.transaction(|conn| update_article_text(id, text, conn))
+    }
+
+    /// Store a local edit together with the new article text, so that the edit history cant
+    /// diverge from the text if one of them fails.
+    pub fn update_text_with_edit(
+        form: &DbEditForm,
+        text: &str,
+        context: &IbisContext,
+    ) -> BackendResult<(Edit, Self)> {
+        let mut conn = context.db_pool.get()?;
+        let (edit, article) = conn.transaction(|conn| {
+            let edit = insert_edit(form, conn)?;
+            let article = update_article_text(edit.article_id, text, conn)?;
+            Ok::<_, BackendError>((edit, article))
+        })?;
+        Notification::notify_edit(&edit, context)?;
+        Ok((edit, article))
+    }
+
+    /// Change the title of an article. The previous title is kept as redirect, so that it can
+    /// still be used to read the article.
+    pub fn update_title(id: ArticleId, title: &str, context: &IbisContext) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        conn.transaction(|conn| {
+            let previous: Article = article::table.find(id).get_result(conn)?;
+            insert_into(article_redirect::table)
+                .values((
+                    article_redirect::article_id.eq(id),
+                    article_redirect::title.eq(previous.title),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            // in case the article is moved back to a previous title
+            delete(
+                article_redirect::table.filter(
+                    article_redirect::article_id
+                        .eq(id)
+                        .and(article_redirect::title.eq(title)),
+                ),
+            )
+            .execute(conn)?;
+            Ok(diesel::update(article::dsl::article.find(id))
+                .set(article::dsl::title.eq(title))
+                .get_result::<Self>(conn)?)
+        })
     }
 
     pub fn update_protected(
@@ -106,6 +268,17 @@ impl Article {
             .get_result::<Self>(conn.deref_mut())?)
     }
 
+    pub fn update_removed(
+        id: ArticleId,
+        removed: bool,
+        context: &IbisContext,
+    ) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        Ok(diesel::update(article::dsl::article.find(id))
+            .set(article::dsl::removed.eq(removed))
+            .get_result::<Self>(conn.deref_mut())?)
+    }
+
     pub fn delete(id: ArticleId, context: &IbisContext) -> BackendResult<Self> {
         let mut conn = context.db_pool.get()?;
         Ok(diesel::delete(article::dsl::article.find(id)).get_result::<Self>(conn.deref_mut())?)
@@ -132,11 +305,19 @@ impl Article {
                     .eq(article::id)
                     .and(article_follow::local_user_id.nullable().eq(local_user_id))),
             )
+            .filter(article::dsl::removed.eq(false))
             .into_boxed();
         query = match params.into() {
             ArticleViewQuery::Id(id) => query.filter(article::id.eq(id)),
             ArticleViewQuery::Name(title, domain) => {
-                query = query.filter(article::dsl::title.eq(title));
+                query = query
+                    .filter(
+                        article::dsl::title
+                            .eq(title)
+                            .or(article::id.eq_any(redirects(title))),
+                    )
+                    // prefer exact title match over redirect
+                    .order_by(article::dsl::title.eq(title).desc());
                 if let Some(domain) = domain {
                     query.filter(instance::dsl::domain.eq(domain))
                 } else {
@@ -163,6 +344,60 @@ impl Article {
         })
     }
 
+    /// Read local article by title or previous title, including removed articles.
+    pub fn read_local_from_title(title: &str, context: &IbisContext) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        Ok(article::table
+            .filter(
+                article::dsl::title
+                    .eq(title)
+                    .or(article::id.eq_any(redirects(title))),
+            )
+            .filter(article::dsl::local.eq(true))
+            .order_by(article::dsl::title.eq(title).desc())
+            .get_result(conn.deref_mut())?)
+    }
+
+    /// Returns the targets for which an article with this title or previous title exists. Local
+    /// articles are matched by targets without domain. Removed articles count as missing.
+    pub fn existing_links(
+        targets: &HashSet<LinkTarget>,
+        context: &IbisContext,
+    ) -> BackendResult<HashSet<LinkTarget>> {
+        let mut conn = context.db_pool.get()?;
+        let titles: HashSet<_> = targets.iter().map(|t| &t.title).collect();
+        let rows: Vec<(String, Option<String>, String, bool)> = article::table
+            .inner_join(instance::table)
+            .left_join(article_redirect::table)
+            .filter(
+                article::dsl::title
+                    .eq_any(&titles)
+                    .or(article_redirect::title.eq_any(&titles)),
+            )
+            .filter(article::dsl::removed.eq(false))
+            .select((
+                article::title,
+                article_redirect::title.nullable(),
+                instance::domain,
+                article::local,
+            ))
+            .get_results(conn.deref_mut())?;
+        Ok(rows
+            .into_iter()
+            .flat_map(|(title, previous_title, domain, local)| {
+                let domain = (!local).then_some(domain);
+                [Some(title), previous_title]
+                    .into_iter()
+                    .flatten()
+                    .map(move |title| LinkTarget {
+                        title,
+                        domain: domain.clone(),
+                    })
+            })
+            .filter(|target| targets.contains(target))
+            .collect())
+    }
+
     pub fn read_from_ap_id(ap_id: &DbUrl, context: &IbisContext) -> BackendResult<Self> {
         let mut conn = context.db_pool.get()?;
         Ok(article::table
@@ -170,12 +405,16 @@ impl Article {
             .get_result(conn.deref_mut())?)
     }
 
-    /// Read all articles, ordered by most recently edited first.
+    /// Read all articles, ordered by most recently edited first. With category, only articles
+    /// in this category or any of its subcategories are returned.
     ///
     /// TODO: Should get rid of only_local param and rely on instance_id
     pub fn read_all(
         only_local: Option<bool>,
         instance_id: Option<InstanceId>,
+        category: Option<&str>,
+        limit: Option<i64>,
+        offset: Option<i64>,
         context: &IbisContext,
     ) -> BackendResult<Vec<Self>> {
         let mut conn = context.db_pool.get()?;
@@ -183,6 +422,7 @@ impl Article {
             .inner_join(edit::table)
             .inner_join(instance::table)
             .filter(article::dsl::approved.eq(true))
+            .filter(article::dsl::removed.eq(false))
             .group_by(article::dsl::id)
             .order_by(max(edit::dsl::published).desc())
             .select(article::all_columns)
@@ -194,25 +434,93 @@ impl Article {
         if let Some(instance_id) = instance_id {
             query = query.filter(instance::dsl::id.eq(instance_id));
         }
+        if let Some(category) = category {
+            query = query.filter(article::dsl::id.eq_any(category_articles(category)));
+        }
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+        if let Some(offset) = offset {
+            query = query.offset(offset);
+        }
         Ok(query.get_results(&mut conn)?)
     }
 
-    pub fn search(query: &str, context: &IbisContext) -> BackendResult<Vec<Self>> {
+    /// Read local articles with newest first, so that pagination is stable even if articles are
+    /// edited in the meantime.
+    pub fn read_local_paged(
+        limit: i64,
+        offset: i64,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<Self>> {
         let mut conn = context.db_pool.get()?;
-        let replaced = query
-            .replace('%', "\\%")
-            .replace('_', "\\_")
-            .replace(' ', "%");
-        let replaced = format!("%{replaced}%");
-        Ok(article::table
-            .filter(
-                article::dsl::title
-                    .ilike(&replaced)
-                    .or(article::dsl::text.ilike(&replaced)),
-            )
+        Ok(Self::local_query()
+            .order_by(article::dsl::id.desc())
+            .limit(limit)
+            .offset(offset)
             .get_results(conn.deref_mut())?)
     }
 
+    pub fn count_local(context: &IbisContext) -> BackendResult<i64> {
+        let mut conn = context.db_pool.get()?;
+        Ok(Self::local_query().count().get_result(conn.deref_mut())?)
+    }
+
+    fn local_query() -> article::BoxedQuery<'static, diesel::pg::Pg> {
+        article::table
+            .filter(article::dsl::local.eq(true))
+            .filter(article::dsl::approved.eq(true))
+            .filter(article::dsl::removed.eq(false))
+            .into_boxed()
+    }
+
+    /// Full text search over article title and text, ordered by relevance. Matches in the title
+    /// are ranked higher than matches in the text.
+    pub fn search(
+        query: &str,
+        instance_id: Option<InstanceId>,
+        category: Option<&str>,
+        limit: i64,
+        offset: i64,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<ArticleSearchView>> {
+        let mut conn = context.db_pool.get()?;
+        let tsquery = "websearch_to_tsquery('english', ";
+        let matches = sql::<Bool>(&format!("{SEARCH_VECTOR} @@ {tsquery}"))
+            .bind::<Text, _>(query)
+            .sql(")");
+        let rank = sql::<Float>(&format!("ts_rank({SEARCH_VECTOR}, {tsquery}"))
+            .bind::<Text, _>(query)
+            .sql("))");
+        let snippet = sql::<Text>(&format!("ts_headline('english', article.text, {tsquery}"))
+            .bind::<Text, _>(query)
+            .sql("), 'StartSel=<mark>, StopSel=</mark>, MaxFragments=2')");
+
+        let mut query = article::table
+            .filter(matches)
+            .filter(article::dsl::approved.eq(true))
+            .filter(article::dsl::removed.eq(false))
+            // Id as tiebreaker so that pages are stable for articles with the same rank
+            .order_by((rank.desc(), article::dsl::id))
+            .select((
+                article::dsl::id,
+                article::dsl::title,
+                article::dsl::ap_id,
+                article::dsl::local,
+                snippet,
+            ))
+            .limit(limit)
+            .offset(offset)
+            .into_boxed();
+        if let Some(instance_id) = instance_id {
+            query = query.filter(article::dsl::instance_id.eq(instance_id));
+        }
+        if let Some(category) = category {
+            query = query.filter(article::dsl::id.eq_any(category_articles(category)));
+        }
+        Ok(query.get_results(conn.deref_mut())?)
+    }
+
     pub fn latest_edit_version(&self, context: &IbisContext) -> BackendResult<EditVersion> {
         let mut conn = context.db_pool.get()?;
         let latest_version: Option<EditVersion> = edit::table
@@ -228,6 +536,105 @@ impl Article {
         }
     }
 
+    /// Articles which transclude the given article as template, ordered by title. Calls which
+    /// use a previous title of the template are included.
+    pub fn list_template_usage(
+        template: &Article,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<Self>> {
+        let mut conn = context.db_pool.get()?;
+        let domain: String = instance::table
+            .find(template.instance_id)
+            .select(instance::domain)
+            .get_result(conn.deref_mut())?;
+        let previous_titles = article_redirect::table
+            .filter(article_redirect::article_id.eq(template.id))
+            .select(article_redirect::title);
+        Ok(article::table
+            .inner_join(template_usage::table)
+            .filter(template_usage::template_domain.eq(domain))
+            .filter(
+                template_usage::template_title
+                    .eq(&template.title)
+                    .or(template_usage::template_title.eq_any(previous_titles)),
+            )
+            .filter(article::dsl::approved.eq(true))
+            .filter(article::dsl::removed.eq(false))
+            .order_by(article::title)
+            .select(article::all_columns)
+            .distinct()
+            .get_results(conn.deref_mut())?)
+    }
+
+    /// Articles which link to the given article, ordered by title. Links which use a previous
+    /// title of the article are included.
+    pub fn list_backlinks(target: &Article, context: &IbisContext) -> BackendResult<Vec<Self>> {
+        let mut conn = context.db_pool.get()?;
+        let domain: String = instance::table
+            .find(target.instance_id)
+            .select(instance::domain)
+            .get_result(conn.deref_mut())?;
+        let previous_titles = article_redirect::table
+            .filter(article_redirect::article_id.eq(target.id))
+            .select(article_redirect::title);
+        Ok(article::table
+            .inner_join(article_link::table)
+            .filter(article_link::target_domain.eq(domain))
+            .filter(
+                article_link::target_title
+                    .eq(&target.title)
+                    .or(article_link::target_title.eq_any(previous_titles)),
+            )
+            .filter(article::id.ne(target.id))
+            .filter(article::dsl::approved.eq(true))
+            .filter(article::dsl::removed.eq(false))
+            .order_by(article::title)
+            .select(article::all_columns)
+            .distinct()
+            .get_results(conn.deref_mut())?)
+    }
+
+    /// Titles on the local instance which are linked from other articles, but don't exist.
+    /// Titles with the most links come first.
+    pub fn list_wanted(
+        limit: i64,
+        offset: i64,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<WantedArticle>> {
+        let mut conn = context.db_pool.get()?;
+        // The linking article is also selected from `article`, so subselects need an alias
+        diesel::alias!(article as existing_article: DbExistingArticle);
+        let existing = existing_article
+            .filter(existing_article.field(article::local).eq(true))
+            .filter(existing_article.field(article::removed).eq(false))
+            .select(existing_article.field(article::title));
+        let redirected = article_redirect::table
+            .inner_join(
+                existing_article.on(existing_article
+                    .field(article::id)
+                    .eq(article_redirect::article_id)),
+            )
+            .filter(existing_article.field(article::local).eq(true))
+            .filter(existing_article.field(article::removed).eq(false))
+            .select(article_redirect::title);
+        Ok(article_link::table
+            .inner_join(article::table)
+            .filter(article_link::target_domain.eq(&context.config.federation.domain))
+            .filter(not(article_link::target_title.eq_any(existing)))
+            .filter(not(article_link::target_title.eq_any(redirected)))
+            .filter(article::dsl::approved.eq(true))
+            .filter(article::dsl::removed.eq(false))
+            .group_by(article_link::target_title)
+            .select((article_link::target_title, count(article_link::article_id)))
+            .order_by((
+                count(article_link::article_id).desc(),
+                article_link::target_title,
+            ))
+            .limit(limit)
+            .offset(offset)
+            .get_results(conn.deref_mut())?)
+    }
+
     pub fn follow(
         article_id_: ArticleId,
         follower: &LocalUserView,
@@ -245,6 +652,21 @@ impl Article {
         Ok(())
     }
 
+    /// Articles followed by the given user, ordered by title.
+    pub fn list_followed(
+        local_user_id_: LocalUserId,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<Self>> {
+        use article_follow::dsl::local_user_id;
+        let mut conn = context.db_pool.get()?;
+        Ok(article::table
+            .inner_join(article_follow::table)
+            .filter(local_user_id.eq(local_user_id_))
+            .order(article::title)
+            .select(article::all_columns)
+            .get_results(conn.deref_mut())?)
+    }
+
     pub fn unfollow(
         article_id_: ArticleId,
         follower: &LocalUserView,
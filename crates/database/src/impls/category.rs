@@ -0,0 +1,77 @@
+use crate::{
+    common::{category::CategoryView, newtypes::ArticleId},
+    error::BackendResult,
+    impls::IbisContext,
+    schema::{article, article_category},
+};
+use diesel::{
+    BoolExpressionMethods,
+    ExpressionMethods,
+    QueryDsl,
+    RunQueryDsl,
+    TextExpressionMethods,
+    pg::Pg,
+    sql_types::Integer,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    ops::DerefMut,
+};
+
+/// Pattern to find all subcategories of the category with `like`.
+fn subcategory_pattern(name: &str) -> String {
+    // underscore is a wildcard in like, category names can't contain other special characters
+    format!("{}/%", name.replace('_', "\\_"))
+}
+
+/// Ids of articles which are in the given category or in any of its subcategories.
+pub(crate) fn category_articles(name: &str) -> article_category::BoxedQuery<'_, Pg, Integer> {
+    article_category::table
+        .filter(
+            article_category::name
+                .eq(name)
+                .or(article_category::name.like(subcategory_pattern(name))),
+        )
+        .select(article_category::article_id)
+        .into_boxed()
+}
+
+impl CategoryView {
+    /// Direct subcategories of the given category, or top level categories if no parent is
+    /// given. Ordered by name.
+    pub fn list(parent: Option<&str>, context: &IbisContext) -> BackendResult<Vec<Self>> {
+        let mut conn = context.db_pool.get()?;
+        let mut query = article_category::table
+            .inner_join(article::table)
+            .filter(article::dsl::approved.eq(true))
+            .filter(article::dsl::removed.eq(false))
+            .select((article_category::name, article_category::article_id))
+            .into_boxed();
+        if let Some(parent) = parent {
+            query = query.filter(article_category::name.like(subcategory_pattern(parent)));
+        }
+        let rows: Vec<(String, ArticleId)> = query.get_results(conn.deref_mut())?;
+
+        // Articles in deeper subcategories are counted for the direct subcategory
+        let mut children: BTreeMap<String, HashSet<ArticleId>> = BTreeMap::new();
+        for (name, article_id) in rows {
+            let rest = match parent {
+                Some(parent) => &name[parent.len() + 1..],
+                None => &name,
+            };
+            let child = rest.split('/').next().unwrap_or(rest);
+            let child = match parent {
+                Some(parent) => format!("{parent}/{child}"),
+                None => child.to_string(),
+            };
+            children.entry(child).or_default().insert(article_id);
+        }
+        Ok(children
+            .into_iter()
+            .map(|(name, articles)| CategoryView {
+                name,
+                article_count: articles.len() as i64,
+            })
+            .collect())
+    }
+}
@@ -96,6 +96,18 @@ impl Comment {
             .get_result(conn.deref_mut())?)
     }
 
+    /// Comments written by the given user which are not deleted yet.
+    pub fn read_for_creator(
+        creator_id: PersonId,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<Self>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(comment::table
+            .filter(comment::creator_id.eq(creator_id))
+            .filter(comment::deleted.eq(false))
+            .get_results(conn.deref_mut())?)
+    }
+
     pub fn read_for_article(
         article_id: ArticleId,
         context: &IbisContext,
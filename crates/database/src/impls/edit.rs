@@ -20,6 +20,7 @@ use diesel::{
     RunQueryDsl,
     dsl::not,
     insert_into,
+    pg::PgConnection,
 };
 use diffy::create_patch;
 use std::ops::DerefMut;
@@ -69,15 +70,20 @@ impl DbEditForm {
     }
 }
 
+/// Insert the edit, or update it if it was already received.
+pub(super) fn insert_edit(form: &DbEditForm, conn: &mut PgConnection) -> BackendResult<Edit> {
+    Ok(insert_into(edit::table)
+        .values(form)
+        .on_conflict(edit::dsl::ap_id)
+        .do_update()
+        .set(form)
+        .get_result(conn)?)
+}
+
 impl Edit {
     pub fn create(form: &DbEditForm, context: &IbisContext) -> BackendResult<Self> {
         let mut conn = context.db_pool.get()?;
-        let edit: Edit = insert_into(edit::table)
-            .values(form)
-            .on_conflict(edit::dsl::ap_id)
-            .do_update()
-            .set(form)
-            .get_result(conn.deref_mut())?;
+        let edit = insert_edit(form, conn.deref_mut())?;
 
         Notification::notify_edit(&edit, context)?;
         Ok(edit)
@@ -97,38 +103,90 @@ impl Edit {
             .get_result(conn.deref_mut())?)
     }
 
-    pub fn list_for_article(id: ArticleId, context: &IbisContext) -> BackendResult<Vec<Self>> {
+    pub fn list_for_article(
+        id: ArticleId,
+        limit: Option<i64>,
+        offset: Option<i64>,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<Self>> {
         let mut conn = context.db_pool.get()?;
-        Ok(edit::table
+        let mut query = edit::table
             .filter(edit::article_id.eq(id))
-            .order(edit::published)
+            .order((edit::published, edit::id))
+            .into_boxed();
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+        if let Some(offset) = offset {
+            query = query.offset(offset);
+        }
+        Ok(query.get_results(conn.deref_mut())?)
+    }
+
+    /// Articles which have at least one accepted edit from the given user.
+    pub fn list_article_ids_for_creator(
+        creator_id: PersonId,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<ArticleId>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(edit::table
+            .filter(edit::creator_id.eq(creator_id))
+            .filter(edit::pending.eq(false))
+            .select(edit::article_id)
+            .distinct()
             .get_results(conn.deref_mut())?)
     }
 
+    pub fn count_for_article(id: ArticleId, context: &IbisContext) -> BackendResult<i64> {
+        let mut conn = context.db_pool.get()?;
+        Ok(edit::table
+            .filter(edit::article_id.eq(id))
+            .count()
+            .get_result(conn.deref_mut())?)
+    }
+
+    /// List edits, returning the page of most recent edits in chronological order.
     pub fn view(
         params: ViewEditParams,
+        hide_bots: bool,
         user: &Option<LocalUserView>,
+        limit: i64,
+        offset: i64,
         context: &IbisContext,
     ) -> BackendResult<Vec<EditView>> {
         let mut conn = context.db_pool.get()?;
         let person_id = user.as_ref().map(|u| u.person.id).unwrap_or(PersonId(-1));
-        let query = edit::table
+        let mut query = edit::table
             .inner_join(article::table)
             .inner_join(person::table)
+            .filter(article::removed.eq(false))
             // only the creator can view pending edits
             .filter(not(edit::pending).or(edit::creator_id.eq(person_id)))
             .into_boxed();
 
+        if hide_bots {
+            query = query.filter(person::bot.eq(false));
+        }
         let query = match params {
             ViewEditParams::PersonId(person_id) => query.filter(edit::creator_id.eq(person_id)),
             ViewEditParams::ArticleId(article_id) => query.filter(edit::article_id.eq(article_id)),
+            ViewEditParams::Version(article_id, version) => query
+                .filter(edit::article_id.eq(article_id))
+                .filter(edit::hash.eq(version)),
         };
 
-        Ok(query.order(edit::published).get_results(conn.deref_mut())?)
+        let mut edits: Vec<EditView> = query
+            .order((edit::published.desc(), edit::id.desc()))
+            .limit(limit)
+            .offset(offset)
+            .get_results(conn.deref_mut())?;
+        edits.reverse();
+        Ok(edits)
     }
 }
 
 pub enum ViewEditParams {
     PersonId(PersonId),
     ArticleId(ArticleId),
+    Version(ArticleId, EditVersion),
 }
@@ -1,13 +1,14 @@
 use crate::{
     DbUrl,
     common::{
-        instance::{Instance, InstanceView, InstanceWithArticles},
+        instance::{Instance, InstanceBlock, InstanceView, InstanceWithArticles},
         newtypes::{CommentId, InstanceId},
         user::Person,
+        utils::http_protocol_str,
     },
     error::BackendResult,
     impls::IbisContext,
-    schema::{article, comment, edit, instance, instance_follow},
+    schema::{article, comment, edit, instance, instance_block, instance_follow},
 };
 use chrono::{DateTime, Utc};
 use diesel::{
@@ -163,7 +164,11 @@ impl Instance {
             .get_results(conn.deref_mut())?)
     }
 
-    pub fn list_with_articles(context: &IbisContext) -> BackendResult<Vec<InstanceWithArticles>> {
+    pub fn list_with_articles(
+        limit: i64,
+        offset: i64,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<InstanceWithArticles>> {
         let mut conn = context.db_pool.get()?;
         // select all instances, with most recently edited first (pending edits are ignored)
         let instances = instance::table
@@ -172,6 +177,8 @@ impl Instance {
             .left_join(edit::table.on(article::id.eq(edit::article_id).and(not(edit::pending))))
             .group_by(instance::id)
             .order_by(max(edit::published).desc())
+            .limit(limit)
+            .offset(offset)
             .select(instance::all_columns)
             .get_results::<Instance>(conn.deref_mut())?;
         let mut res = vec![];
@@ -181,6 +188,7 @@ impl Instance {
         for instance in instances {
             let articles = article::table
                 .filter(article::instance_id.eq(instance.id))
+                .filter(article::removed.eq(false))
                 .inner_join(edit::table)
                 .group_by(article::id)
                 .order_by((article::local.desc(), max(edit::published).desc()))
@@ -207,4 +215,53 @@ impl Instance {
             .select(instance::all_columns)
             .get_result(conn.deref_mut())?)
     }
+
+    /// Remove content which was federated from the given remote domain. The instance is
+    /// deleted together with its articles, and comments written by its users are marked as
+    /// deleted so that replies from other users are kept.
+    pub fn purge(domain: &str, context: &IbisContext) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        // Escape wildcards so that only urls on exactly this domain are matched
+        let escaped = domain
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let ap_id_pattern = format!("{}://{escaped}/%", http_protocol_str());
+        conn.transaction(|conn| {
+            update(comment::table)
+                .filter(comment::ap_id.like(&ap_id_pattern))
+                .filter(comment::local.eq(false))
+                .set(comment::deleted.eq(true))
+                .execute(conn)?;
+            delete(instance::table)
+                .filter(instance::domain.eq(domain))
+                .filter(instance::local.eq(false))
+                .execute(conn)?;
+            Ok(())
+        })
+    }
+}
+
+impl InstanceBlock {
+    pub fn create(domain: &str, context: &IbisContext) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        insert_into(instance_block::table)
+            .values(instance_block::domain.eq(domain))
+            .on_conflict_do_nothing()
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    pub fn delete(domain: &str, context: &IbisContext) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        delete(instance_block::table.find(domain)).execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    pub fn list(context: &IbisContext) -> BackendResult<Vec<InstanceBlock>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(instance_block::table
+            .order_by(instance_block::domain)
+            .get_results(conn.deref_mut())?)
+    }
 }
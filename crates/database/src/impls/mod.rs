@@ -9,12 +9,14 @@ use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use std::{env::var, ops::DerefMut};
 
 pub mod article;
+pub mod category;
 pub mod comment;
 pub mod conflict;
 pub mod edit;
 pub mod instance;
 pub mod instance_stats;
 pub mod notifications;
+pub mod upload;
 pub mod user;
 
 pub type DbPool = Pool<ConnectionManager<PgConnection>>;
@@ -4,14 +4,25 @@ use crate::{
         comment::Comment,
         newtypes::{ArticleId, ArticleNotifId, CommentId, EditId, LocalUserId, PersonId},
         notifications::ApiNotification,
-        user::{LocalUserView, Person},
+        user::{LocalUserView, Person, RegistrationApplication},
     },
     error::BackendResult,
     impls::IbisContext,
-    schema::{article, article_follow, comment, conflict, edit, local_user, notification, person},
+    schema::{
+        article,
+        article_follow,
+        comment,
+        conflict,
+        edit,
+        local_user,
+        notification,
+        person,
+        registration_application,
+    },
 };
 use chrono::{DateTime, Utc};
 use diesel::{
+    BoolExpressionMethods,
     ExpressionMethods,
     Insertable,
     JoinOnDsl,
@@ -48,8 +59,12 @@ struct NotificationInsertForm {
 }
 
 impl Notification {
+    /// List notifications of all types, most recent first. Each type is read separately, so
+    /// every query needs to return enough items to fill the requested page.
     pub async fn list(
         user: &LocalUserView,
+        limit: i64,
+        offset: i64,
         context: &IbisContext,
     ) -> BackendResult<Vec<ApiNotification>> {
         let mut conn = context.db_pool.get()?;
@@ -59,6 +74,8 @@ impl Notification {
         let conflicts: Vec<(Conflict, Article)> = conflict::table
             .inner_join(article::table)
             .filter(conflict::dsl::creator_id.eq(user.person.id))
+            .order_by(conflict::dsl::published.desc())
+            .limit(limit + offset)
             .select((conflict::all_columns, article::all_columns))
             .get_results(conn.deref_mut())?;
         notifications.extend(
@@ -72,19 +89,39 @@ impl Notification {
             let articles = article::table
                 .group_by(article::dsl::id)
                 .filter(article::dsl::approved.eq(false))
+                .order_by(article::dsl::published.desc())
+                .limit(limit + offset)
                 .select(article::all_columns)
                 .get_results(&mut conn)?
                 .into_iter();
-            notifications.extend(articles.map(ApiNotification::ArticleApprovalRequired))
+            notifications.extend(articles.map(ApiNotification::ArticleApprovalRequired));
+
+            // new users waiting for approval
+            let applications = registration_application::table
+                .inner_join(local_user::table.inner_join(person::table))
+                .order_by(registration_application::published.desc())
+                .limit(limit + offset)
+                .select((registration_application::all_columns, person::all_columns))
+                .get_results::<(RegistrationApplication, Person)>(&mut conn)?
+                .into_iter();
+            notifications
+                .extend(applications.map(|(a, p)| ApiNotification::RegistrationApplication(a, p)));
         }
 
         // new edits and comments for followed articles
-        let article_notifications = notification::table
+        let mut query = notification::table
             .inner_join(article::table)
             .inner_join(person::table)
             .left_join(comment::table)
             .left_join(edit::table)
             .filter(notification::local_user_id.eq(user.local_user.id))
+            .into_boxed();
+        if user.local_user.hide_bot_edits {
+            query = query.filter(person::bot.eq(false).or(notification::edit_id.is_null()));
+        }
+        let article_notifications = query
+            .order_by(notification::published.desc())
+            .limit(limit + offset)
             .select((
                 notification::all_columns,
                 article::all_columns,
@@ -106,7 +143,11 @@ impl Notification {
         ));
 
         notifications.sort_by(|a, b| b.published().cmp(a.published()));
-        Ok(notifications)
+        Ok(notifications
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
     }
 
     pub fn count(user: &LocalUserView, context: &IbisContext) -> BackendResult<i64> {
@@ -129,11 +170,24 @@ impl Notification {
                 .first::<i64>(conn.deref_mut())
                 .unwrap_or(0);
             num += articles;
+
+            // new users waiting for approval
+            let applications = registration_application::table
+                .select(count(registration_application::id))
+                .first::<i64>(conn.deref_mut())
+                .unwrap_or(0);
+            num += applications;
         }
 
         // new edits and comments for followed articles
-        let article_notifications = notification::table
+        let mut query = notification::table
+            .inner_join(person::table)
             .filter(notification::local_user_id.eq(user.local_user.id))
+            .into_boxed();
+        if user.local_user.hide_bot_edits {
+            query = query.filter(person::bot.eq(false).or(notification::edit_id.is_null()));
+        }
+        let article_notifications = query
             .select(count(notification::id))
             .first::<i64>(conn.deref_mut())
             .unwrap_or(0);
@@ -197,6 +251,7 @@ impl Notification {
         Self::notify(
             comment.article_id,
             comment.creator_id,
+            false,
             |local_user_id| NotificationInsertForm {
                 local_user_id,
                 article_id: comment.article_id,
@@ -211,9 +266,16 @@ impl Notification {
     }
 
     pub(super) fn notify_edit(edit: &Edit, context: &IbisContext) -> BackendResult<()> {
+        // connection is only used for this query, so that it is returned to the pool before
+        // notify takes another one
+        let creator_is_bot = person::table
+            .find(edit.creator_id)
+            .select(person::bot)
+            .get_result(context.db_pool.get()?.deref_mut())?;
         Self::notify(
             edit.article_id,
             edit.creator_id,
+            creator_is_bot,
             |local_user_id| NotificationInsertForm {
                 local_user_id,
                 article_id: edit.article_id,
@@ -225,9 +287,12 @@ impl Notification {
         )
     }
 
+    /// Notify followers of the article. If `skip_hide_bots` is true, users who opted out of bot
+    /// edits are not notified.
     fn notify<F>(
         article_id: ArticleId,
         creator_id: PersonId,
+        skip_hide_bots: bool,
         map_fn: F,
         context: &IbisContext,
     ) -> BackendResult<()>
@@ -236,9 +301,14 @@ impl Notification {
     {
         let mut conn = context.db_pool.get()?;
         // get followers for this article
-        let followers = article_follow::table
+        let mut query = article_follow::table
             .inner_join(local_user::table)
             .filter(article_follow::article_id.eq(article_id))
+            .into_boxed();
+        if skip_hide_bots {
+            query = query.filter(local_user::hide_bot_edits.eq(false));
+        }
+        let followers = query
             .select((local_user::person_id, local_user::id))
             .get_results::<(PersonId, LocalUserId)>(&mut conn)?;
         // create insert form with edit/comment it
@@ -0,0 +1,119 @@
+use crate::{
+    common::{
+        newtypes::{PersonId, UploadId},
+        upload::{Upload, UploadView},
+        user::Person,
+        utils::http_protocol_str,
+    },
+    error::BackendResult,
+    impls::IbisContext,
+    schema::{person, upload},
+};
+use diesel::{
+    BoolExpressionMethods,
+    ExpressionMethods,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    delete,
+    dsl::insert_into,
+};
+use std::ops::DerefMut;
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = upload, check_for_backend(diesel::pg::Pg))]
+pub struct DbUploadForm {
+    pub uploader_id: PersonId,
+    pub filename: String,
+    pub original_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub thumbnail: Option<String>,
+}
+
+impl Upload {
+    pub fn create(form: DbUploadForm, context: &IbisContext) -> BackendResult<UploadView> {
+        let mut conn = context.db_pool.get()?;
+        let upload: Upload = insert_into(upload::table)
+            .values(&form)
+            .get_result(conn.deref_mut())?;
+        let uploader = Person::read(upload.uploader_id, context)?;
+        Ok(UploadView::new(upload, uploader, context))
+    }
+
+    pub fn read(id: UploadId, context: &IbisContext) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        Ok(upload::table.find(id).get_result(conn.deref_mut())?)
+    }
+
+    /// Find the upload which is stored under this name, either as the file itself or as its
+    /// thumbnail.
+    pub fn read_from_filename(filename: &str, context: &IbisContext) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        Ok(upload::table
+            .filter(
+                upload::filename
+                    .eq(filename)
+                    .or(upload::thumbnail.eq(filename)),
+            )
+            .get_result(conn.deref_mut())?)
+    }
+
+    pub fn read_from_filenames(
+        filenames: &[String],
+        context: &IbisContext,
+    ) -> BackendResult<Vec<Self>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(upload::table
+            .filter(upload::filename.eq_any(filenames))
+            .order_by(upload::id)
+            .get_results(conn.deref_mut())?)
+    }
+
+    /// Media library with all uploads of the local instance, newest first.
+    pub fn list(limit: i64, offset: i64, context: &IbisContext) -> BackendResult<Vec<UploadView>> {
+        let mut conn = context.db_pool.get()?;
+        let uploads = upload::table
+            .inner_join(person::table)
+            .order_by(upload::published.desc())
+            .limit(limit)
+            .offset(offset)
+            .get_results::<(Upload, Person)>(conn.deref_mut())?;
+        Ok(uploads
+            .into_iter()
+            .map(|(upload, uploader)| UploadView::new(upload, uploader, context))
+            .collect())
+    }
+
+    pub fn delete(id: UploadId, context: &IbisContext) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        delete(upload::table.find(id)).execute(conn.deref_mut())?;
+        Ok(())
+    }
+}
+
+impl UploadView {
+    fn new(upload: Upload, uploader: Person, context: &IbisContext) -> Self {
+        let url = media_url(&upload.filename, context);
+        let thumbnail_url = upload
+            .thumbnail
+            .as_ref()
+            .map(|t| media_url(t, context))
+            .unwrap_or_else(|| url.clone());
+        UploadView {
+            upload,
+            uploader,
+            url,
+            thumbnail_url,
+        }
+    }
+}
+
+/// Absolute url so that it also works in articles which are federated to other instances.
+pub fn media_url(filename: &str, context: &IbisContext) -> String {
+    format!(
+        "{}://{}/media/{filename}",
+        http_protocol_str(),
+        context.config.federation.domain
+    )
+}
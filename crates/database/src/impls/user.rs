@@ -2,34 +2,70 @@ use crate::{
     DbUrl,
     common::{
         instance::InstanceFollow,
-        newtypes::PersonId,
-        user::{LocalUser, LocalUserView, Person},
+        newtypes::{ApiTokenId, LocalUserId, PersonId, RegistrationApplicationId, SessionId},
+        user::{
+            ApiToken,
+            ApiTokenScope,
+            LocalUser,
+            LocalUserView,
+            Person,
+            RegistrationApplication,
+            Session,
+        },
         utils::http_protocol_str,
     },
-    error::BackendResult,
+    error::{BackendError, BackendResult},
     impls::IbisContext,
-    schema::{instance, instance_follow, local_user, person},
+    schema::{
+        api_token,
+        comment,
+        edit,
+        email_token,
+        instance,
+        instance_follow,
+        local_user,
+        oauth_account,
+        person,
+        registration_application,
+        session,
+        upload,
+    },
     utils::generate_keypair,
 };
 use bcrypt::{DEFAULT_COST, hash};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use diesel::{
     AsChangeset,
+    BoolExpressionMethods,
+    Connection,
     ExpressionMethods,
     Insertable,
     JoinOnDsl,
+    PgArrayExpressionMethods,
     PgTextExpressionMethods,
     QueryDsl,
     RunQueryDsl,
+    SelectableHelper,
+    define_sql_function,
+    delete,
+    deserialize::{self, FromSql},
+    dsl::exists,
+    expression::AsExpression,
     insert_into,
+    pg::{Pg, PgValue},
+    select,
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::{Array, Text},
+    update,
 };
-use std::ops::DerefMut;
+use sha2::{Digest, Sha256};
+use std::{io::Write, ops::DerefMut};
 use url::Url;
 
 #[derive(Debug, Clone, Insertable, AsChangeset)]
 #[diesel(table_name = local_user, check_for_backend(diesel::pg::Pg))]
 pub struct LocalUserForm {
-    pub password_encrypted: String,
+    pub password_encrypted: Option<String>,
     pub person_id: PersonId,
     pub admin: bool,
 }
@@ -46,6 +82,7 @@ pub struct PersonInsertForm {
     pub local: bool,
     pub display_name: Option<String>,
     pub bio: Option<String>,
+    pub bot: bool,
 }
 
 #[derive(Debug, Clone, Insertable, AsChangeset)]
@@ -53,6 +90,14 @@ pub struct PersonInsertForm {
 pub struct PersonUpdateForm {
     pub display_name: Option<String>,
     pub bio: Option<String>,
+    pub bot: Option<bool>,
+}
+
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = local_user, check_for_backend(diesel::pg::Pg))]
+pub struct LocalUserUpdateForm {
+    pub hide_bot_edits: Option<bool>,
+    pub email_notifications: Option<bool>,
 }
 
 impl Person {
@@ -82,7 +127,7 @@ impl Person {
 
     pub fn create_local(
         username: String,
-        password: String,
+        password: Option<String>,
         admin: bool,
         context: &IbisContext,
     ) -> BackendResult<LocalUserView> {
@@ -105,6 +150,7 @@ impl Person {
             local: true,
             display_name: None,
             bio: None,
+            bot: false,
         };
 
         let person = insert_into(person::table)
@@ -112,7 +158,7 @@ impl Person {
             .get_result::<Person>(conn.deref_mut())?;
 
         let local_user_form = LocalUserForm {
-            password_encrypted: hash(password, DEFAULT_COST)?,
+            password_encrypted: password.map(|p| hash(p, DEFAULT_COST)).transpose()?,
             person_id: person.id,
             admin,
         };
@@ -190,6 +236,49 @@ impl Person {
             .get_results(conn.deref_mut())?)
     }
 
+    pub fn update_banned(
+        id: PersonId,
+        banned: bool,
+        context: &IbisContext,
+    ) -> BackendResult<Person> {
+        let mut conn = context.db_pool.get()?;
+        Ok(diesel::update(person::table.find(id))
+            .set(person::banned.eq(banned))
+            .get_result(conn.deref_mut())?)
+    }
+
+    pub fn list_banned(context: &IbisContext) -> BackendResult<Vec<Person>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(person::table
+            .filter(person::banned)
+            .order_by(person::username)
+            .get_results(conn.deref_mut())?)
+    }
+
+    /// Delete the account of a user. Edits and comments are reassigned to the ghost user so that
+    /// article history and comment threads stay intact, comments are also marked as deleted.
+    /// Uploads are also kept, as they may be embedded in articles.
+    pub fn delete(id: PersonId, context: &IbisContext) -> BackendResult<()> {
+        let ghost = Person::ghost(context)?;
+        let mut conn = context.db_pool.get()?;
+        conn.transaction(|conn| {
+            update(edit::table)
+                .filter(edit::creator_id.eq(id))
+                .set(edit::creator_id.eq(ghost.id))
+                .execute(conn)?;
+            update(comment::table)
+                .filter(comment::creator_id.eq(id))
+                .set((comment::creator_id.eq(ghost.id), comment::deleted.eq(true)))
+                .execute(conn)?;
+            update(upload::table)
+                .filter(upload::uploader_id.eq(id))
+                .set(upload::uploader_id.eq(ghost.id))
+                .execute(conn)?;
+            delete(person::table.find(id)).execute(conn)?;
+            Ok(())
+        })
+    }
+
     /// Ghost user serves as placeholder for deleted accounts
     pub fn ghost(context: &IbisContext) -> BackendResult<Person> {
         let username = "ghost";
@@ -215,8 +304,541 @@ impl Person {
                 local: true,
                 display_name: None,
                 bio: None,
+                bot: false,
             };
             Person::create(&person_form, context)
         }
     }
 }
+
+impl LocalUser {
+    pub fn update(
+        form: &LocalUserUpdateForm,
+        id: LocalUserId,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        update(local_user::table.find(id))
+            .set(form)
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    pub fn update_password(
+        id: LocalUserId,
+        password: &str,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        update(local_user::table.find(id))
+            .set(local_user::password_encrypted.eq(hash(password, DEFAULT_COST)?))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    /// Store a new secret while two factor authentication is being set up, or remove it. Either
+    /// way two factor authentication is disabled until the user confirms a code.
+    pub fn set_totp_secret(
+        id: LocalUserId,
+        secret: Option<String>,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        update(local_user::table.find(id))
+            .set((
+                local_user::totp_secret.eq(secret),
+                local_user::totp_enabled.eq(false),
+                local_user::totp_recovery_codes.eq(Vec::<String>::new()),
+                local_user::totp_last_step.eq(None::<i64>),
+            ))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    pub fn enable_totp(
+        id: LocalUserId,
+        recovery_codes: &[String],
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        let hashes: Vec<_> = recovery_codes.iter().map(|c| hash_token(c)).collect();
+        update(local_user::table.find(id))
+            .set((
+                local_user::totp_enabled.eq(true),
+                local_user::totp_recovery_codes.eq(hashes),
+            ))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    /// Returns true if no code for this or a later time step was accepted before, and marks the
+    /// step as used.
+    pub fn use_totp_step(id: LocalUserId, step: i64, context: &IbisContext) -> BackendResult<bool> {
+        let mut conn = context.db_pool.get()?;
+        let updated = update(local_user::table.find(id))
+            .filter(
+                local_user::totp_last_step
+                    .is_null()
+                    .or(local_user::totp_last_step.lt(step)),
+            )
+            .set(local_user::totp_last_step.eq(step))
+            .execute(conn.deref_mut())?;
+        Ok(updated == 1)
+    }
+
+    /// Returns true if the recovery code is valid. It is removed so that it cant be used again.
+    pub fn use_totp_recovery_code(
+        id: LocalUserId,
+        code: &str,
+        context: &IbisContext,
+    ) -> BackendResult<bool> {
+        let mut conn = context.db_pool.get()?;
+        let hash = hash_token(code);
+        let updated = update(local_user::table.find(id))
+            .filter(local_user::totp_recovery_codes.contains(vec![hash.clone()]))
+            .set(
+                local_user::totp_recovery_codes
+                    .eq(array_remove(local_user::totp_recovery_codes, hash)),
+            )
+            .execute(conn.deref_mut())?;
+        Ok(updated == 1)
+    }
+
+    /// Set a new email address which needs to be verified, or remove the email address.
+    pub fn update_email(
+        id: LocalUserId,
+        email: Option<String>,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        conn.transaction(|conn| {
+            // verification and password reset links which were sent to the previous address
+            // are not valid anymore
+            delete(email_token::table)
+                .filter(email_token::local_user_id.eq(id))
+                .execute(conn)?;
+            update(local_user::table.find(id))
+                .set((
+                    local_user::email.eq(email),
+                    local_user::email_verified.eq(false),
+                ))
+                .execute(conn)?;
+            Ok(())
+        })
+    }
+
+    /// Mark the email address of the user as verified. Other accounts which entered the same
+    /// address without verifying it lose their claim, and their verification links become
+    /// invalid.
+    pub fn set_email_verified(id: LocalUserId, context: &IbisContext) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        conn.transaction(|conn| {
+            let email: Option<String> = local_user::table
+                .find(id)
+                .select(local_user::email)
+                .get_result(conn)?;
+            let Some(email) = email else {
+                return Err(BackendError::not_found("No email address to verify"));
+            };
+            let verified_elsewhere = select(exists(
+                local_user::table
+                    .filter(local_user::email.eq(&email))
+                    .filter(local_user::email_verified.eq(true))
+                    .filter(local_user::id.ne(id)),
+            ))
+            .get_result::<bool>(conn)?;
+            if verified_elsewhere {
+                return Err(BackendError::conflict("Email is already in use"));
+            }
+            let others = local_user::table
+                .filter(local_user::email.eq(&email))
+                .filter(local_user::id.ne(id))
+                .select(local_user::id);
+            delete(email_token::table)
+                .filter(email_token::local_user_id.eq_any(others))
+                .execute(conn)?;
+            update(local_user::table)
+                .filter(local_user::email.eq(&email))
+                .filter(local_user::id.ne(id))
+                .set(local_user::email.eq(None::<String>))
+                .execute(conn)?;
+            update(local_user::table.find(id))
+                .set(local_user::email_verified.eq(true))
+                .execute(conn)?;
+            Ok(())
+        })
+    }
+
+    pub fn set_last_digest_sent(
+        id: LocalUserId,
+        time: DateTime<Utc>,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        update(local_user::table.find(id))
+            .set(local_user::last_digest_sent.eq(time))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    /// Read the user with this verified email address. Unverified addresses are ignored, as
+    /// they can be entered by anyone.
+    pub fn read_from_email(email: &str, context: &IbisContext) -> BackendResult<LocalUserView> {
+        let mut conn = context.db_pool.get()?;
+        let (local_user, person) = local_user::table
+            .inner_join(person::table)
+            .filter(local_user::email.eq(email))
+            .filter(local_user::email_verified.eq(true))
+            .get_result::<(LocalUser, Person)>(conn.deref_mut())?;
+        Ok(LocalUserView { person, local_user })
+    }
+
+    /// Users who want to receive notifications by email, and didnt get one since `interval`.
+    pub fn list_email_notifications(
+        interval: TimeDelta,
+        context: &IbisContext,
+    ) -> BackendResult<Vec<LocalUserView>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(local_user::table
+            .inner_join(person::table)
+            .filter(local_user::email_verified.eq(true))
+            .filter(local_user::email_notifications.eq(true))
+            .filter(
+                local_user::last_digest_sent
+                    .is_null()
+                    .or(local_user::last_digest_sent.lt(Utc::now() - interval)),
+            )
+            .get_results::<(LocalUser, Person)>(conn.deref_mut())?
+            .into_iter()
+            .map(|(local_user, person)| LocalUserView { person, local_user })
+            .collect())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AsExpression)]
+#[diesel(sql_type = Text)]
+pub enum EmailTokenKind {
+    VerifyEmail,
+    ResetPassword,
+}
+
+/// Secret token which is sent by email to verify the address or reset the password.
+pub struct EmailToken;
+
+impl EmailToken {
+    pub fn create(
+        local_user_id: LocalUserId,
+        kind: EmailTokenKind,
+        token: &str,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        insert_into(email_token::table)
+            .values((
+                email_token::local_user_id.eq(local_user_id),
+                email_token::token_hash.eq(hash_token(token)),
+                email_token::kind.eq(kind),
+            ))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    /// Deletes the token and returns the user it belongs to. Fails if the token doesnt exist or
+    /// is older than `max_age`.
+    pub fn consume(
+        token: &str,
+        kind: EmailTokenKind,
+        max_age: TimeDelta,
+        context: &IbisContext,
+    ) -> BackendResult<LocalUserId> {
+        let mut conn = context.db_pool.get()?;
+        let (local_user_id, published): (LocalUserId, DateTime<Utc>) = delete(email_token::table)
+            .filter(email_token::token_hash.eq(hash_token(token)))
+            .filter(email_token::kind.eq(kind))
+            .returning((email_token::local_user_id, email_token::published))
+            .get_result(conn.deref_mut())
+            .map_err(|_| BackendError::not_found("Invalid token"))?;
+        if published + max_age < Utc::now() {
+            return Err(BackendError::validation("Token is expired"));
+        }
+        Ok(local_user_id)
+    }
+}
+
+/// Account at an OpenID Connect provider which is linked to a local user.
+pub struct OAuthAccount;
+
+impl OAuthAccount {
+    pub fn create(
+        local_user_id: LocalUserId,
+        provider: &str,
+        subject: &str,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        insert_into(oauth_account::table)
+            .values((
+                oauth_account::local_user_id.eq(local_user_id),
+                oauth_account::provider.eq(provider),
+                oauth_account::subject.eq(subject),
+            ))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    /// Returns the local user which is linked to the account at the provider.
+    pub fn read_user(
+        provider: &str,
+        subject: &str,
+        context: &IbisContext,
+    ) -> BackendResult<LocalUserView> {
+        let mut conn = context.db_pool.get()?;
+        let (local_user, person) = oauth_account::table
+            .inner_join(local_user::table.inner_join(person::table))
+            .filter(oauth_account::provider.eq(provider))
+            .filter(oauth_account::subject.eq(subject))
+            .select((LocalUser::as_select(), Person::as_select()))
+            .get_result::<(LocalUser, Person)>(conn.deref_mut())?;
+        Ok(LocalUserView { person, local_user })
+    }
+}
+
+/// Sessions are checked on every request, but the last used time only needs to be roughly
+/// accurate, so it isnt written each time.
+const SESSION_UPDATE_INTERVAL: TimeDelta = TimeDelta::minutes(5);
+
+impl Session {
+    pub fn create(
+        local_user_id: LocalUserId,
+        user_agent: Option<String>,
+        context: &IbisContext,
+    ) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        Ok(insert_into(session::table)
+            .values((
+                session::local_user_id.eq(local_user_id),
+                session::user_agent.eq(user_agent),
+            ))
+            .get_result(conn.deref_mut())?)
+    }
+
+    /// Updates the last used time of the session, at most once per [SESSION_UPDATE_INTERVAL].
+    /// Returns an error if the session doesnt exist, or belongs to another user.
+    pub fn mark_used(
+        id: SessionId,
+        local_user_id: LocalUserId,
+        context: &IbisContext,
+    ) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        let session: Self = session::table
+            .find(id)
+            .filter(session::local_user_id.eq(local_user_id))
+            .get_result(conn.deref_mut())?;
+        if Utc::now() - session.last_used < SESSION_UPDATE_INTERVAL {
+            return Ok(session);
+        }
+        Ok(update(session::table.find(id))
+            .set(session::last_used.eq(Utc::now()))
+            .get_result(conn.deref_mut())?)
+    }
+
+    pub fn list(local_user_id: LocalUserId, context: &IbisContext) -> BackendResult<Vec<Self>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(session::table
+            .filter(session::local_user_id.eq(local_user_id))
+            .order(session::last_used.desc())
+            .get_results(conn.deref_mut())?)
+    }
+
+    pub fn delete(
+        id: SessionId,
+        local_user_id: LocalUserId,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        delete(session::table.find(id))
+            .filter(session::local_user_id.eq(local_user_id))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    /// Delete all sessions of the user, except for the given one.
+    pub fn delete_all(
+        local_user_id: LocalUserId,
+        except: Option<SessionId>,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        let mut query = delete(session::table)
+            .filter(session::local_user_id.eq(local_user_id))
+            .into_boxed();
+        if let Some(except) = except {
+            query = query.filter(session::id.ne(except));
+        }
+        query.execute(conn.deref_mut())?;
+        Ok(())
+    }
+}
+
+impl ApiToken {
+    pub fn create(
+        local_user_id: LocalUserId,
+        name: String,
+        scope: ApiTokenScope,
+        token: &str,
+        context: &IbisContext,
+    ) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        Ok(insert_into(api_token::table)
+            .values((
+                api_token::local_user_id.eq(local_user_id),
+                api_token::name.eq(name),
+                api_token::token_hash.eq(hash_token(token)),
+                api_token::scope.eq(scope),
+            ))
+            .get_result(conn.deref_mut())?)
+    }
+
+    /// Read the user and scope for an api token, and update its last used time at most once per
+    /// [SESSION_UPDATE_INTERVAL].
+    pub fn authenticate(
+        token: &str,
+        context: &IbisContext,
+    ) -> BackendResult<(LocalUserView, ApiTokenScope)> {
+        let mut conn = context.db_pool.get()?;
+        let api_token: ApiToken = api_token::table
+            .filter(api_token::token_hash.eq(hash_token(token)))
+            .get_result(conn.deref_mut())?;
+        if api_token
+            .last_used
+            .is_none_or(|last_used| Utc::now() - last_used >= SESSION_UPDATE_INTERVAL)
+        {
+            update(api_token::table.find(api_token.id))
+                .set(api_token::last_used.eq(Utc::now()))
+                .execute(conn.deref_mut())?;
+        }
+        let (local_user, person) = local_user::table
+            .inner_join(person::table)
+            .filter(local_user::id.eq(api_token.local_user_id))
+            .get_result::<(LocalUser, Person)>(conn.deref_mut())?;
+        Ok((LocalUserView { person, local_user }, api_token.scope))
+    }
+
+    pub fn list(local_user_id: LocalUserId, context: &IbisContext) -> BackendResult<Vec<Self>> {
+        let mut conn = context.db_pool.get()?;
+        Ok(api_token::table
+            .filter(api_token::local_user_id.eq(local_user_id))
+            .order(api_token::published.desc())
+            .get_results(conn.deref_mut())?)
+    }
+
+    pub fn delete(
+        id: ApiTokenId,
+        local_user_id: LocalUserId,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        delete(api_token::table.find(id))
+            .filter(api_token::local_user_id.eq(local_user_id))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+
+    pub fn delete_all(local_user_id: LocalUserId, context: &IbisContext) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        delete(api_token::table)
+            .filter(api_token::local_user_id.eq(local_user_id))
+            .execute(conn.deref_mut())?;
+        Ok(())
+    }
+}
+
+impl RegistrationApplication {
+    /// Marks the user as not approved, so that login is only possible after an admin accepts the
+    /// application.
+    pub fn create(
+        local_user_id: LocalUserId,
+        answer: Option<String>,
+        context: &IbisContext,
+    ) -> BackendResult<Self> {
+        let mut conn = context.db_pool.get()?;
+        conn.transaction(|conn| {
+            update(local_user::table.find(local_user_id))
+                .set(local_user::approved.eq(false))
+                .execute(conn)?;
+            Ok(insert_into(registration_application::table)
+                .values((
+                    registration_application::local_user_id.eq(local_user_id),
+                    registration_application::answer.eq(answer),
+                ))
+                .get_result(conn)?)
+        })
+    }
+
+    /// Approve the user and remove the application. If it is rejected the user account is
+    /// deleted, so that the username can be registered again.
+    pub fn handle(
+        id: RegistrationApplicationId,
+        approve: bool,
+        context: &IbisContext,
+    ) -> BackendResult<()> {
+        let mut conn = context.db_pool.get()?;
+        let (application, person_id): (Self, PersonId) = registration_application::table
+            .find(id)
+            .inner_join(local_user::table)
+            .select((Self::as_select(), local_user::person_id))
+            .get_result(conn.deref_mut())?;
+        if approve {
+            conn.transaction(|conn| {
+                update(local_user::table.find(application.local_user_id))
+                    .set(local_user::approved.eq(true))
+                    .execute(conn)?;
+                delete(registration_application::table.find(id)).execute(conn)?;
+                Ok(())
+            })
+        } else {
+            Person::delete(person_id, context)
+        }
+    }
+}
+
+define_sql_function!(fn array_remove(array: Array<Text>, element: Text) -> Array<Text>);
+
+/// Tokens are stored as hash, so that they cant be used if the database is leaked.
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+impl ToSql<Text, Pg> for ApiTokenScope {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let scope = match self {
+            ApiTokenScope::Read => "read",
+            ApiTokenScope::Edit => "edit",
+            ApiTokenScope::Admin => "admin",
+        };
+        out.write_all(scope.as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl ToSql<Text, Pg> for EmailTokenKind {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let kind = match self {
+            EmailTokenKind::VerifyEmail => "verify_email",
+            EmailTokenKind::ResetPassword => "reset_password",
+        };
+        out.write_all(kind.as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for ApiTokenScope {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"read" => Ok(ApiTokenScope::Read),
+            b"edit" => Ok(ApiTokenScope::Edit),
+            b"admin" => Ok(ApiTokenScope::Admin),
+            _ => Err("Unknown api token scope".into()),
+        }
+    }
+}
@@ -5,7 +5,6 @@ use url::Url;
 pub mod common;
 #[cfg(feature = "ssr")]
 pub mod config;
-#[cfg(feature = "ssr")]
 pub mod error;
 #[cfg(feature = "ssr")]
 pub mod impls;
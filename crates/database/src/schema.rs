@@ -1,5 +1,17 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_token (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        name -> Text,
+        token_hash -> Text,
+        scope -> Text,
+        published -> Timestamptz,
+        last_used -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     article (id) {
         id -> Int4,
@@ -12,6 +24,14 @@ diesel::table! {
         protected -> Bool,
         approved -> Bool,
         published -> Timestamptz,
+        removed -> Bool,
+    }
+}
+
+diesel::table! {
+    article_category (article_id, name) {
+        article_id -> Int4,
+        name -> Text,
     }
 }
 
@@ -22,6 +42,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    article_link (article_id, target_title, target_domain) {
+        article_id -> Int4,
+        target_title -> Text,
+        target_domain -> Text,
+    }
+}
+
+diesel::table! {
+    article_redirect (article_id, title) {
+        article_id -> Int4,
+        title -> Text,
+    }
+}
+
 diesel::table! {
     comment (id) {
         id -> Int4,
@@ -68,6 +103,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    email_token (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        token_hash -> Text,
+        kind -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     instance (id) {
         id -> Int4,
@@ -89,6 +134,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    instance_block (domain) {
+        domain -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     instance_follow (id) {
         id -> Int4,
@@ -119,9 +171,19 @@ diesel::table! {
 diesel::table! {
     local_user (id) {
         id -> Int4,
-        password_encrypted -> Text,
+        password_encrypted -> Nullable<Text>,
         person_id -> Int4,
         admin -> Bool,
+        hide_bot_edits -> Bool,
+        approved -> Bool,
+        email -> Nullable<Text>,
+        email_verified -> Bool,
+        email_notifications -> Bool,
+        last_digest_sent -> Nullable<Timestamptz>,
+        totp_secret -> Nullable<Text>,
+        totp_enabled -> Bool,
+        totp_recovery_codes -> Array<Text>,
+        totp_last_step -> Nullable<Int8>,
     }
 }
 
@@ -137,6 +199,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    oauth_account (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        provider -> Text,
+        subject -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     person (id) {
         id -> Int4,
@@ -153,18 +225,65 @@ diesel::table! {
         display_name -> Nullable<Varchar>,
         #[max_length = 1000]
         bio -> Nullable<Varchar>,
+        banned -> Bool,
+        bot -> Bool,
+    }
+}
+
+diesel::table! {
+    registration_application (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        answer -> Nullable<Text>,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    session (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        user_agent -> Nullable<Text>,
+        published -> Timestamptz,
+        last_used -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    template_usage (article_id, template_title, template_domain) {
+        article_id -> Int4,
+        template_title -> Text,
+        template_domain -> Text,
+    }
+}
+
+diesel::table! {
+    upload (id) {
+        id -> Int4,
+        uploader_id -> Int4,
+        filename -> Text,
+        original_name -> Text,
+        content_type -> Text,
+        size -> Int8,
+        thumbnail -> Nullable<Text>,
+        published -> Timestamptz,
     }
 }
 
+diesel::joinable!(api_token -> local_user (local_user_id));
 diesel::joinable!(article -> instance (instance_id));
+diesel::joinable!(article_category -> article (article_id));
 diesel::joinable!(article_follow -> article (article_id));
 diesel::joinable!(article_follow -> local_user (local_user_id));
+diesel::joinable!(article_link -> article (article_id));
+diesel::joinable!(article_redirect -> article (article_id));
 diesel::joinable!(comment -> article (article_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(conflict -> article (article_id));
 diesel::joinable!(conflict -> person (creator_id));
 diesel::joinable!(edit -> article (article_id));
 diesel::joinable!(edit -> person (creator_id));
+diesel::joinable!(email_token -> local_user (local_user_id));
 diesel::joinable!(instance_follow -> instance (instance_id));
 diesel::joinable!(instance_follow -> person (follower_id));
 diesel::joinable!(local_user -> person (person_id));
@@ -173,18 +292,34 @@ diesel::joinable!(notification -> comment (comment_id));
 diesel::joinable!(notification -> edit (edit_id));
 diesel::joinable!(notification -> local_user (local_user_id));
 diesel::joinable!(notification -> person (creator_id));
+diesel::joinable!(oauth_account -> local_user (local_user_id));
+diesel::joinable!(registration_application -> local_user (local_user_id));
+diesel::joinable!(session -> local_user (local_user_id));
+diesel::joinable!(template_usage -> article (article_id));
+diesel::joinable!(upload -> person (uploader_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_token,
     article,
+    article_category,
     article_follow,
+    article_link,
+    article_redirect,
     comment,
     conflict,
     edit,
+    email_token,
     instance,
+    instance_block,
     instance_follow,
     instance_stats,
     jwt_secret,
     local_user,
     notification,
+    oauth_account,
     person,
+    registration_application,
+    session,
+    template_usage,
+    upload,
 );
@@ -1,4 +1,8 @@
-use crate::{DbUrl, error::BackendResult};
+use crate::{
+    DbUrl,
+    common::DEFAULT_PAGE_LIMIT,
+    error::{BackendError, BackendResult},
+};
 use activitypub_federation::{
     fetch::{collection_id::CollectionId, object_id::ObjectId},
     http_signatures::{Keypair, generate_actor_keypair},
@@ -101,3 +105,23 @@ pub fn generate_keypair() -> BackendResult<Keypair> {
         Ok(generate_actor_keypair()?)
     }
 }
+
+const MAX_PAGE_LIMIT: i64 = 50;
+
+/// Convert optional page (starting at 1) and limit params into limit and offset for queries.
+pub fn limit_and_offset(page: Option<i64>, limit: Option<i64>) -> BackendResult<(i64, i64)> {
+    let page = page.unwrap_or(1);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    if page < 1 {
+        return Err(BackendError::validation("Page must be at least 1"));
+    }
+    if !(1..=MAX_PAGE_LIMIT).contains(&limit) {
+        return Err(BackendError::validation(format!(
+            "Limit must be between 1 and {MAX_PAGE_LIMIT}"
+        )));
+    }
+    let offset = (page - 1)
+        .checked_mul(limit)
+        .ok_or_else(|| BackendError::validation("Page is too large"))?;
+    Ok((limit, offset))
+}
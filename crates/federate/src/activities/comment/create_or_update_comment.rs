@@ -8,6 +8,7 @@ use crate::{
     },
     routes::AnnouncableActivities,
     send_activity_to_instance,
+    validate::validate_not_banned,
 };
 use activitypub_federation::{
     config::Data,
@@ -79,9 +80,11 @@ impl ActivityHandler for CreateOrUpdateComment {
         self.actor.inner()
     }
 
-    async fn verify(&self, _context: &Data<Self::DataType>) -> Result<(), Self::Error> {
+    async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
         verify_domains_match(&self.id, self.object.id.inner())?;
         verify_domains_match(&self.id, self.actor.inner())?;
+        let creator = self.actor.dereference(context).await?;
+        validate_not_banned(&creator)?;
         Ok(())
     }
 
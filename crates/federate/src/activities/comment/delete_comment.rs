@@ -85,6 +85,12 @@ impl ActivityHandler for DeleteComment {
             ..Default::default()
         };
         let comment = self.object.dereference(context).await?;
+        let creator = self.actor.dereference(context).await?;
+        if comment.creator_id != creator.id {
+            return Err(BackendError::forbidden(
+                "Only the creator can delete a comment",
+            ));
+        }
         Comment::update(form, comment.id, context)?;
 
         let instance = Instance::read_for_comment(comment.id, context)?;
@@ -0,0 +1,171 @@
+use crate::{
+    activities::comment::delete_comment::DeleteComment,
+    generate_activity_id,
+    objects::{instance::InstanceWrapper, user::PersonWrapper},
+    routes::UserOrInstance,
+    send_activity,
+};
+use activitypub_federation::{
+    config::Data,
+    fetch::object_id::ObjectId,
+    kinds::activity::DeleteType,
+    protocol::{
+        helpers::deserialize_one_or_many,
+        verification::{verify_domains_match, verify_urls_match},
+    },
+    traits::{ActivityHandler, Actor},
+};
+use ibis_database::{
+    common::{article::Article, comment::Comment, instance::Instance, user::Person},
+    error::{BackendError, BackendResult},
+    impls::IbisContext,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Deletion of a user, article or comment. Other platforms use the same activity for deleting
+/// any kind of object, so the object type is only determined when receiving it.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Delete {
+    pub actor: ObjectId<UserOrInstance>,
+    #[serde(deserialize_with = "deserialize_one_or_many")]
+    pub to: Vec<Url>,
+    pub object: Url,
+    #[serde(rename = "type")]
+    pub kind: DeleteType,
+    pub id: Url,
+}
+
+impl Delete {
+    /// Sent by the user before the local account is deleted, to all known instances.
+    pub async fn send_user(
+        person: &PersonWrapper,
+        context: &Data<IbisContext>,
+    ) -> BackendResult<()> {
+        debug_assert!(person.local);
+        let id = generate_activity_id(context)?;
+        let inboxes: Vec<_> = Instance::list(context)?
+            .into_iter()
+            .map(|i| InstanceWrapper::from(i).shared_inbox_or_inbox())
+            .collect();
+        let delete = Delete {
+            actor: person.ap_id.clone().into(),
+            to: inboxes.clone(),
+            object: person.ap_id.clone().into(),
+            kind: Default::default(),
+            id,
+        };
+        send_activity(person, delete, inboxes, context).await?;
+        Ok(())
+    }
+
+    /// Sent from article origin instance
+    pub async fn send_article(article: &Article, context: &Data<IbisContext>) -> BackendResult<()> {
+        debug_assert!(article.local);
+        let local_instance: InstanceWrapper = Instance::read_local(context)?.into();
+        let id = generate_activity_id(context)?;
+        let delete = Delete {
+            actor: local_instance.ap_id.clone().into(),
+            to: local_instance.follower_ids(context)?,
+            object: article.ap_id.clone().into(),
+            kind: Default::default(),
+            id,
+        };
+        local_instance
+            .send_to_followers(delete, vec![], context)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for Delete {
+    type DataType = IbisContext;
+    type Error = BackendError;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        self.actor.inner()
+    }
+
+    async fn verify(&self, _context: &Data<Self::DataType>) -> Result<(), Self::Error> {
+        verify_domains_match(self.actor.inner(), &self.id)?;
+        verify_domains_match(self.actor.inner(), &self.object)?;
+        Ok(())
+    }
+
+    /// Deleted objects cant be fetched anymore, so they are looked up in the local database.
+    /// Users are removed with their content reassigned to the ghost user, articles are removed
+    /// together with their edits and comments, and comments are marked as deleted.
+    async fn receive(self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
+        let object = self.object.clone().into();
+        if let Ok(person) = Person::read_from_ap_id(&object, context) {
+            verify_urls_match(self.actor.inner(), &self.object)?;
+            if !person.local {
+                Person::delete(person.id, context)?;
+            }
+        } else if let Ok(article) = Article::read_from_ap_id(&object, context) {
+            let UserOrInstance::Instance(_) = self.actor.dereference(context).await? else {
+                return Err(BackendError::forbidden(
+                    "Only instances can delete articles",
+                ));
+            };
+            if !article.local {
+                Article::delete(article.id, context)?;
+            }
+        } else if Comment::read_from_ap_id(&object, context).is_ok() {
+            let delete = DeleteComment {
+                actor: self.actor.into_inner().into(),
+                to: self.to,
+                object: self.object.into(),
+                kind: self.kind,
+                id: self.id,
+            };
+            delete.verify(context).await?;
+            delete.receive(context).await?;
+        } else {
+            return Err(BackendError::not_found(format!(
+                "Deleted object {} not found",
+                self.object
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::routes::InboxActivities;
+    use activitypub_federation::protocol::context::WithContext;
+
+    #[test]
+    #[expect(clippy::unwrap_used)]
+    fn test_parse_delete() {
+        // account deletion as sent by Mastodon
+        let json = r#"{
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://mastodon.example/users/alice#delete",
+            "type": "Delete",
+            "actor": "https://mastodon.example/users/alice",
+            "to": ["https://www.w3.org/ns/activitystreams#Public"],
+            "object": "https://mastodon.example/users/alice"
+        }"#;
+        let activity: WithContext<InboxActivities> = serde_json::from_str(json).unwrap();
+        assert!(matches!(activity.inner(), InboxActivities::Delete(_)));
+
+        // comment deletion as sent by Ibis
+        let json = r#"{
+            "id": "https://ibis.example/activity/123",
+            "type": "Delete",
+            "actor": "https://ibis.example/user/alice",
+            "to": "https://ibis.example/",
+            "object": "https://ibis.example/comment/1"
+        }"#;
+        let activity: InboxActivities = serde_json::from_str(json).unwrap();
+        assert!(matches!(activity, InboxActivities::Delete(_)));
+    }
+}
@@ -17,6 +17,7 @@ pub mod accept;
 pub mod announce;
 pub mod comment;
 pub mod create_article;
+pub mod delete;
 pub mod follow;
 pub mod reject;
 pub mod undo_follow;
@@ -40,8 +41,7 @@ pub async fn submit_article_update(
         false,
     )?;
     if original_article.local {
-        let edit = Edit::create(&form, context)?;
-        let updated_article = Article::update_text(edit.article_id, &new_text, context)?;
+        let (_, updated_article) = Article::update_text_with_edit(&form, &new_text, context)?;
 
         UpdateLocalArticle::send(updated_article.into(), vec![], context).await?;
     } else {
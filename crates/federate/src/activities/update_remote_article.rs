@@ -6,6 +6,7 @@ use crate::{
         instance::InstanceWrapper,
     },
     send_activity,
+    validate::validate_not_banned,
 };
 use activitypub_federation::{
     config::Data,
@@ -81,6 +82,8 @@ impl ActivityHandler for UpdateRemoteArticle {
     async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
         let article = Article::read_from_ap_id(&self.object.object.clone().into(), context)?;
         can_edit_article(&article, false)?;
+        let creator = self.object.attributed_to.dereference(context).await?;
+        validate_not_banned(&creator)?;
         Ok(())
     }
 
@@ -8,16 +8,21 @@ use activitypub_federation::{
 };
 use async_trait::async_trait;
 use ibis_database::{
-    common::utils::http_protocol_str,
-    config::IbisConfig,
+    common::{instance::InstanceBlock, utils::http_protocol_str},
     error::BackendResult,
     impls::IbisContext,
 };
+use moka::sync::Cache;
 use objects::{instance::InstanceWrapper, user::PersonWrapper};
 use rand::{Rng, distributions::Alphanumeric, thread_rng};
 use routes::AnnouncableActivities;
 use serde::Serialize;
-use std::fmt::Debug;
+use std::{
+    collections::HashSet,
+    fmt::Debug,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
 use url::Url;
 
 pub mod activities;
@@ -25,6 +30,7 @@ pub mod nodeinfo;
 pub mod objects;
 pub mod routes;
 pub mod validate;
+pub mod webfinger;
 
 pub async fn send_activity<Activity, ActorType: Actor>(
     actor: &ActorType,
@@ -56,15 +62,29 @@ pub async fn send_activity_to_instance(
     Ok(())
 }
 
+/// Domains blocked by the admin. Urls are verified for every federation request, so the
+/// blocklist is cached instead of reading it from the database each time.
+static BLOCKED_INSTANCES: LazyLock<Cache<(), Arc<HashSet<String>>>> = LazyLock::new(|| {
+    Cache::builder()
+        .time_to_live(Duration::from_secs(60))
+        .build()
+});
+
+/// Needs to be called when instance blocks are changed, so that they take effect immediately.
+pub fn clear_blocked_instances_cache() {
+    BLOCKED_INSTANCES.invalidate(&());
+}
+
 #[derive(Clone)]
-pub struct VerifyUrlData(pub IbisConfig);
+pub struct VerifyUrlData(pub IbisContext);
 
 #[async_trait]
 impl UrlVerifier for VerifyUrlData {
-    /// Check domain against allowlist and blocklist from config file.
+    /// Check domain against allowlist and blocklist from config file, and against instances
+    /// which were blocked by the admin.
     async fn verify(&self, url: &Url) -> Result<(), ActivityPubError> {
         let domain = url.domain().expect("url has domain");
-        if let Some(allowlist) = &self.0.federation.allowlist {
+        if let Some(allowlist) = &self.0.config.federation.allowlist {
             let allowlist = allowlist.split(',').collect::<Vec<_>>();
             if !allowlist.contains(&domain) {
                 return Err(ActivityPubError::Other(format!(
@@ -72,13 +92,28 @@ impl UrlVerifier for VerifyUrlData {
                 )));
             }
         }
-        if let Some(blocklist) = &self.0.federation.blocklist {
-            let blocklist = blocklist.split(',').collect::<Vec<_>>();
-            if blocklist.contains(&domain) {
-                return Err(ActivityPubError::Other(format!(
-                    "Domain {domain} is blocked"
-                )));
-            }
+        let blocked_in_config = self
+            .0
+            .config
+            .federation
+            .blocklist
+            .as_ref()
+            .is_some_and(|b| b.split(',').any(|b| b == domain));
+        // Blocks from admin may include the port, same as `Instance.domain`
+        let domain_with_port = match url.port() {
+            Some(port) => format!("{domain}:{port}"),
+            None => domain.to_string(),
+        };
+        let blocked = BLOCKED_INSTANCES
+            .try_get_with((), || {
+                let blocks = InstanceBlock::list(&self.0)?;
+                BackendResult::Ok(Arc::new(blocks.into_iter().map(|b| b.domain).collect()))
+            })
+            .map_err(|e| ActivityPubError::Other(e.to_string()))?;
+        if blocked_in_config || blocked.contains(domain) || blocked.contains(&domain_with_port) {
+            return Err(ActivityPubError::Other(format!(
+                "Domain {domain} is blocked"
+            )));
         }
         Ok(())
     }
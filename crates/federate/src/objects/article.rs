@@ -3,7 +3,10 @@ use crate::validate::validate_article_title;
 use activitypub_federation::{
     config::Data,
     fetch::{collection_id::CollectionId, object_id::ObjectId},
-    kinds::{object::ArticleType, public},
+    kinds::{
+        object::{ArticleType, DocumentType},
+        public,
+    },
     protocol::{
         helpers::deserialize_one_or_many,
         verification::{verify_domains_match, verify_is_remote_object},
@@ -13,10 +16,13 @@ use activitypub_federation::{
 use ibis_database::{
     common::{
         article::{Article, EditVersion},
+        category::parse_categories,
         instance::Instance,
+        upload::Upload,
+        utils::http_protocol_str,
     },
     error::BackendError,
-    impls::{IbisContext, article::DbArticleForm},
+    impls::{IbisContext, article::DbArticleForm, upload::media_url},
 };
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
@@ -36,6 +42,41 @@ pub struct ApubArticle {
     content: String,
     name: String,
     protected: bool,
+    /// Uploaded files which are embedded in the article text
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachment: Vec<ApubMedia>,
+    /// Categories of the article, for other Fediverse software. They are also contained in the
+    /// text, which is where Ibis reads them from when receiving an article.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag: Vec<ApubCategory>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApubMedia {
+    #[serde(rename = "type")]
+    pub kind: DocumentType,
+    pub url: Url,
+    pub media_type: String,
+    pub name: String,
+}
+
+/// Same type which Mastodon and others use for hashtags
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub enum HashtagType {
+    #[default]
+    Hashtag,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApubCategory {
+    #[serde(rename = "type")]
+    pub kind: HashtagType,
+    /// Category page on the instance of the article
+    pub href: Url,
+    /// Category name with leading `#`, as other Fediverse software expects for hashtags
+    pub name: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -69,6 +110,13 @@ impl Object for ArticleWrapper {
         Ok(article.map(Into::into))
     }
 
+    async fn delete(self, context: &Data<Self::DataType>) -> Result<(), Self::Error> {
+        if !self.local {
+            Article::delete(self.id, context)?;
+        }
+        Ok(())
+    }
+
     async fn into_json(self, context: &Data<Self::DataType>) -> Result<Self::Kind, Self::Error> {
         let local_instance: InstanceWrapper = Instance::read_local(context)?.into();
         Ok(ApubArticle {
@@ -81,6 +129,8 @@ impl Object for ArticleWrapper {
             content: self.text.clone(),
             name: self.title.clone(),
             protected: self.protected,
+            attachment: embedded_uploads(&self.text, context)?,
+            tag: categories(&self.text, context)?,
         })
     }
 
@@ -109,6 +159,12 @@ impl Object for ArticleWrapper {
             approved: true,
         };
         form.title = validate_article_title(&form.title)?;
+        // article was moved on origin instance, keep the previous title as redirect
+        if let Ok(existing) = Article::read_from_ap_id(&form.ap_id, context) {
+            if existing.title != form.title {
+                Article::update_title(existing.id, &form.title, context)?;
+            }
+        }
         let article = Article::create_or_update(form, context)?;
 
         json.edits.dereference(&article, context).await?;
@@ -116,3 +172,49 @@ impl Object for ArticleWrapper {
         Ok(article.into())
     }
 }
+
+fn categories(text: &str, context: &IbisContext) -> Result<Vec<ApubCategory>, BackendError> {
+    parse_categories(text)
+        .into_iter()
+        .map(|name| {
+            let href = format!(
+                "{}://{}/category/{name}",
+                http_protocol_str(),
+                context.config.federation.domain
+            );
+            Ok(ApubCategory {
+                kind: Default::default(),
+                href: Url::parse(&href)?,
+                name: format!("#{name}"),
+            })
+        })
+        .collect()
+}
+
+/// Finds local uploads which are embedded in the text, by their url.
+fn embedded_uploads(text: &str, context: &IbisContext) -> Result<Vec<ApubMedia>, BackendError> {
+    let prefix = media_url("", context);
+    let filenames: Vec<String> = text
+        .match_indices(&prefix)
+        .map(|(i, _)| {
+            text[i + prefix.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+                .collect()
+        })
+        .collect();
+    if filenames.is_empty() {
+        return Ok(vec![]);
+    }
+    Upload::read_from_filenames(&filenames, context)?
+        .into_iter()
+        .map(|upload| {
+            Ok(ApubMedia {
+                kind: Default::default(),
+                url: Url::parse(&media_url(&upload.filename, context))?,
+                media_type: upload.content_type,
+                name: upload.original_name,
+            })
+        })
+        .collect()
+}
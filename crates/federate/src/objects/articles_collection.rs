@@ -1,9 +1,12 @@
-use super::article::ArticleWrapper;
+use super::{
+    article::ArticleWrapper,
+    collection_page::{ApubCollectionPage, COLLECTION_PAGE_SIZE, collection_page_url},
+};
 use crate::objects::article::ApubArticle;
 use activitypub_federation::{
     config::Data,
     fetch::collection_id::CollectionId,
-    kinds::collection::CollectionType,
+    kinds::collection::OrderedCollectionType,
     protocol::verification::verify_domains_match,
     traits::{Collection, Object},
 };
@@ -12,20 +15,27 @@ use ibis_database::{
     common::{article::Article, utils::http_protocol_str},
     error::{BackendError, BackendResult},
     impls::IbisContext,
+    utils::limit_and_offset,
 };
 use log::warn;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use url::Url;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApubArticleCollection {
-    pub r#type: CollectionType,
+    pub r#type: OrderedCollectionType,
     pub id: Url,
     pub total_items: i32,
+    /// Only used by older versions which dont paginate the collection
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<ApubArticle>,
+    pub first: Option<Url>,
 }
 
+pub type ApubArticleCollectionPage = ApubCollectionPage<ApubArticle>;
+
 #[derive(Clone, Debug)]
 pub struct ArticleCollection(());
 
@@ -36,6 +46,42 @@ pub fn local_articles_url(domain: &str) -> BackendResult<CollectionId<ArticleCol
     ))?)
 }
 
+impl ArticleCollection {
+    pub async fn read_local_page(
+        page: i64,
+        context: &Data<IbisContext>,
+    ) -> BackendResult<ApubArticleCollectionPage> {
+        let (limit, offset) = limit_and_offset(Some(page), Some(COLLECTION_PAGE_SIZE))?;
+        let local_articles = Article::read_local_paged(limit, offset, context)?;
+        let articles = try_join_all(
+            local_articles
+                .into_iter()
+                .map(ArticleWrapper)
+                .map(|a| a.into_json(context))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+        let id = local_articles_url(&context.config.federation.domain)?.into();
+        let total_items = Article::count_local(context)?;
+        ApubCollectionPage::new(&id, page, total_items, articles)
+    }
+
+    async fn receive_articles(articles: Vec<ApubArticle>, context: &Data<IbisContext>) {
+        let articles = articles
+            .into_iter()
+            .filter(|i| !i.id.is_local(context))
+            .map(|article| async {
+                let id = article.id.clone();
+                let res = ArticleWrapper::from_json(article, context).await;
+                if let Err(e) = &res {
+                    warn!("Failed to synchronize article {id}: {e}");
+                }
+                res
+            });
+        join_all(articles).await;
+    }
+}
+
 #[async_trait::async_trait]
 impl Collection for ArticleCollection {
     type Owner = ();
@@ -47,20 +93,13 @@ impl Collection for ArticleCollection {
         _owner: &Self::Owner,
         context: &Data<Self::DataType>,
     ) -> Result<Self::Kind, Self::Error> {
-        let local_articles = Article::read_all(Some(true), None, context)?;
-        let articles = try_join_all(
-            local_articles
-                .into_iter()
-                .map(ArticleWrapper)
-                .map(|a| a.into_json(context))
-                .collect::<Vec<_>>(),
-        )
-        .await?;
+        let id = local_articles_url(&context.config.federation.domain)?.into();
         let collection = ApubArticleCollection {
             r#type: Default::default(),
-            id: local_articles_url(&context.config.federation.domain)?.into(),
-            total_items: articles.len() as i32,
-            items: articles,
+            total_items: Article::count_local(context)? as i32,
+            items: vec![],
+            first: Some(collection_page_url(&id, 1)),
+            id,
         };
         Ok(collection)
     }
@@ -74,24 +113,28 @@ impl Collection for ArticleCollection {
         Ok(())
     }
 
+    /// Receive articles which are included inline, then walk through all the collection pages.
     async fn from_json(
         apub: Self::Kind,
         _owner: &Self::Owner,
         context: &Data<Self::DataType>,
     ) -> Result<Self, Self::Error> {
-        let articles = apub
-            .items
-            .into_iter()
-            .filter(|i| !i.id.is_local(context))
-            .map(|article| async {
-                let id = article.id.clone();
-                let res = ArticleWrapper::from_json(article, context).await;
-                if let Err(e) = &res {
-                    warn!("Failed to synchronize article {id}: {e}");
+        Self::receive_articles(apub.items, context).await;
+
+        // Stop if a page links to a page which was already fetched, to avoid endless loops
+        let mut visited = HashSet::new();
+        let mut next = apub.first;
+        while let Some(url) = next.filter(|url| visited.insert(url.clone())) {
+            let page = match ApubArticleCollectionPage::fetch(&url, &apub.id, context).await {
+                Ok(page) => page,
+                Err(e) => {
+                    warn!("Failed to fetch articles page {url}: {e}");
+                    break;
                 }
-                res
-            });
-        join_all(articles).await;
+            };
+            next = page.next;
+            Self::receive_articles(page.ordered_items, context).await;
+        }
 
         Ok(ArticleCollection(()))
     }
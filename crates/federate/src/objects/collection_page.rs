@@ -0,0 +1,72 @@
+use activitypub_federation::{
+    config::Data,
+    fetch::fetch_object_http,
+    kinds::collection::OrderedCollectionPageType,
+    protocol::verification::verify_domains_match,
+};
+use ibis_database::{error::BackendResult, impls::IbisContext};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use url::Url;
+
+/// Number of items in each page of a collection.
+pub const COLLECTION_PAGE_SIZE: i64 = 50;
+
+/// Single page of an ordered collection. Collections only link to the first page, and each
+/// page links to the next one.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApubCollectionPage<T> {
+    pub r#type: OrderedCollectionPageType,
+    pub id: Url,
+    pub part_of: Url,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<Url>,
+    pub ordered_items: Vec<T>,
+}
+
+impl<T> ApubCollectionPage<T> {
+    /// Build the page with the given number (starting at 1). `total_items` is used to decide
+    /// if there is a next page.
+    pub fn new(
+        collection_id: &Url,
+        page: i64,
+        total_items: i64,
+        ordered_items: Vec<T>,
+    ) -> BackendResult<Self> {
+        let next = page
+            .checked_mul(COLLECTION_PAGE_SIZE)
+            .is_some_and(|count| count < total_items)
+            .then(|| collection_page_url(collection_id, page + 1));
+        Ok(ApubCollectionPage {
+            r#type: Default::default(),
+            id: collection_page_url(collection_id, page),
+            part_of: collection_id.clone(),
+            next,
+            ordered_items,
+        })
+    }
+
+    /// Fetch a page of a remote collection. The page needs to be on the same domain as the
+    /// collection.
+    pub async fn fetch(
+        url: &Url,
+        collection_id: &Url,
+        context: &Data<IbisContext>,
+    ) -> BackendResult<Self>
+    where
+        T: DeserializeOwned,
+    {
+        verify_domains_match(url, collection_id)?;
+        let page: Self = fetch_object_http(url, context).await?.object;
+        verify_domains_match(&page.id, collection_id)?;
+        Ok(page)
+    }
+}
+
+pub fn collection_page_url(collection_id: &Url, page: i64) -> Url {
+    let mut url = collection_id.clone();
+    url.query_pairs_mut()
+        .clear()
+        .append_pair("page", &page.to_string());
+    url
+}
@@ -1,4 +1,7 @@
-use super::edit::EditWrapper;
+use super::{
+    collection_page::{ApubCollectionPage, COLLECTION_PAGE_SIZE, collection_page_url},
+    edit::EditWrapper,
+};
 use crate::objects::edit::ApubEdit;
 use activitypub_federation::{
     config::Data,
@@ -9,11 +12,13 @@ use activitypub_federation::{
 use futures::{future, future::try_join_all};
 use ibis_database::{
     common::article::{Article, Edit},
-    error::BackendError,
+    error::{BackendError, BackendResult},
     impls::IbisContext,
+    utils::limit_and_offset,
 };
 use log::warn;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use url::Url;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -22,12 +27,55 @@ pub struct ApubEditCollection {
     pub r#type: OrderedCollectionType,
     pub id: Url,
     pub total_items: i32,
+    /// Only used by older versions which dont paginate the collection
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<ApubEdit>,
+    pub first: Option<Url>,
 }
 
+pub type ApubEditCollectionPage = ApubCollectionPage<ApubEdit>;
+
 #[derive(Clone, Debug)]
 pub struct EditCollection();
 
+impl EditCollection {
+    pub async fn read_local_page(
+        article: &Article,
+        page: i64,
+        context: &Data<IbisContext>,
+    ) -> BackendResult<ApubEditCollectionPage> {
+        let article = Article::read(article.id, context)?;
+        let (limit, offset) = limit_and_offset(Some(page), Some(COLLECTION_PAGE_SIZE))?;
+        let edits = Edit::list_for_article(article.id, Some(limit), Some(offset), context)?;
+        let edits = future::try_join_all(
+            edits
+                .into_iter()
+                .map(EditWrapper)
+                .map(|e| e.into_json(context))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+        let total_items = Edit::count_for_article(article.id, context)?;
+        ApubCollectionPage::new(article.edits_id()?.inner(), page, total_items, edits)
+    }
+
+    async fn receive_edits(
+        edits: Vec<ApubEdit>,
+        owner: &Article,
+        context: &Data<IbisContext>,
+    ) -> BackendResult<()> {
+        try_join_all(
+            edits
+                .into_iter()
+                .map(|i| EditWrapper::from_json(i, context)),
+        )
+        .await
+        .map_err(|e| warn!("Failed to synchronize edits for {}: {e}", owner.ap_id))
+        .ok();
+        Ok(())
+    }
+}
+
 #[async_trait::async_trait]
 impl Collection for EditCollection {
     type Owner = Article;
@@ -40,20 +88,13 @@ impl Collection for EditCollection {
         context: &Data<Self::DataType>,
     ) -> Result<Self::Kind, Self::Error> {
         let article = Article::read(article.id, context)?;
-        let edits = Edit::list_for_article(article.id, context)?;
-        let edits = future::try_join_all(
-            edits
-                .into_iter()
-                .map(EditWrapper)
-                .map(|e| e.into_json(context))
-                .collect::<Vec<_>>(),
-        )
-        .await?;
+        let id = article.edits_id()?.into();
         let collection = ApubEditCollection {
             r#type: Default::default(),
-            id: article.edits_id()?.into(),
-            total_items: edits.len() as i32,
-            items: edits,
+            total_items: Edit::count_for_article(article.id, context)? as i32,
+            items: vec![],
+            first: Some(collection_page_url(&id, 1)),
+            id,
         };
         Ok(collection)
     }
@@ -67,19 +108,29 @@ impl Collection for EditCollection {
         Ok(())
     }
 
+    /// Receive edits which are included inline, then walk through all the collection pages.
+    /// Edits are inserted page by page, so that huge histories dont need to be kept in memory.
     async fn from_json(
         apub: Self::Kind,
         owner: &Self::Owner,
         context: &Data<Self::DataType>,
     ) -> Result<Self, Self::Error> {
-        try_join_all(
-            apub.items
-                .into_iter()
-                .map(|i| EditWrapper::from_json(i, context)),
-        )
-        .await
-        .map_err(|e| warn!("Failed to synchronize edits for {}: {e}", owner.ap_id))
-        .ok();
+        Self::receive_edits(apub.items, owner, context).await?;
+
+        // Stop if a page links to a page which was already fetched, to avoid endless loops
+        let mut visited = HashSet::new();
+        let mut next = apub.first;
+        while let Some(url) = next.filter(|url| visited.insert(url.clone())) {
+            let page = match ApubEditCollectionPage::fetch(&url, &apub.id, context).await {
+                Ok(page) => page,
+                Err(e) => {
+                    warn!("Failed to fetch edits page {url}: {e}");
+                    break;
+                }
+            };
+            next = page.next;
+            Self::receive_edits(page.ordered_items, owner, context).await?;
+        }
         Ok(EditCollection())
     }
 }
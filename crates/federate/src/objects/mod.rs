@@ -1,9 +1,11 @@
 pub mod article;
 mod article_or_comment;
 pub mod articles_collection;
+pub mod collection_page;
 pub mod comment;
 pub mod edit;
 pub mod edits_collection;
 pub mod instance;
 pub mod instance_collection;
+pub mod tombstone;
 pub mod user;
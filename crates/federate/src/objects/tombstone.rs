@@ -0,0 +1,21 @@
+use activitypub_federation::kinds::object::TombstoneType;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Served in place of objects which were deleted.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Tombstone {
+    pub id: Url,
+    #[serde(rename = "type")]
+    pub kind: TombstoneType,
+}
+
+impl Tombstone {
+    pub fn new(id: Url) -> Self {
+        Tombstone {
+            id,
+            kind: Default::default(),
+        }
+    }
+}
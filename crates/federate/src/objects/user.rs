@@ -27,6 +27,9 @@ pub struct ApubUser {
     summary: Option<String>,
     inbox: Url,
     public_key: PublicKey,
+    /// Not using `type: Service` for bots because that is already used by instance actors.
+    #[serde(default)]
+    bot: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -74,6 +77,7 @@ impl Object for PersonWrapper {
             public_key: self.public_key(),
             name: self.display_name.clone(),
             summary: self.bio.clone(),
+            bot: self.bot,
         })
     }
 
@@ -100,6 +104,7 @@ impl Object for PersonWrapper {
             local: false,
             display_name: json.name,
             bio: json.summary,
+            bot: json.bot,
         };
         Person::create(&form, context).map(Into::into)
     }
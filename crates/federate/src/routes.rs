@@ -17,6 +17,7 @@ use crate::{
         accept::Accept,
         announce::AnnounceActivity,
         create_article::CreateArticle,
+        delete::Delete,
         follow::Follow,
         reject::RejectEdit,
         undo_follow::UndoFollow,
@@ -24,10 +25,10 @@ use crate::{
         update_remote_article::UpdateRemoteArticle,
     },
     objects::{
-        article::ApubArticle,
-        articles_collection::{ApubArticleCollection, ArticleCollection},
-        edits_collection::{ApubEditCollection, EditCollection},
+        articles_collection::ArticleCollection,
+        edits_collection::EditCollection,
         instance::ApubInstance,
+        tombstone::Tombstone,
         user::ApubUser,
     },
 };
@@ -42,8 +43,9 @@ use activitypub_federation::{
 };
 use axum::{
     Router,
-    extract::Path,
-    response::IntoResponse,
+    extract::{Path, Query},
+    http::StatusCode,
+    response::{IntoResponse, Response},
     routing::{get, post},
 };
 use axum_macros::debug_handler;
@@ -93,12 +95,23 @@ async fn http_get_person(
     Ok(FederationJson(WithContext::new_default(json_person)))
 }
 
+/// Query parameter for collection pages. Without it the collection summary is returned.
+#[derive(Deserialize)]
+struct PageQuery {
+    page: Option<i64>,
+}
+
 #[debug_handler]
 async fn http_get_all_articles(
+    Query(query): Query<PageQuery>,
     context: Data<IbisContext>,
-) -> BackendResult<FederationJson<WithContext<ApubArticleCollection>>> {
+) -> BackendResult<Response> {
+    if let Some(page) = query.page {
+        let page = ArticleCollection::read_local_page(page, &context).await?;
+        return Ok(FederationJson(WithContext::new_default(page)).into_response());
+    }
     let collection = ArticleCollection::read_local(&(), &context).await?;
-    Ok(FederationJson(WithContext::new_default(collection)))
+    Ok(FederationJson(WithContext::new_default(collection)).into_response())
 }
 
 #[debug_handler]
@@ -113,22 +126,30 @@ async fn http_get_linked_instances(
 async fn http_get_article(
     Path(title): Path<String>,
     context: Data<IbisContext>,
-) -> BackendResult<FederationJson<WithContext<ApubArticle>>> {
-    let article: ArticleWrapper = Article::read_view((&title, None), None, &context)?
-        .article
-        .into();
+) -> BackendResult<Response> {
+    let article: ArticleWrapper = Article::read_local_from_title(&title, &context)?.into();
+    if article.removed {
+        let tombstone = Tombstone::new(article.ap_id.clone().into());
+        let json = FederationJson(WithContext::new_default(tombstone));
+        return Ok((StatusCode::GONE, json).into_response());
+    }
     let json = article.into_json(&context).await?;
-    Ok(FederationJson(WithContext::new_default(json)))
+    Ok(FederationJson(WithContext::new_default(json)).into_response())
 }
 
 #[debug_handler]
 async fn http_get_article_edits(
     Path(title): Path<String>,
+    Query(query): Query<PageQuery>,
     context: Data<IbisContext>,
-) -> BackendResult<FederationJson<WithContext<ApubEditCollection>>> {
+) -> BackendResult<Response> {
     let article = Article::read_view((&title, None), None, &context)?;
+    if let Some(page) = query.page {
+        let page = EditCollection::read_local_page(&article.article, page, &context).await?;
+        return Ok(FederationJson(WithContext::new_default(page)).into_response());
+    }
     let json = EditCollection::read_local(&article.article, &context).await?;
-    Ok(FederationJson(WithContext::new_default(json)))
+    Ok(FederationJson(WithContext::new_default(json)).into_response())
 }
 
 #[debug_handler]
@@ -150,6 +171,8 @@ pub enum InboxActivities {
     UndoFollow(UndoFollow),
     Accept(Accept),
     CreateArticle(CreateArticle),
+    // Needs to be before AnnouncableActivities which contains DeleteComment
+    Delete(Delete),
     UpdateLocalArticle(UpdateLocalArticle),
     UpdateRemoteArticle(UpdateRemoteArticle),
     RejectEdit(RejectEdit),
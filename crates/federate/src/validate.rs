@@ -1,5 +1,7 @@
-use anyhow::anyhow;
-use ibis_database::error::BackendResult;
+use ibis_database::{
+    common::user::Person,
+    error::{BackendError, BackendResult},
+};
 use regex::Regex;
 use std::sync::LazyLock;
 
@@ -9,11 +11,26 @@ pub fn validate_article_title(title: &str) -> BackendResult<String> {
         LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9_]{3,100}$").expect("compile regex"));
     let title = title.replace(' ', "_");
     if !TITLE_REGEX.is_match(&title) {
-        return Err(anyhow!("Invalid title").into());
+        return Err(BackendError::validation("Invalid title"));
     }
     Ok(title)
 }
 
+/// Basic sanity check for email addresses, returns the address in lowercase. Whether the
+/// address actually exists is confirmed with a verification email.
+pub fn validate_email(email: &str) -> BackendResult<String> {
+    let email = email.trim().to_lowercase();
+    let valid = email.len() <= 254
+        && !email.contains(|c: char| c.is_whitespace() || c.is_control() || "<>,;".contains(c))
+        && email.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty() && domain.contains('.') && !domain.contains('@')
+        });
+    if !valid {
+        return Err(BackendError::validation("Invalid email address"));
+    }
+    Ok(email)
+}
+
 pub fn validate_user_name(name: &str) -> BackendResult<()> {
     #[allow(clippy::expect_used)]
     static VALID_ACTOR_NAME_REGEX: LazyLock<Regex> =
@@ -22,14 +39,14 @@ pub fn validate_user_name(name: &str) -> BackendResult<()> {
     if VALID_ACTOR_NAME_REGEX.is_match(name) {
         Ok(())
     } else {
-        Err(anyhow!("Invalid username").into())
+        Err(BackendError::validation("Invalid username"))
     }
 }
 
 pub fn validate_display_name(name: &Option<String>) -> BackendResult<()> {
     if let Some(name) = name {
         if name.contains('@') || name.len() < 3 || name.len() > 20 {
-            return Err(anyhow!("Invalid displayname").into());
+            return Err(BackendError::validation("Invalid displayname"));
         }
     }
     Ok(())
@@ -37,14 +54,24 @@ pub fn validate_display_name(name: &Option<String>) -> BackendResult<()> {
 
 pub fn validate_comment_max_depth(depth: i32) -> BackendResult<()> {
     if depth > 50 {
-        return Err(anyhow!("Max comment depth reached").into());
+        return Err(BackendError::validation("Max comment depth reached"));
+    }
+    Ok(())
+}
+
+pub fn validate_not_banned(person: &Person) -> BackendResult<()> {
+    if person.banned {
+        return Err(BackendError::forbidden(format!(
+            "User {} is banned",
+            person.username
+        )));
     }
     Ok(())
 }
 
 pub fn validate_not_empty(text: &str) -> BackendResult<()> {
     if text.trim().len() < 2 {
-        return Err(anyhow!("Empty text submitted").into());
+        return Err(BackendError::validation("Empty text submitted"));
     }
     Ok(())
 }
@@ -59,3 +86,16 @@ fn test_validate_article_title() {
     assert!(validate_article_title(&"long".to_string().repeat(100)).is_err());
     assert!(validate_article_title("a").is_err());
 }
+
+#[test]
+#[expect(clippy::unwrap_used)]
+fn test_validate_email() {
+    assert_eq!(
+        validate_email(" Alice@Example.com ").unwrap(),
+        "alice@example.com"
+    );
+    assert!(validate_email("alice").is_err());
+    assert!(validate_email("@example.com").is_err());
+    assert!(validate_email("alice@localhost").is_err());
+    assert!(validate_email("alice@example.com\r\nBcc: eve@example.com").is_err());
+}
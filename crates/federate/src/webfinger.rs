@@ -0,0 +1,158 @@
+use crate::VerifyUrlData;
+use activitypub_federation::{
+    config::{Data, UrlVerifier},
+    fetch::{
+        object_id::ObjectId,
+        webfinger::{WEBFINGER_CONTENT_TYPE, Webfinger, build_webfinger_response_with_type},
+    },
+    traits::Object,
+};
+use axum::{Json, Router, extract::Query, routing::get};
+use axum_macros::debug_handler;
+use ibis_database::{
+    common::{article::Article, instance::Instance, user::Person, utils::http_protocol_str},
+    error::{BackendError, BackendResult},
+    impls::{IbisContext, article::ArticleViewQuery},
+};
+use percent_encoding::percent_decode_str;
+use reqwest::{Client, header::ACCEPT, redirect::Policy};
+use serde::Deserialize;
+use std::{sync::LazyLock, time::Duration};
+use url::Url;
+
+/// Used for webfinger lookups. Redirects are disabled because their target urls can't be
+/// verified.
+static WEBFINGER_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    let timeout = Duration::from_secs(10);
+    Client::builder()
+        .redirect(Policy::none())
+        .timeout(timeout)
+        .connect_timeout(timeout)
+        .build()
+        .expect("init webfinger client")
+});
+
+pub fn config() -> Router<()> {
+    Router::new().route("/.well-known/webfinger", get(webfinger))
+}
+
+#[derive(Deserialize)]
+struct WebfingerQuery {
+    resource: String,
+}
+
+/// Answers queries of the form `acct:name@example.com`. The name can refer to a local user or
+/// a local article. The instance actor itself is available under `acct:example.com@example.com`.
+#[debug_handler]
+async fn webfinger(
+    Query(query): Query<WebfingerQuery>,
+    context: Data<IbisContext>,
+) -> BackendResult<Json<Webfinger>> {
+    let (name, domain) = split_handle(&query.resource)?;
+    if domain != context.domain() {
+        return Err(BackendError::not_found(format!(
+            "Webfinger domain {domain} does not match local domain"
+        )));
+    }
+
+    let mut urls: Vec<(Url, Option<&str>)> = vec![];
+    if name == domain {
+        let instance = Instance::read_local(&context)?;
+        urls.push((instance.ap_id.into(), Some("Service")));
+    }
+    if let Ok(user) = Person::read_local_from_name(name, &context) {
+        urls.push((user.person.ap_id.into(), Some("Person")));
+    }
+    if let Ok(article) = Article::read_view(ArticleViewQuery::Name(name, None), None, &context) {
+        urls.push((article.article.ap_id.into(), Some("Article")));
+    }
+    if urls.is_empty() {
+        return Err(BackendError::not_found(format!(
+            "Webfinger resource {} not found",
+            query.resource
+        )));
+    }
+    Ok(Json(build_webfinger_response_with_type(
+        format!("acct:{name}@{domain}"),
+        urls,
+    )))
+}
+
+/// Fetch an object either by its ap_id, or by handle in the form `acct:name@example.com`.
+pub async fn resolve_object<Kind>(id: Url, context: &Data<IbisContext>) -> BackendResult<Kind>
+where
+    Kind: Object<DataType = IbisContext, Error = BackendError> + Send + 'static,
+    for<'de> <Kind as Object>::Kind: Deserialize<'de>,
+{
+    if id.scheme() == "acct" {
+        let handle = percent_decode_str(id.path()).decode_utf8()?;
+        webfinger_resolve(&handle, context).await
+    } else {
+        ObjectId::from(id).dereference(context).await
+    }
+}
+
+/// Fetch an object from a handle like `name@example.com` by first looking up its ap_id with
+/// webfinger. Unlike `webfinger_resolve_actor` from the federation library this also works
+/// for objects which are not actors, such as articles.
+pub async fn webfinger_resolve<Kind>(
+    handle: &str,
+    context: &Data<IbisContext>,
+) -> BackendResult<Kind>
+where
+    Kind: Object<DataType = IbisContext, Error = BackendError> + Send + 'static,
+    for<'de> <Kind as Object>::Kind: Deserialize<'de>,
+{
+    let (name, domain) = split_handle(handle)?;
+    let fetch_url = Url::parse_with_params(
+        &format!("{}://{domain}/.well-known/webfinger", http_protocol_str()),
+        &[("resource", format!("acct:{name}@{domain}"))],
+    )?;
+    // Reject domains which contain a path, query or credentials
+    let authority = fetch_url.host_str().map(|host| match fetch_url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    });
+    if fetch_url.path() != "/.well-known/webfinger"
+        || !fetch_url.username().is_empty()
+        || authority != Some(domain.to_lowercase())
+    {
+        return Err(BackendError::validation(format!("Invalid handle {handle}")));
+    }
+    VerifyUrlData(IbisContext::clone(context))
+        .verify(&fetch_url)
+        .await?;
+
+    let res: Webfinger = WEBFINGER_CLIENT
+        .get(fetch_url)
+        .header(ACCEPT, &WEBFINGER_CONTENT_TYPE)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    // The same name may refer to multiple objects of different types, so try each of them
+    let ap_ids = res
+        .links
+        .into_iter()
+        .filter(|l| l.rel.as_deref() == Some("self"))
+        .filter_map(|l| l.href);
+    for ap_id in ap_ids {
+        if let Ok(object) = ObjectId::<Kind>::from(ap_id).dereference(context).await {
+            return Ok(object);
+        }
+    }
+    Err(BackendError::not_found(format!(
+        "Failed to resolve {handle}"
+    )))
+}
+
+/// Split a handle like `@name@example.com` or `acct:name@example.com` into name and domain.
+fn split_handle(handle: &str) -> BackendResult<(&str, &str)> {
+    let handle = handle.trim_start_matches("acct:").trim_start_matches('@');
+    handle
+        .rsplit_once('@')
+        .filter(|(name, domain)| !name.is_empty() && !domain.is_empty())
+        .ok_or_else(|| BackendError::validation(format!("Invalid handle {handle}")))
+}
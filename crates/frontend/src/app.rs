@@ -8,21 +8,28 @@ use crate::{
             discussion::ArticleDiscussion,
             edit::EditArticle,
             history::ArticleHistory,
+            links::ArticleLinks,
             read::ReadArticle,
         },
         instance::{
             about::About,
+            categories::{Categories, CategoryDetails},
             details::InstanceDetails,
             explore::Explore,
+            media_library::MediaLibrary,
             search::Search,
             settings::InstanceSettings,
+            wanted::WantedArticles,
         },
         user::{
             edit_profile::UserEditProfile,
             login::Login,
             notifications::Notifications,
+            oauth_callback::OAuthCallback,
             profile::UserProfile,
             register::Register,
+            reset_password::{ForgotPassword, ResetPassword},
+            verify_email::VerifyEmail,
         },
     },
     utils::{dark_mode::DarkMode, formatting::instance_title, resources::site},
@@ -98,6 +105,7 @@ pub fn App() -> impl IntoView {
                         <Route path=path!("/article/:title") view=ReadArticle />
                         <Route path=path!("/article/:title/discussion") view=ArticleDiscussion />
                         <Route path=path!("/article/:title/history") view=ArticleHistory />
+                        <Route path=path!("/article/:title/links") view=ArticleLinks />
                         <IbisProtectedRoute path=path!("/article/:title/edit") view=EditArticle />
                         <IbisProtectedRoute
                             path=path!("/article/:title/actions")
@@ -106,11 +114,19 @@ pub fn App() -> impl IntoView {
                         <Route path=path!("/article/:title/diff/:hash") view=EditDiff />
                         <IbisProtectedRoute path=path!("/create-article") view=CreateArticle />
                         <Route path=path!("/explore") view=Explore />
+                        <Route path=path!("/media_library") view=MediaLibrary />
+                        <Route path=path!("/wanted") view=WantedArticles />
+                        <Route path=path!("/categories") view=Categories />
+                        <Route path=path!("/category/*name") view=CategoryDetails />
                         <Route path=path!("/instance/:hostname") view=InstanceDetails />
                         <Route path=path!("/about") view=About />
                         <Route path=path!("/user/:name") view=UserProfile />
                         <Route path=path!("/login") view=Login />
                         <Route path=path!("/register") view=Register />
+                        <Route path=path!("/oauth/callback") view=OAuthCallback />
+                        <Route path=path!("/verify_email") view=VerifyEmail />
+                        <Route path=path!("/forgot_password") view=ForgotPassword />
+                        <Route path=path!("/reset_password") view=ResetPassword />
                         <Route path=path!("/search") view=Search />
                         <IbisProtectedRoute path=path!("/edit_profile") view=UserEditProfile />
                         <IbisProtectedRoute path=path!("/notifications") view=Notifications />
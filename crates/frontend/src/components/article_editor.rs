@@ -1,5 +1,18 @@
-use crate::{markdown::render_article_markdown, utils::use_cookie};
-use leptos::{ev::beforeunload, html::Textarea, prelude::*};
+use crate::{
+    components::upload_button::UploadButton,
+    markdown::{
+        article_link::{LinkContext, find_missing_links},
+        render_article_markdown,
+        template::{expand_templates, resolve_templates},
+    },
+    utils::use_cookie,
+};
+use ibis_database::common::{
+    link::parse_article_links,
+    template::parse_template_calls,
+    upload::UploadView,
+};
+use leptos::{ev::beforeunload, html::Textarea, prelude::*, task::spawn_local};
 use leptos_use::{use_event_listener, use_window};
 
 #[component]
@@ -7,8 +20,66 @@ pub fn EditorView(
     textarea_ref: NodeRef<Textarea>,
     content: Signal<String>,
     set_content: WriteSignal<String>,
+    /// Instance of the article, used to load templates and resolve links. `None` for the local
+    /// instance.
+    #[prop(optional_no_strip)]
+    domain: Option<String>,
 ) -> impl IntoView {
-    let (preview, set_preview) = signal(render_article_markdown(&content.get_untracked()));
+    // Load templates again only if template calls were added or removed
+    let template_calls = Memo::new({
+        let domain = domain.clone();
+        move |_| {
+            parse_template_calls(&content.get())
+                .into_iter()
+                .map(|call| (call.title.clone(), call.domain_or(&domain)))
+                .collect::<Vec<_>>()
+        }
+    });
+    let (templates, set_templates) = signal(vec![]);
+    Effect::new({
+        let domain = domain.clone();
+        move |_| {
+            template_calls.track();
+            let text = content.get_untracked();
+            let domain = domain.clone();
+            spawn_local(async move {
+                set_templates.set(resolve_templates(text, domain).await);
+            });
+        }
+    });
+    let expanded = Memo::new({
+        let domain = domain.clone();
+        move |_| templates.with(|t| expand_templates(&content.get(), &domain, t))
+    });
+    // Check again for missing articles only if links were added or removed
+    let link_targets = Memo::new({
+        let domain = domain.clone();
+        move |_| {
+            parse_article_links(&expanded.get())
+                .iter()
+                .map(|link| link.target(&domain))
+                .collect::<Vec<_>>()
+        }
+    });
+    let (missing_links, set_missing_links) = signal(vec![]);
+    Effect::new({
+        let domain = domain.clone();
+        move |_| {
+            link_targets.track();
+            let text = expanded.get_untracked();
+            let domain = domain.clone();
+            spawn_local(async move {
+                set_missing_links.set(find_missing_links(text, domain).await);
+            });
+        }
+    });
+    let preview = Memo::new(move |_| {
+        let links = LinkContext {
+            domain: domain.clone(),
+            missing: missing_links.get(),
+        };
+        render_article_markdown(&expanded.get(), &links)
+    });
     let cookie = use_cookie("editor_preview");
     let show_preview = Signal::derive(move || cookie.0.get().unwrap_or(true));
 
@@ -20,6 +91,29 @@ pub fn EditorView(
         evt.prevent_default();
     });
 
+    // Insert markdown for the uploaded file at the cursor position
+    let insert_upload = move |upload: UploadView| {
+        let text = content.get_untracked();
+        let cursor = textarea_ref
+            .get_untracked()
+            .and_then(|t| t.selection_start().ok().flatten())
+            .map(|c| c as usize)
+            .unwrap_or(usize::MAX);
+        // cursor position is counted in utf-16 code units
+        let mut units = 0;
+        let index = text
+            .char_indices()
+            .find(|(_, c)| {
+                let found = units >= cursor;
+                units += c.len_utf16();
+                found
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(text.len());
+        let text = format!("{}{}{}", &text[..index], upload.markdown(), &text[index..]);
+        set_content.set(text);
+    };
+
     view! {
         <div>
             <div class="flex my-4 w-full max-sm:flex-col">
@@ -28,9 +122,7 @@ pub fn EditorView(
                     placeholder="Article text..."
                     class="text-base resize-none grow textarea textarea-primary min-h-80"
                     on:input=move |evt| {
-                        let val = event_target_value(&evt);
-                        set_preview.set(render_article_markdown(&val));
-                        set_content.set(val);
+                        set_content.set(event_target_value(&evt));
                     }
                     node_ref=textarea_ref
                 ></textarea>
@@ -51,6 +143,9 @@ pub fn EditorView(
                 >
                     Preview
                 </button>
+                <div class="ml-2">
+                    <UploadButton on_upload=Callback::new(insert_upload) />
+                </div>
                 <p class="mx-4">
                     <a
                         class="link link-secondary"
@@ -18,6 +18,7 @@ use phosphor_leptos::{
     FEDIVERSE_LOGO,
     GEAR_SIX,
     Icon,
+    LINK,
     LIST,
     LOCK_SIMPLE,
     PENCIL,
@@ -28,6 +29,7 @@ pub enum ActiveTab {
     Read,
     Discussion,
     History,
+    Links,
     Edit,
     Actions,
 }
@@ -84,6 +86,14 @@ pub fn ArticleNav(
                                     <Icon icon=LIST />
                                     "History"
                                 </A>
+                                <A
+                                    href=format!("{article_link}/links")
+                                    {..}
+                                    class=tab_classes.links
+                                >
+                                    <Icon icon=LINK />
+                                    "Links"
+                                </A>
                                 <Show when=move || {
                                     is_logged_in()
                                         && can_edit_article(&article_.article, is_admin()).is_ok()
@@ -154,6 +164,7 @@ struct ActiveTab2Classes {
     read: &'static str,
     discussion: &'static str,
     history: &'static str,
+    links: &'static str,
     edit: &'static str,
     actions: &'static str,
 }
@@ -165,6 +176,7 @@ fn tab_classes(active_tab: ActiveTab) -> ActiveTab2Classes {
         read: TAB_INACTIVE,
         discussion: TAB_INACTIVE,
         history: TAB_INACTIVE,
+        links: TAB_INACTIVE,
         edit: TAB_INACTIVE,
         actions: TAB_INACTIVE,
     };
@@ -172,6 +184,7 @@ fn tab_classes(active_tab: ActiveTab) -> ActiveTab2Classes {
         ActiveTab::Read => classes.read = TAB_ACTIVE,
         ActiveTab::Discussion => classes.discussion = TAB_ACTIVE,
         ActiveTab::History => classes.history = TAB_ACTIVE,
+        ActiveTab::Links => classes.links = TAB_ACTIVE,
         ActiveTab::Edit => classes.edit = TAB_ACTIVE,
         ActiveTab::Actions => classes.actions = TAB_ACTIVE,
     }
@@ -183,6 +196,7 @@ fn page_title(active_tab: ActiveTab, article_title: &str) -> String {
         ActiveTab::Read => return article_title.to_string(),
         ActiveTab::Discussion => "Discuss",
         ActiveTab::History => "History",
+        ActiveTab::Links => "What links here",
         ActiveTab::Edit => "Edit",
         ActiveTab::Actions => "Actions",
     };
@@ -7,6 +7,9 @@ pub fn CredentialsForm(
     action: Action<(String, String), ()>,
     error: Signal<Option<String>>,
     disabled: Signal<bool>,
+    /// Additional form fields which are shown above the submit button
+    #[prop(optional)]
+    children: Option<Children>,
 ) -> impl IntoView {
     let (password, set_password) = signal(String::new());
     let (username, set_username) = signal(String::new());
@@ -45,6 +48,7 @@ pub fn CredentialsForm(
                 prop:disabled=move || disabled.get()
                 bind:value=(password, set_password)
             />
+            {children.map(|c| c())}
 
             <div>
                 <button
@@ -15,6 +15,7 @@ pub fn EditList(edits: Vec<EditView>, for_article: bool) -> impl IntoView {
                     .map(|edit: EditView| {
                         let path = edit_path(&edit.edit, &edit.article);
                         let date = render_date_time(edit.edit.published);
+                        let bot = edit.creator.bot;
                         let second_line = if for_article {
                             Either::Left(
                                 view! {
@@ -44,6 +45,9 @@ pub fn EditList(edits: Vec<EditView>, for_article: bool) -> impl IntoView {
                                                 Pending
                                             </span>
                                         </Show>
+                                        <Show when=move || bot>
+                                            <span class="ml-1 badge badge-neutral">"Bot"</span>
+                                        </Show>
                                     </div>
                                     <p>{second_line}</p>
                                 </div>
@@ -6,5 +6,7 @@ pub mod credentials;
 pub mod edit_list;
 pub mod instance_follow_button;
 pub mod nav;
+pub mod pagination;
 pub mod protected_route;
 pub mod suspense_error;
+pub mod upload_button;
@@ -11,10 +11,13 @@ use phosphor_leptos::{
     CARDS,
     EXCLAMATION_MARK,
     GEAR,
+    HASH,
     HOUSE,
+    IMAGES,
     Icon,
     MAGNIFYING_GLASS,
     PLUS,
+    QUESTION,
 };
 
 #[component]
@@ -68,6 +71,24 @@ pub fn Nav() -> impl IntoView {
                                     "Explore"
                                 </a>
                             </li>
+                            <li>
+                                <a href="/media_library">
+                                    <Icon icon=IMAGES />
+                                    "Media Library"
+                                </a>
+                            </li>
+                            <li>
+                                <a href="/categories">
+                                    <Icon icon=HASH />
+                                    "Categories"
+                                </a>
+                            </li>
+                            <li>
+                                <a href="/wanted">
+                                    <Icon icon=QUESTION />
+                                    "Wanted Articles"
+                                </a>
+                            </li>
                             <li>
                                 <a href="/about">
                                     <Icon icon=EXCLAMATION_MARK />
@@ -139,7 +160,9 @@ pub fn Nav() -> impl IntoView {
                                     <li>
                                         <a href="/login">"Login"</a>
                                     </li>
-                                    <Show when=move || config().registration_open>
+                                    <Show when=move || {
+                                        config().registration_open && config().password_login
+                                    }>
                                         <li>
                                             <a href="/register">"Register"</a>
                                         </li>
@@ -0,0 +1,49 @@
+use ibis_database::common::DEFAULT_PAGE_LIMIT;
+use leptos::prelude::*;
+use leptos_router::hooks::{use_location, use_query_map};
+
+/// Current page number from the `page` query param, starting at 1.
+pub fn use_page() -> Memo<i64> {
+    let params = use_query_map();
+    Memo::new(move |_| {
+        params
+            .get()
+            .get("page")
+            .and_then(|p| p.parse().ok())
+            .filter(|p| *p >= 1)
+            .unwrap_or(1)
+    })
+}
+
+/// Whether there may be another page after a page with the given number of items, assuming
+/// the default page limit was used.
+pub fn has_next_page(items: usize) -> bool {
+    items as i64 >= DEFAULT_PAGE_LIMIT
+}
+
+/// Links to the previous and next page. Other query params of the current url are preserved.
+#[component]
+pub fn Pagination(has_next: bool) -> impl IntoView {
+    let location = use_location();
+    let params = use_query_map();
+    let page = use_page();
+    let page_href = move |page: i64| {
+        let mut params = params.get();
+        params.replace("page", page.to_string());
+        format!("{}{}", location.pathname.get(), params.to_query_string())
+    };
+    view! {
+        <div class="flex gap-2 my-4">
+            <Show when=move || { page.get() > 1 }>
+                <a class="btn btn-sm" href=move || page_href(page.get() - 1)>
+                    "Previous"
+                </a>
+            </Show>
+            <Show when=move || has_next>
+                <a class="btn btn-sm" href=move || page_href(page.get() + 1)>
+                    "Next"
+                </a>
+            </Show>
+        </div>
+    }
+}
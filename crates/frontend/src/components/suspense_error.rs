@@ -1,5 +1,6 @@
 use crate::{pages::article_title_param, utils::resources::is_logged_in};
 use ibis_api_client::errors::FrontendResult;
+use ibis_database::error::ErrorType;
 use leptos::{either::Either, prelude::*};
 
 #[component]
@@ -13,7 +14,10 @@ where
         }>
             {move || {
                 if let Some(Err(e)) = result.get() {
-                    let article_title = article_title_param();
+                    let error_type = e.error_type();
+                    // offer to create the article if it doesnt exist yet
+                    let article_title = article_title_param()
+                        .filter(|_| error_type == ErrorType::NotFound);
                     let href = format!(
                         "/create-article?title={}",
                         article_title.clone().unwrap_or_default(),
@@ -28,6 +32,11 @@ where
                                             Create Article
                                         </a>
                                     </Show>
+                                    <Show when=move || error_type == ErrorType::NotLoggedIn>
+                                        <a class="mt-4 btn" href="/login">
+                                            Login
+                                        </a>
+                                    </Show>
                                 </div>
                             </div>
                         },
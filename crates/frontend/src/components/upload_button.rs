@@ -0,0 +1,56 @@
+use ibis_api_client::{
+    CLIENT,
+    errors::{ErrorPopup, FrontendResultExt},
+    upload::UploadFileParams,
+};
+use ibis_database::common::upload::UploadView;
+use js_sys::Uint8Array;
+use leptos::{
+    prelude::*,
+    web_sys::{File, HtmlInputElement},
+};
+use wasm_bindgen_futures::JsFuture;
+
+/// Button which opens a file picker and uploads the selected file.
+#[component]
+pub fn UploadButton(on_upload: Callback<UploadView>) -> impl IntoView {
+    let upload_action = Action::new_local(move |file: &File| {
+        let file = file.clone();
+        async move {
+            let data = match JsFuture::from(file.array_buffer()).await {
+                Ok(buffer) => Uint8Array::new(&buffer).to_vec(),
+                Err(e) => {
+                    ErrorPopup::set(format!("Failed to read file: {e:?}"));
+                    return;
+                }
+            };
+            let params = UploadFileParams {
+                filename: file.name(),
+                data,
+            };
+            CLIENT
+                .upload_file(params)
+                .await
+                .error_popup(|upload| on_upload.run(upload));
+        }
+    });
+    let pending = upload_action.pending();
+
+    view! {
+        <label class="btn btn-secondary btn-sm" class:btn-disabled=pending>
+            {move || if pending.get() { "Uploading..." } else { "Upload File" }}
+            <input
+                type="file"
+                class="hidden"
+                on:change=move |ev| {
+                    let input: HtmlInputElement = event_target(&ev);
+                    if let Some(file) = input.files().and_then(|f| f.get(0)) {
+                        upload_action.dispatch_local(file);
+                    }
+                    // allows to select the same file again
+                    input.set_value("");
+                }
+            />
+        </label>
+    }
+}
@@ -1,3 +1,5 @@
+use ibis_api_client::{CLIENT, article::FindMissingLinksParams};
+use ibis_database::common::link::{ArticleLink, LinkTarget, parse_article_links};
 use markdown_it::{
     Node,
     NodeValue,
@@ -5,23 +7,63 @@ use markdown_it::{
     parser::inline::{InlineRule, InlineState},
 };
 
+/// Information which is needed to render article links, but isn't part of the markdown text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinkContext {
+    /// Instance of the article, links without domain point here. `None` for the local instance.
+    pub domain: Option<String>,
+    /// Link targets which don't exist, these are shown in red.
+    pub missing: Vec<LinkTarget>,
+}
+
+/// Check which links in the text point to articles that don't exist. Doesn't send any request
+/// if the text contains no links.
+pub async fn find_missing_links(text: String, domain: Option<String>) -> Vec<LinkTarget> {
+    if parse_article_links(&text).is_empty() {
+        return vec![];
+    }
+    let params = FindMissingLinksParams { text, domain };
+    CLIENT.find_missing_links(params).await.unwrap_or_else(|e| {
+        log::warn!("Failed to check article links: {e}");
+        vec![]
+    })
+}
+
 #[derive(Debug)]
-struct ArticleLink {
-    label: String,
-    title: String,
-    domain: String,
+pub struct ArticleLinkNode {
+    link: ArticleLink,
+    target: LinkTarget,
+    missing: bool,
+}
+
+impl ArticleLinkNode {
+    /// Needs to be called after parsing, as the inline parser has no access to the context.
+    pub fn resolve(&mut self, context: &LinkContext) {
+        self.target = self.link.target(&context.domain);
+        self.missing = context.missing.contains(&self.target);
+    }
 }
 
 // This defines how your custom node should be rendered.
-impl NodeValue for ArticleLink {
+impl NodeValue for ArticleLinkNode {
     fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
         let mut attrs = node.attrs.clone();
 
-        let link = format!("/article/{}@{}", self.title, self.domain);
+        let title = &self.target.title;
+        let link = match (&self.target.domain, self.missing) {
+            (Some(domain), _) => format!("/article/{title}@{domain}"),
+            // Missing local articles can be created directly
+            (None, true) => format!("/create-article?title={title}"),
+            (None, false) => format!("/article/{title}"),
+        };
         attrs.push(("href", link));
+        if self.missing {
+            attrs.push(("class", "text-error".to_string()));
+            attrs.push(("title", "Article does not exist".to_string()));
+        }
 
         fmt.open("a", &attrs);
-        fmt.text(&self.label);
+        fmt.text(&self.link.label);
         fmt.close("a");
     }
 }
@@ -31,7 +73,7 @@ pub struct ArticleLinkScanner;
 impl InlineRule for ArticleLinkScanner {
     const MARKER: char = '[';
 
-    /// Find `[[Title@example.com]], return the position and split title/domain.
+    /// Find `[[Title]]` or `[[Title@example.com]]`, return the position and split title/domain.
     fn run(state: &mut InlineState) -> Option<(Node, usize)> {
         let input = &state.src[state.pos..state.pos_max];
         if !input.starts_with("[[") {
@@ -43,15 +85,14 @@ impl InlineRule for ArticleLinkScanner {
             let start = state.pos + SEPARATOR_LENGTH;
             let i = start + length - SEPARATOR_LENGTH;
             let content = &state.src[start..i];
-            content.split_once('@').map(|(title, domain)| {
-                // Handle custom link label if provided, otherwise use title as label
-                let (domain, label) = domain.split_once('|').unwrap_or((domain, title));
-                let node = Node::new(ArticleLink {
-                    label: label.to_string(),
-                    title: title.to_string(),
-                    domain: domain.to_string(),
-                });
-                (node, length + SEPARATOR_LENGTH)
+            // Same parsing as for stored links, so that backlinks match the rendered links
+            ArticleLink::parse(content).map(|link| {
+                let node = ArticleLinkNode {
+                    target: link.target(&None),
+                    link,
+                    missing: false,
+                };
+                (Node::new(node), length + SEPARATOR_LENGTH)
             })
         })
     }
@@ -59,20 +100,58 @@ impl InlineRule for ArticleLinkScanner {
 
 #[cfg(test)]
 mod test {
+    use super::LinkContext;
     use crate::markdown::render_article_markdown;
+    use ibis_database::common::link::LinkTarget;
 
     #[test]
     fn test_markdown_article_link() {
-        let plain = render_article_markdown("[[Title@example.com]]");
+        let context = LinkContext::default();
+        let plain = render_article_markdown("[[Title@example.com]]", &context);
         assert_eq!(
             "<p><a href=\"/article/Title@example.com\">Title</a></p>\n",
             plain
         );
 
-        let with_label = render_article_markdown("[[Title@example.com|Example Article]]");
+        let with_label = render_article_markdown("[[Title@example.com|Example Article]]", &context);
         assert_eq!(
             "<p><a href=\"/article/Title@example.com\">Example Article</a></p>\n",
             with_label
         );
+
+        let local = render_article_markdown("[[Local title]]", &context);
+        assert_eq!(
+            "<p><a href=\"/article/Local_title\">Local title</a></p>\n",
+            local
+        );
+    }
+
+    #[test]
+    fn test_markdown_article_link_context() {
+        let context = LinkContext {
+            domain: Some("example.com".to_string()),
+            missing: vec![LinkTarget {
+                title: "Missing".to_string(),
+                domain: Some("example.com".to_string()),
+            }],
+        };
+        let remote = render_article_markdown("[[Title]] [[Missing]]", &context);
+        assert_eq!(
+            "<p><a href=\"/article/Title@example.com\">Title</a> <a href=\"/article/Missing@example.com\" class=\"text-error\" title=\"Article does not exist\">Missing</a></p>\n",
+            remote
+        );
+
+        let context = LinkContext {
+            domain: None,
+            missing: vec![LinkTarget {
+                title: "Missing".to_string(),
+                domain: None,
+            }],
+        };
+        let local = render_article_markdown("[[Missing]]", &context);
+        assert_eq!(
+            "<p><a href=\"/create-article?title=Missing\" class=\"text-error\" title=\"Article does not exist\">Missing</a></p>\n",
+            local
+        );
     }
 }
@@ -0,0 +1,69 @@
+use ibis_database::common::category::{CATEGORY_PREFIX, normalize_category};
+use markdown_it::{
+    Node,
+    NodeValue,
+    Renderer,
+    parser::inline::{InlineRule, InlineState, Text},
+    plugins::cmark::{block::paragraph::Paragraph, inline::newline::Softbreak},
+};
+
+/// Categories are shown below the article instead of inside the text, so this renders nothing.
+#[derive(Debug)]
+pub struct CategoryNode;
+
+impl NodeValue for CategoryNode {
+    fn render(&self, _node: &Node, _fmt: &mut dyn Renderer) {}
+}
+
+impl CategoryNode {
+    /// Paragraphs which only contain categories are hidden completely, so that they don't
+    /// leave empty space.
+    pub fn hide_paragraph(node: &mut Node) {
+        if !node.is::<Paragraph>() || node.children.is_empty() {
+            return;
+        }
+        let only_categories = node.children.iter().all(|child| {
+            child.is::<CategoryNode>()
+                || child.is::<Softbreak>()
+                || child
+                    .cast::<Text>()
+                    .is_some_and(|t| t.content.trim().is_empty())
+        });
+        if only_categories {
+            node.replace(CategoryNode);
+        }
+    }
+}
+
+pub struct CategoryScanner;
+
+impl InlineRule for CategoryScanner {
+    const MARKER: char = '[';
+
+    /// Find `[[Category:Name]]`, invalid names are left as text.
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let content = input.strip_prefix(CATEGORY_PREFIX)?;
+        let length = content.find("]]")?;
+        normalize_category(&content[..length])?;
+        Some((Node::new(CategoryNode), CATEGORY_PREFIX.len() + length + 2))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::markdown::{article_link::LinkContext, render_article_markdown};
+
+    #[test]
+    fn test_markdown_category() {
+        let context = LinkContext::default();
+        let text = "Some text [[Category:Science/Physics]]\n\n[[Category:A]] [[Category:B]]\n";
+        assert_eq!(
+            "<p>Some text </p>\n",
+            render_article_markdown(text, &context)
+        );
+
+        let invalid = render_article_markdown("[[Category:<b>]]", &context);
+        assert_eq!("<p>[[Category:&lt;b&gt;]]</p>\n", invalid);
+    }
+}
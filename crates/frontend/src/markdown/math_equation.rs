@@ -58,13 +58,15 @@ impl InlineRule for MathEquationScanner {
 
 #[cfg(test)]
 mod test {
-    use crate::markdown::render_article_markdown;
+    use crate::markdown::{article_link::LinkContext, render_article_markdown};
 
     #[test]
     #[expect(clippy::unwrap_used)]
     fn test_markdown_equation_katex() {
-        let rendered =
-            render_article_markdown("here is a math equation: $$E=mc^2$$. Pretty cool, right?");
+        let rendered = render_article_markdown(
+            "here is a math equation: $$E=mc^2$$. Pretty cool, right?",
+            &LinkContext::default(),
+        );
         assert_eq!(
             "<p>here is a math equation: ".to_owned()
                 + &katex::render("E=mc^2").unwrap()
@@ -1,4 +1,5 @@
-use article_link::ArticleLinkScanner;
+use article_link::{ArticleLinkNode, ArticleLinkScanner, LinkContext};
+use category::{CategoryNode, CategoryScanner};
 use markdown_it::{
     MarkdownIt,
     plugins::cmark::block::{heading::ATXHeading, lheading::SetextHeader},
@@ -8,10 +9,13 @@ use std::sync::OnceLock;
 use table_of_contents::{TocMarkerScanner, TocScanner};
 
 pub mod article_link;
+pub mod category;
 pub mod math_equation;
 pub mod table_of_contents;
+pub mod template;
 
-pub fn render_article_markdown(text: &str) -> String {
+/// Render markdown of an article. Links to other articles are resolved with the given context.
+pub fn render_article_markdown(text: &str, links: &LinkContext) -> String {
     static INSTANCE: OnceLock<MarkdownIt> = OnceLock::new();
     let mut parsed = INSTANCE.get_or_init(article_markdown).parse(text);
 
@@ -24,6 +28,11 @@ pub fn render_article_markdown(text: &str) -> String {
         if let Some(heading) = node.cast_mut::<SetextHeader>() {
             heading.level += 1;
         }
+        // Links without domain and missing articles can only be handled after parsing
+        if let Some(link) = node.cast_mut::<ArticleLinkNode>() {
+            link.resolve(links);
+        }
+        CategoryNode::hide_paragraph(node);
     });
     parsed.render()
 }
@@ -47,6 +56,7 @@ fn article_markdown() -> MarkdownIt {
 
     // Ibis custom extensions
     parser.inline.add_rule::<ArticleLinkScanner>();
+    parser.inline.add_rule::<CategoryScanner>();
     parser.inline.add_rule::<MathEquationScanner>();
     parser.inline.add_rule::<TocMarkerScanner>();
     parser.add_rule::<TocScanner>();
@@ -58,8 +68,8 @@ fn common_markdown() -> MarkdownIt {
     let mut parser = MarkdownIt::new();
     let p = &mut parser;
     {
-        // Markdown-it inline core features. Image is disabled to prevent embedding external
-        // images. Later we need to add proper image support using pictrs.
+        // Markdown-it inline core features. Image is only enabled for articles, where files
+        // can be uploaded to the instance.
         use markdown_it::plugins::cmark::inline::*;
         newline::add(p);
         escape::add(p);
@@ -0,0 +1,215 @@
+use ibis_api_client::{CLIENT, article::ResolveTemplatesParams};
+use ibis_database::common::template::{
+    MAX_TEMPLATE_DEPTH,
+    MAX_TEMPLATES,
+    Template,
+    parse_template_calls,
+};
+use std::collections::HashMap;
+
+/// Limits the size of the output if templates are included many times.
+const MAX_EXPANSIONS: usize = MAX_TEMPLATES * 10;
+
+/// Maximum number of bytes which templates can add to an article. Parameters can be copied
+/// into multiple placeholders, so nested calls could otherwise double the size on every level.
+const MAX_OUTPUT_SIZE: usize = 1_000_000;
+
+/// What is left of the limits while expanding the templates of an article.
+struct Budget {
+    expansions: usize,
+    bytes: usize,
+}
+
+/// Load the templates which are needed to render the text. Doesn't send any request if the text
+/// contains no template calls.
+pub async fn resolve_templates(text: String, domain: Option<String>) -> Vec<Template> {
+    if parse_template_calls(&text).is_empty() {
+        return vec![];
+    }
+    let params = ResolveTemplatesParams { text, domain };
+    CLIENT.resolve_templates(params).await.unwrap_or_else(|e| {
+        log::warn!("Failed to resolve templates: {e}");
+        vec![]
+    })
+}
+
+/// Replace template calls like `{{Infobox|name=Ibis}}` with the text of the template, where
+/// placeholders like `{{{name}}}` or `{{{name|default}}}` are replaced by the parameters. This
+/// needs to run before the markdown is rendered.
+///
+/// `domain` is the instance of the article, with `None` for the local instance. Templates
+/// which include themselves are only expanded once.
+pub fn expand_templates(text: &str, domain: &Option<String>, templates: &[Template]) -> String {
+    let mut budget = Budget {
+        expansions: MAX_EXPANSIONS,
+        bytes: MAX_OUTPUT_SIZE,
+    };
+    expand(text, domain, templates, &mut vec![], &mut budget)
+}
+
+fn expand(
+    text: &str,
+    domain: &Option<String>,
+    templates: &[Template],
+    stack: &mut Vec<(String, Option<String>)>,
+    budget: &mut Budget,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for call in parse_template_calls(text) {
+        out.push_str(&text[last..call.range.start]);
+        last = call.range.end;
+
+        let key = (call.title.clone(), call.domain_or(domain));
+        let template = templates
+            .iter()
+            .find(|t| t.title == key.0 && t.domain == key.1);
+        let Some(template) = template else {
+            out.push_str(&format!("**Template not found: {}**", call.title));
+            continue;
+        };
+        if stack.contains(&key) {
+            out.push_str(&format!("**Template loop: {}**", call.title));
+            continue;
+        }
+        if stack.len() >= MAX_TEMPLATE_DEPTH || budget.expansions == 0 {
+            out.push_str(&format!("**Template limit reached: {}**", call.title));
+            continue;
+        }
+        budget.expansions -= 1;
+
+        // parameters belong to the calling article, so they are expanded in its context
+        let params: HashMap<_, _> = call
+            .params
+            .iter()
+            .map(|(name, value)| {
+                let value = expand(value, domain, templates, stack, budget);
+                (name.clone(), value)
+            })
+            .collect();
+        let text = template.text.trim_end_matches('\n');
+        let Some(text) = substitute_params(text, &params, budget.bytes) else {
+            budget.bytes = 0;
+            out.push_str(&format!("**Template output too large: {}**", call.title));
+            continue;
+        };
+        budget.bytes -= text.len();
+        stack.push(key);
+        out.push_str(&expand(&text, &template.domain, templates, stack, budget));
+        stack.pop();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Placeholders without matching parameter or default value are left unchanged. Returns `None`
+/// if the result would be longer than `max_len`.
+fn substitute_params(
+    text: &str,
+    params: &HashMap<String, String>,
+    max_len: usize,
+) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{{") {
+        let Some(length) = rest[start + 3..].find("}}}") else {
+            break;
+        };
+        let end = start + 3 + length + 3;
+        let content = &rest[start + 3..start + 3 + length];
+        let (name, default) = match content.split_once('|') {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (content.trim(), None),
+        };
+        out.push_str(&rest[..start]);
+        match params.get(name).map(String::as_str).or(default) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..end]),
+        }
+        if out.len() > max_len {
+            return None;
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    (out.len() <= max_len).then_some(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn template(title: &str, domain: Option<&str>, text: &str) -> Template {
+        Template {
+            title: title.to_string(),
+            domain: domain.map(str::to_string),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_expand_templates() {
+        let templates = vec![
+            template("Greeting", None, "Hello {{{name|World}}}, {{{1}}}!\n"),
+            template("Notice", Some("example.com"), "Note: {{Inner}}"),
+            template(
+                "Inner",
+                Some("example.com"),
+                "from example.com {{{missing}}}",
+            ),
+        ];
+        let expanded = expand_templates(
+            "{{Greeting|name=Ibis|welcome}} {{Greeting}}",
+            &None,
+            &templates,
+        );
+        assert_eq!("Hello Ibis, welcome! Hello World, {{{1}}}!", expanded);
+
+        // nested templates without domain are loaded from the instance of the template
+        let expanded = expand_templates("{{Notice@example.com}}", &None, &templates);
+        assert_eq!("Note: from example.com {{{missing}}}", expanded);
+
+        let expanded = expand_templates("{{Unknown}}", &None, &templates);
+        assert_eq!("**Template not found: Unknown**", expanded);
+    }
+
+    #[test]
+    fn test_expand_templates_recursion() {
+        let templates = vec![
+            template("First", None, "first {{Second}}"),
+            template("Second", None, "second {{First}}"),
+            template("Param", None, "[{{{1}}}]"),
+        ];
+        let expanded = expand_templates("{{First}}", &None, &templates);
+        assert_eq!("first second **Template loop: First**", expanded);
+
+        let expanded = expand_templates("{{Param|{{Param|x}}}}", &None, &templates);
+        assert_eq!("[[x]]", expanded);
+
+        // deeply nested templates without a loop stop at the depth limit
+        let templates: Vec<_> = (0..=MAX_TEMPLATE_DEPTH)
+            .map(|i| template(&format!("T{i}"), None, &format!("{i} {{{{T{}}}}}", i + 1)))
+            .collect();
+        let expanded = expand_templates("{{T0}}", &None, &templates);
+        assert_eq!(
+            "0 1 2 3 4 5 6 7 8 9 **Template limit reached: T10**",
+            expanded
+        );
+    }
+
+    #[test]
+    fn test_expand_templates_size() {
+        let templates = vec![template("Twice", None, "{{{1}}}{{{1}}}")];
+        let expanded = expand_templates("{{Twice|{{Twice|x}}}}", &None, &templates);
+        assert_eq!("xxxx", expanded);
+
+        // each level doubles the size of the parameter
+        let mut text = "x".repeat(1000);
+        for _ in 0..30 {
+            text = format!("{{{{Twice|{text}}}}}");
+        }
+        let expanded = expand_templates(&text, &None, &templates);
+        assert!(expanded.len() < MAX_OUTPUT_SIZE);
+        assert!(expanded.contains("**Template output too large: Twice**"));
+    }
+}
@@ -4,11 +4,14 @@ use crate::{
         suspense_error::SuspenseError,
     },
     pages::article_resource,
-    utils::{formatting::article_path, resources::is_admin},
+    utils::{
+        formatting::article_path,
+        resources::{is_admin, is_logged_in},
+    },
 };
 use ibis_api_client::{
     CLIENT,
-    article::{ForkArticleParams, ProtectArticleParams},
+    article::{ForkArticleParams, MoveArticleParams, ProtectArticleParams},
 };
 use ibis_database::common::{article::Article, newtypes::ArticleId};
 use leptos::{ev::KeyboardEvent, prelude::*};
@@ -18,7 +21,7 @@ use leptos_router::components::Redirect;
 pub fn ArticleActions() -> impl IntoView {
     let article = article_resource();
     let (new_title, set_new_title) = signal(String::new());
-    let (fork_response, set_fork_response) = signal(Option::<Article>::None);
+    let (redirect_article, set_redirect_article) = signal(Option::<Article>::None);
     let (error, set_error) = signal(None::<String>);
     let fork_action = Action::new(move |(article_id, new_title): &(ArticleId, String)| {
         let params = ForkArticleParams {
@@ -29,7 +32,23 @@ pub fn ArticleActions() -> impl IntoView {
             set_error.update(|e| *e = None);
             let result = CLIENT.fork_article(&params).await;
             match result {
-                Ok(res) => set_fork_response.set(Some(res.article)),
+                Ok(res) => set_redirect_article.set(Some(res.article)),
+                Err(err) => {
+                    set_error.update(|e| *e = Some(err.to_string()));
+                }
+            }
+        }
+    });
+    let move_action = Action::new(move |(article_id, new_title): &(ArticleId, String)| {
+        let params = MoveArticleParams {
+            article_id: *article_id,
+            new_title: new_title.to_string(),
+        };
+        async move {
+            set_error.update(|e| *e = None);
+            let result = CLIENT.move_article(&params).await;
+            match result {
+                Ok(res) => set_redirect_article.set(Some(res.article)),
                 Err(err) => {
                     set_error.update(|e| *e = Some(err.to_string()));
                 }
@@ -52,6 +71,20 @@ pub fn ArticleActions() -> impl IntoView {
             }
         }
     });
+    let (deleted, set_deleted) = signal(false);
+    let delete_action = Action::new(move |id: &ArticleId| {
+        let id = *id;
+        async move {
+            set_error.update(|e| *e = None);
+            let result = CLIENT.delete_article(id).await;
+            match result {
+                Ok(_res) => set_deleted.set(true),
+                Err(err) => {
+                    set_error.update(|e| *e = Some(err.to_string()));
+                }
+            }
+        }
+    });
     view! {
         <ArticleNav article=article active_tab=ActiveTab::Actions />
         <SuspenseError result=article>
@@ -78,6 +111,43 @@ pub fn ArticleActions() -> impl IntoView {
                                         Toggle Article Protection
                                     </button>
                                     <p>"Protect a local article so that only admins can edit it"</p>
+                                </Show> <Show when=move || { is_logged_in() && article.article.local }>
+                                    <input
+                                        class="input"
+                                        placeholder="New Title"
+                                        on:keyup=move |ev: KeyboardEvent| {
+                                            let val = event_target_value(&ev);
+                                            set_new_title.update(|v| *v = val);
+                                        }
+                                    />
+
+                                    <button
+                                        class="btn"
+                                        disabled=move || new_title.get().is_empty()
+                                        on:click=move |_| {
+                                            move_action.dispatch((article.article.id, new_title.get()));
+                                        }
+                                    >
+
+                                        Move Article
+                                    </button>
+                                    <p>
+                                        "Change the title of this article. Links to the previous title will continue to work."
+                                    </p>
+                                </Show> <Show when=is_admin>
+                                    <button
+                                        class="btn btn-error"
+                                        on:click=move |_| {
+                                            delete_action.dispatch(article.article.id);
+                                        }
+                                    >
+                                        Delete Article
+                                    </button>
+                                    <p>
+                                        "Remove this article so that it is no longer shown. Local articles are hidden
+                                        and other instances are told to delete their copies. Remote articles are only
+                                        deleted from this instance."
+                                    </p>
                                 </Show> <Show when=move || !article.article.local>
                                     <input
                                         class="input"
@@ -107,8 +177,10 @@ pub fn ArticleActions() -> impl IntoView {
                         }
                     })
             })}
-            {fork_response.get().map(|article| view! { <Redirect path=article_path(&article) /> })}
+            {redirect_article.get().map(|article| view! { <Redirect path=article_path(&article) /> })}
+            <Show when=move || deleted.get()>
+                <Redirect path="/" />
+            </Show>
         </SuspenseError>
-        <p>"TODO: add option for admin to delete article etc"</p>
     }
 }
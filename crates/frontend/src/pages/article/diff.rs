@@ -1,34 +1,58 @@
 use crate::{
     components::{
         article_nav::{ActiveTab, ArticleNav},
+        pagination::use_page,
         suspense_error::SuspenseError,
     },
     pages::{article_edits_resource, article_resource},
-    utils::formatting::{article_title, render_date_time, user_link},
+    utils::{
+        formatting::{article_path, article_title, render_date_time, user_link},
+        resources::is_logged_in,
+    },
+};
+use ibis_api_client::{CLIENT, article::RevertArticleParams, errors::FrontendResultExt};
+use ibis_database::common::{
+    article::{Article, EditVersion},
+    newtypes::ArticleId,
 };
 use leptos::{either::Either, prelude::*};
 use leptos_meta::Title;
-use leptos_router::hooks::use_params_map;
+use leptos_router::{components::Redirect, hooks::use_params_map};
 
 #[component]
 pub fn EditDiff() -> impl IntoView {
     let params = use_params_map();
     let article = article_resource();
+    let page = use_page();
+    let (revert_response, set_revert_response) = signal(None::<Article>);
+    let revert_action = Action::new(move |(article_id, version): &(ArticleId, EditVersion)| {
+        let params = RevertArticleParams {
+            article_id: *article_id,
+            version: version.clone(),
+        };
+        async move {
+            CLIENT
+                .revert_article(&params)
+                .await
+                .error_popup(|res| set_revert_response.set(Some(res.article)));
+        }
+    });
 
     view! {
         <ArticleNav article=article active_tab=ActiveTab::History />
         <SuspenseError result=article>
             {move || Suspend::new(async move {
-                let edits = article_edits_resource(article).await;
-                let article_title = article
+                let version = params.get_untracked().get("hash").and_then(|h| h.parse().ok());
+                let is_valid = version.is_some();
+                let edits = article_edits_resource(article, page, version).await;
+                let (article_title, latest_version) = article
                     .await
-                    .map(|a| article_title(&a.article))
+                    .map(|a| (article_title(&a.article), Some(a.latest_version)))
                     .unwrap_or_default();
                 edits
                     .await
                     .map(|edits| {
-                        let hash = params.get_untracked().get("hash").clone();
-                        let edit = edits.iter().find(|e| Some(e.edit.hash.0.to_string()) == hash);
+                        let edit = edits.first().filter(|_| is_valid);
                         if let Some(edit) = edit {
                             let label = format!(
                                 "{} ({})",
@@ -36,6 +60,10 @@ pub fn EditDiff() -> impl IntoView {
                                 render_date_time(edit.edit.published),
                             );
                             let pending = edit.edit.pending;
+                            let can_revert = !pending
+                                && Some(&edit.edit.hash) != latest_version.as_ref();
+                            let article_id = edit.article.id;
+                            let version = edit.edit.hash.clone();
                             let title = format!("Diff {} — {}", edit.edit.summary, article_title);
                             Either::Left(
                                 view! {
@@ -51,6 +79,19 @@ pub fn EditDiff() -> impl IntoView {
                                         </Show>
                                     </div>
                                     <p>"by " {user_link(&edit.creator)}</p>
+                                    <Show when=move || can_revert && is_logged_in()>
+                                        <button
+                                            class="my-2 btn btn-secondary btn-sm"
+                                            on:click={
+                                                let version = version.clone();
+                                                move |_| {
+                                                    revert_action.dispatch((article_id, version.clone()));
+                                                }
+                                            }
+                                        >
+                                            "Revert to this version"
+                                        </button>
+                                    </Show>
                                     <div class="max-w-full prose prose-slate">
                                         <pre class="text-wrap">
                                             <code>{edit.edit.diff.clone()}</code>
@@ -69,7 +110,13 @@ pub fn EditDiff() -> impl IntoView {
                         }
                     })
             })}
-
+            {move || {
+                revert_response
+                    .get()
+                    .map(|article| {
+                        view! { <Redirect path=format!("{}/history", article_path(&article)) /> }
+                    })
+            }}
         </SuspenseError>
     }
 }
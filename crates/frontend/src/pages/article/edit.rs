@@ -4,7 +4,7 @@ use crate::{
         article_nav::{ActiveTab, ArticleNav},
         suspense_error::SuspenseError,
     },
-    pages::article_resource,
+    pages::{article_domain, article_resource},
 };
 use chrono::{Days, Utc};
 use ibis_api_client::{CLIENT, article::EditArticleParams};
@@ -124,6 +124,7 @@ pub fn EditArticle() -> impl IntoView {
                                     }
                                     set_content.set(article.article.text.clone());
                                     let article_ = article.clone();
+                                    let domain = article_domain(&article);
                                     let show_federation_warning = !article.instance.local
                                         && article.instance.last_refreshed_at + Days::new(3)
                                             < Utc::now();
@@ -145,7 +146,13 @@ pub fn EditArticle() -> impl IntoView {
                                                         .num_days()}
                                                     " days. Edits will most likely fail. Instead consider forking the article to your local instance (under Actions), or edit a different article."
                                                 </div>
-                                            </Show> <EditorView textarea_ref content set_content />
+                                            </Show>
+                                            <EditorView
+                                                textarea_ref
+                                                content
+                                                set_content
+                                                domain
+                                            />
                                             <div class="flex flex-row mr-2">
                                                 <input
                                                     type="text"
@@ -2,6 +2,7 @@ use crate::{
     components::{
         article_nav::{ActiveTab, ArticleNav},
         edit_list::EditList,
+        pagination::{Pagination, has_next_page, use_page},
         suspense_error::SuspenseError,
     },
     pages::{article_edits_resource, article_resource},
@@ -11,18 +12,21 @@ use leptos::prelude::*;
 #[component]
 pub fn ArticleHistory() -> impl IntoView {
     let article = article_resource();
+    let page = use_page();
 
     view! {
         <ArticleNav article=article active_tab=ActiveTab::History />
         <SuspenseError result=article>
             {move || Suspend::new(async move {
-                let edits = article_edits_resource(article).await;
+                let edits = article_edits_resource(article, page, None).await;
                 edits
                     .await
                     .map(|edits| {
+                        let has_next = has_next_page(edits.len());
                         view! {
                             // TODO: move edits resource here? but leads to strange crash
                             <EditList edits=edits for_article=true />
+                            <Pagination has_next />
                         }
                     })
             })}
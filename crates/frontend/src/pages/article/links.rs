@@ -0,0 +1,49 @@
+use crate::{
+    components::{
+        article_nav::{ActiveTab, ArticleNav},
+        suspense_error::SuspenseError,
+    },
+    pages::article_resource,
+    utils::formatting::article_link,
+};
+use ibis_api_client::CLIENT;
+use leptos::prelude::*;
+
+/// Other articles which link to this article ("What links here").
+#[component]
+pub fn ArticleLinks() -> impl IntoView {
+    let article = article_resource();
+    let backlinks = Resource::new(
+        move || article.get().and_then(Result::ok).map(|a| a.article.id),
+        |id| async move {
+            match id {
+                Some(id) => CLIENT.list_backlinks(id).await,
+                None => Ok(vec![]),
+            }
+        },
+    );
+
+    view! {
+        <ArticleNav article=article active_tab=ActiveTab::Links />
+        <SuspenseError result=backlinks>
+            {move || Suspend::new(async move {
+                backlinks
+                    .await
+                    .map(|backlinks| {
+                        let is_empty = backlinks.is_empty();
+                        view! {
+                            <Show when=move || is_empty>
+                                <p class="my-4">"No other articles link here."</p>
+                            </Show>
+                            <ul class="list-disc list-inside">
+                                {backlinks
+                                    .iter()
+                                    .map(|a| view! { <li>{article_link(a)}</li> })
+                                    .collect::<Vec<_>>()}
+                            </ul>
+                        }
+                    })
+            })}
+        </SuspenseError>
+    }
+}
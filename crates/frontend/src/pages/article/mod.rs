@@ -4,4 +4,5 @@ pub mod diff;
 pub mod discussion;
 pub mod edit;
 pub mod history;
+pub mod links;
 pub mod read;
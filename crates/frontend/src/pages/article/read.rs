@@ -3,9 +3,16 @@ use crate::{
         article_nav::{ActiveTab, ArticleNav},
         suspense_error::SuspenseError,
     },
-    markdown::render_article_markdown,
-    pages::article_resource,
+    markdown::{
+        article_link::{LinkContext, find_missing_links},
+        render_article_markdown,
+        template::{expand_templates, resolve_templates},
+    },
+    pages::{article_domain, article_resource},
+    utils::formatting::{article_link, category_path},
 };
+use ibis_api_client::CLIENT;
+use ibis_database::common::category::parse_categories;
 use leptos::{either::Either, prelude::*};
 use leptos_router::hooks::use_query_map;
 
@@ -14,17 +21,93 @@ pub fn ReadArticle() -> impl IntoView {
     let article = article_resource();
     let query = use_query_map();
     let edit_successful = query.get_untracked().get("edit_successful").is_some();
+    let templates = Resource::new(
+        move || article.get().and_then(Result::ok),
+        |article| async move {
+            match article {
+                Some(a) => {
+                    let domain = article_domain(&a);
+                    resolve_templates(a.article.text, domain).await
+                }
+                None => vec![],
+            }
+        },
+    );
+    // Links which are included from templates are checked as well
+    let missing_links = Resource::new(
+        move || (article.get().and_then(Result::ok), templates.get()),
+        |(article, templates)| async move {
+            match (article, templates) {
+                (Some(a), Some(templates)) => {
+                    let domain = article_domain(&a);
+                    let text = expand_templates(&a.article.text, &domain, &templates);
+                    find_missing_links(text, domain).await
+                }
+                _ => vec![],
+            }
+        },
+    );
+    let template_usage = Resource::new(
+        move || article.get().and_then(Result::ok).map(|a| a.article.id),
+        |id| async move {
+            match id {
+                Some(id) => CLIENT.list_template_usage(id).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
 
     view! {
         <ArticleNav article=article active_tab=ActiveTab::Read />
         <SuspenseError result=article>
             {move || Suspend::new(async move {
                 let article = article.await;
-                let markdown = article.map(|a| render_article_markdown(&a.article.text));
-                if let Ok(markdown) = markdown {
+                let templates = templates.await;
+                let template_usage = template_usage.await;
+                let missing = missing_links.await;
+                let markdown = article
+                    .map(|a| {
+                        let domain = article_domain(&a);
+                        let text = expand_templates(&a.article.text, &domain, &templates);
+                        let markdown = render_article_markdown(&text, &LinkContext { domain, missing });
+                        (markdown, parse_categories(&a.article.text))
+                    });
+                if let Ok((markdown, categories)) = markdown {
                     Either::Right(
                         view! {
                             <div class="max-w-full prose prose-slate" inner_html=markdown></div>
+                            {(!categories.is_empty())
+                                .then(|| {
+                                    view! {
+                                        <div class="flex flex-wrap gap-2 items-center mt-8">
+                                            <span class="font-bold">"Categories:"</span>
+                                            {categories
+                                                .iter()
+                                                .map(|c| {
+                                                    view! {
+                                                        <a class="badge badge-outline" href=category_path(c)>
+                                                            {c.replace('_', " ")}
+                                                        </a>
+                                                    }
+                                                })
+                                                .collect::<Vec<_>>()}
+                                        </div>
+                                    }
+                                })}
+                            {(!template_usage.is_empty())
+                                .then(|| {
+                                    view! {
+                                <div class="mt-8">
+                                    <h2 class="font-bold">"Used as template in"</h2>
+                                    <ul class="list-disc list-inside">
+                                        {template_usage
+                                            .iter()
+                                            .map(|a| view! { <li>{article_link(a)}</li> })
+                                            .collect::<Vec<_>>()}
+                                    </ul>
+                                </div>
+                                    }
+                                })}
                         },
                     )
                 } else {
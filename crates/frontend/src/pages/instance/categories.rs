@@ -0,0 +1,191 @@
+use crate::{
+    components::{
+        pagination::{Pagination, has_next_page, use_page},
+        suspense_error::SuspenseError,
+    },
+    utils::formatting::{article_path, article_title, category_link, category_title},
+};
+use ibis_api_client::{
+    CLIENT,
+    article::{ListArticlesParams, ListCategoriesParams},
+};
+use ibis_database::common::category::{CategoryView, parent_category};
+use leptos::{ev, prelude::*};
+use leptos_meta::Title;
+use leptos_router::hooks::{use_navigate, use_params_map};
+use url::form_urlencoded;
+
+/// Top level categories of all articles.
+#[component]
+pub fn Categories() -> impl IntoView {
+    let categories = Resource::new(
+        || (),
+        |_| async move {
+            CLIENT
+                .list_categories(ListCategoriesParams::default())
+                .await
+        },
+    );
+
+    view! {
+        <Title text="Categories" />
+        <h1 class="my-4 font-serif text-4xl font-bold">Categories</h1>
+        <SuspenseError result=categories>
+            {move || Suspend::new(async move {
+                categories
+                    .await
+                    .map(|categories| {
+                        let is_empty = categories.is_empty();
+                        view! {
+                            <Show when=move || is_empty>
+                                <p class="my-4">
+                                    "No categories yet. Add articles to a category by writing [[Category:Name]] in the text."
+                                </p>
+                            </Show>
+                            {category_list(categories)}
+                        }
+                    })
+            })}
+        </SuspenseError>
+    }
+}
+
+/// Subcategories and articles of a single category. Articles in subcategories are included.
+#[component]
+pub fn CategoryDetails() -> impl IntoView {
+    let params = use_params_map();
+    let page = use_page();
+    let name = Memo::new(move |_| params.get().get("name").unwrap_or_default());
+    let subcategories = Resource::new(
+        move || name.get(),
+        |name| async move {
+            let params = ListCategoriesParams { parent: Some(name) };
+            CLIENT.list_categories(params).await
+        },
+    );
+    let articles = Resource::new(
+        move || (name.get(), page.get()),
+        |(name, page)| async move {
+            CLIENT
+                .list_articles(ListArticlesParams {
+                    category: Some(name),
+                    page: Some(page),
+                    ..Default::default()
+                })
+                .await
+        },
+    );
+    let (search_query, set_search_query) = signal(String::new());
+
+    view! {
+        <Title text=move || format!("Category — {}", category_title(&name.get())) />
+        <div class="mt-4 breadcrumbs">
+            <ul>
+                <li>
+                    <a class="link" href="/categories">
+                        Categories
+                    </a>
+                </li>
+                {move || {
+                    parents(&name.get())
+                        .into_iter()
+                        .map(|p| view! { <li>{category_link(&p)}</li> })
+                        .collect::<Vec<_>>()
+                }}
+            </ul>
+        </div>
+        <h1 class="mb-4 font-serif text-4xl font-bold">{move || category_title(&name.get())}</h1>
+        <form
+            class="flex mb-4"
+            on:submit=move |ev| {
+                ev.prevent_default();
+                let mut params = form_urlencoded::Serializer::new(String::new());
+                params.append_pair("query", &search_query.get());
+                params.append_pair("category", &name.get());
+                let navigate = use_navigate();
+                navigate(&format!("/search?{}", params.finish()), Default::default());
+            }
+        >
+            <input
+                type="text"
+                class="input input-bordered input-sm"
+                placeholder="Search in category"
+                prop:value=search_query
+                on:keyup=move |ev: ev::KeyboardEvent| {
+                    set_search_query.set(event_target_value(&ev));
+                }
+            />
+            <button class="ml-2 btn btn-sm btn-secondary">Search</button>
+        </form>
+        <SuspenseError result=subcategories>
+            {move || Suspend::new(async move {
+                subcategories
+                    .await
+                    .map(|subcategories| {
+                        (!subcategories.is_empty())
+                            .then(|| {
+                                view! {
+                                    <h2 class="font-serif text-xl font-bold">Subcategories</h2>
+                                    {category_list(subcategories)}
+                                }
+                            })
+                    })
+            })}
+        </SuspenseError>
+        <h2 class="font-serif text-xl font-bold">Articles</h2>
+        <SuspenseError result=articles>
+            {move || Suspend::new(async move {
+                articles
+                    .await
+                    .map(|articles| {
+                        let has_next = has_next_page(articles.len());
+                        view! {
+                            <ul class="my-4 list-none">
+                                {articles
+                                    .into_iter()
+                                    .map(|a| {
+                                        view! {
+                                            <li>
+                                                <a class="text-lg link" href=article_path(&a)>
+                                                    {article_title(&a)}
+                                                </a>
+                                            </li>
+                                        }
+                                    })
+                                    .collect::<Vec<_>>()}
+                            </ul>
+                            <Pagination has_next />
+                        }
+                    })
+            })}
+        </SuspenseError>
+    }
+}
+
+fn category_list(categories: Vec<CategoryView>) -> impl IntoView {
+    view! {
+        <ul class="my-4 list-disc list-inside">
+            {categories
+                .into_iter()
+                .map(|c| {
+                    view! {
+                        <li>
+                            {category_link(&c.name)} " (" {c.article_count} ")"
+                        </li>
+                    }
+                })
+                .collect::<Vec<_>>()}
+        </ul>
+    }
+}
+
+/// Parent categories from top level downwards, without the category itself.
+fn parents(name: &str) -> Vec<String> {
+    let mut parents = vec![];
+    let mut current = name;
+    while let Some(parent) = parent_category(current) {
+        parents.insert(0, parent.to_string());
+        current = parent;
+    }
+    parents
+}
@@ -1,5 +1,9 @@
 use crate::{
-    components::{instance_follow_button::InstanceFollowButton, suspense_error::SuspenseError},
+    components::{
+        instance_follow_button::InstanceFollowButton,
+        pagination::{Pagination, has_next_page, use_page},
+        suspense_error::SuspenseError,
+    },
     utils::formatting::{
         article_path,
         article_title,
@@ -20,6 +24,7 @@ use leptos_router::hooks::use_params_map;
 #[component]
 pub fn InstanceDetails() -> impl IntoView {
     let params = use_params_map();
+    let page = use_page();
     let hostname = move || params.get().get("hostname").clone();
     let instance = Resource::new(hostname, move |hostname| async move {
         let hostname = hostname.ok_or(FrontendError::new("No instance given"))?;
@@ -37,12 +42,15 @@ pub fn InstanceDetails() -> impl IntoView {
                     .await
                     .map(|instance_| {
                         let articles = Resource::new(
-                            move || instance_.instance.id,
-                            |instance_id| async move {
+                            move || (instance_.instance.id, page.get()),
+                            |(instance_id, page)| async move {
                                 CLIENT
                                     .list_articles(ListArticlesParams {
                                         only_local: None,
                                         instance_id: Some(instance_id),
+                                        category: None,
+                                        page: Some(page),
+                                        limit: None,
                                     })
                                     .await
                             },
@@ -60,27 +68,32 @@ pub fn InstanceDetails() -> impl IntoView {
                                 <div class="divider"></div>
                                 <div>{instance_.instance.topic}</div>
                                 <h2 class="font-serif text-xl font-bold">Articles</h2>
-                                <ul class="list-none">
-                                    <SuspenseError result=articles>
+                                <SuspenseError result=articles>
                                         {move || Suspend::new(async move {
                                             articles
                                                 .await
                                                 .map(|a| {
-                                                    a.into_iter()
-                                                        .map(|a| {
-                                                            view! {
-                                                                <li>
-                                                                    <a class="text-lg link" href=article_path(&a)>
-                                                                        {article_title(&a)}
-                                                                    </a>
-                                                                </li>
-                                                            }
-                                                        })
-                                                        .collect::<Vec<_>>()
+                                                    let has_next = has_next_page(a.len());
+                                                    view! {
+                                                        <ul class="list-none">
+                                                        {a
+                                                            .into_iter()
+                                                            .map(|a| {
+                                                                view! {
+                                                                    <li>
+                                                                        <a class="text-lg link" href=article_path(&a)>
+                                                                            {article_title(&a)}
+                                                                        </a>
+                                                                    </li>
+                                                                }
+                                                            })
+                                                            .collect::<Vec<_>>()}
+                                                        </ul>
+                                                        <Pagination has_next />
+                                                    }
                                                 })
                                         })}
-                                    </SuspenseError>
-                                </ul>
+                                </SuspenseError>
                             </div>
                         }
                     })
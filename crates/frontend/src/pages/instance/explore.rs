@@ -1,5 +1,8 @@
 use crate::{
-    components::suspense_error::SuspenseError,
+    components::{
+        pagination::{Pagination, has_next_page, use_page},
+        suspense_error::SuspenseError,
+    },
     utils::formatting::{article_link, instance_title_with_domain, instance_updated},
 };
 use codee::string::JsonSerdeCodec;
@@ -7,14 +10,24 @@ use ibis_api_client::{
     CLIENT,
     errors::{FrontendResult, FrontendResultExt},
 };
-use ibis_database::common::{article::Article, instance::InstanceWithArticles};
+use ibis_database::common::{PaginationParams, article::Article, instance::InstanceWithArticles};
 use leptos::prelude::*;
 use leptos_meta::Title;
 use url::Url;
 
 #[component]
 pub fn Explore() -> impl IntoView {
-    let instances = Resource::new(move || (), |_| async move { CLIENT.list_instances().await });
+    let page = use_page();
+    let instances = Resource::new(
+        move || page.get(),
+        |page| async move {
+            let params = PaginationParams {
+                page: Some(page),
+                limit: None,
+            };
+            CLIENT.list_instances(params).await
+        },
+    );
 
     view! {
         <Title text="Explore" />
@@ -23,6 +36,7 @@ pub fn Explore() -> impl IntoView {
             {move || Suspend::new(async move {
                 let instances_ = instances.await;
                 let is_empty = instances_.as_ref().map(|i| i.is_empty()).unwrap_or(true);
+                let has_next = instances_.as_ref().map(|i| has_next_page(i.len())).unwrap_or(false);
                 view! {
                     <Show
                         when=move || !is_empty
@@ -37,6 +51,7 @@ pub fn Explore() -> impl IntoView {
                                 .map(instance_card)
                                 .collect::<Vec<_>>()}
                         </ul>
+                        <Pagination has_next />
                     </Show>
                 }
             })}
@@ -0,0 +1,128 @@
+use crate::{
+    components::{
+        pagination::{Pagination, has_next_page, use_page},
+        suspense_error::SuspenseError,
+        upload_button::UploadButton,
+    },
+    utils::{
+        formatting::{time_ago, user_link},
+        resources::{is_logged_in, my_profile},
+    },
+};
+use ibis_api_client::{CLIENT, errors::FrontendResultExt};
+use ibis_database::common::{PaginationParams, upload::UploadView};
+use leptos::prelude::*;
+use leptos_meta::Title;
+use phosphor_leptos::{FILE, Icon};
+
+/// All images and files which were uploaded to this instance.
+#[component]
+pub fn MediaLibrary() -> impl IntoView {
+    let page = use_page();
+    let uploads = Resource::new(
+        move || page.get(),
+        |page| async move {
+            let params = PaginationParams {
+                page: Some(page),
+                limit: None,
+            };
+            CLIENT.list_uploads(params).await
+        },
+    );
+    let delete_action = Action::new(move |upload: &UploadView| {
+        let id = upload.upload.id;
+        async move {
+            CLIENT
+                .delete_upload(id)
+                .await
+                .error_popup(|_| uploads.refetch());
+        }
+    });
+
+    view! {
+        <Title text="Media Library" />
+        <h1 class="my-4 font-serif text-4xl font-bold">Media Library</h1>
+        <Show when=is_logged_in>
+            <UploadButton on_upload=Callback::new(move |_| uploads.refetch()) />
+        </Show>
+        <SuspenseError result=uploads>
+            {move || Suspend::new(async move {
+                uploads
+                    .await
+                    .map(|uploads| {
+                        let has_next = has_next_page(uploads.len());
+                        let is_empty = uploads.is_empty();
+                        view! {
+                            <Show when=move || is_empty>
+                                <p class="my-4">"Nothing was uploaded yet."</p>
+                            </Show>
+                            <div class="grid grid-cols-1 gap-4 my-4 md:grid-cols-2 xl:grid-cols-3">
+                                {uploads
+                                    .into_iter()
+                                    .map(|u| upload_card(u, delete_action))
+                                    .collect::<Vec<_>>()}
+                            </div>
+                            <Pagination has_next />
+                        }
+                    })
+            })}
+        </SuspenseError>
+    }
+}
+
+fn upload_card(upload: UploadView, delete_action: Action<UploadView, ()>) -> impl IntoView {
+    let can_delete = my_profile()
+        .map(|p| p.local_user.admin || p.person.id == upload.upload.uploader_id)
+        .unwrap_or_default();
+    let size = format!("{:.1} MB", upload.upload.size as f64 / 1_000_000.0);
+    let upload_ = upload.clone();
+    view! {
+        <div class="shadow card bg-base-100">
+            <figure class="h-48 bg-base-200">
+                {if upload.upload.is_image() {
+                    view! {
+                        <img
+                            class="object-contain h-full"
+                            src=upload.thumbnail_url.clone()
+                            alt=upload.upload.original_name.clone()
+                        />
+                    }
+                        .into_any()
+                } else {
+                    view! { <Icon icon=FILE size="64px" /> }.into_any()
+                }}
+            </figure>
+            <div class="p-4 card-body">
+                <a class="break-all link" href=upload.url.clone() target="_blank">
+                    {upload.upload.original_name.clone()}
+                </a>
+                <p>
+                    {user_link(&upload.uploader)} " · " {time_ago(upload.upload.published)} " · "
+                    {size}
+                </p>
+                <input
+                    type="text"
+                    class="w-full input input-bordered input-sm"
+                    readonly
+                    title="Markdown to embed the file in an article"
+                    prop:value=upload.markdown()
+                />
+                <Show when=move || can_delete>
+                    <div class="justify-end card-actions">
+                        <button
+                            class="btn btn-error btn-sm"
+                            on:click={
+                                let upload = upload_.clone();
+                                move |_| {
+                                    delete_action.dispatch(upload.clone());
+                                }
+                            }
+                        >
+                            Delete
+                        </button>
+                    </div>
+                </Show>
+            </div>
+        </div>
+    }
+}
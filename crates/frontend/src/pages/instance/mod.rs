@@ -1,5 +1,8 @@
 pub mod about;
+pub mod categories;
 pub mod details;
 pub mod explore;
+pub mod media_library;
 pub mod search;
 pub mod settings;
+pub mod wanted;
@@ -1,15 +1,21 @@
-use crate::utils::formatting::{article_path, article_title};
+use crate::utils::formatting::{article_path_from, article_title_from};
 use ibis_api_client::{CLIENT, instance::SearchArticleParams};
-use ibis_database::common::{article::Article, instance::Instance};
-use leptos::prelude::*;
+use ibis_database::common::{
+    DEFAULT_PAGE_LIMIT,
+    PaginationParams,
+    article::ArticleSearchView,
+    instance::Instance,
+    newtypes::InstanceId,
+};
+use leptos::{either::Either, prelude::*};
 use leptos_meta::Title;
-use leptos_router::hooks::use_query_map;
+use leptos_router::hooks::{use_navigate, use_query_map};
 use serde::{Deserialize, Serialize};
-use url::Url;
+use url::{Url, form_urlencoded};
 
 #[derive(Default, Clone, Deserialize, Serialize, Debug)]
 struct SearchResults {
-    articles: Vec<Article>,
+    articles: Vec<ArticleSearchView>,
     instance: Option<Instance>,
 }
 
@@ -19,17 +25,83 @@ impl SearchResults {
     }
 }
 
+#[derive(Clone, PartialEq)]
+struct SearchQuery {
+    query: String,
+    instance_id: Option<InstanceId>,
+    category: Option<String>,
+    page: i64,
+}
+
+impl SearchQuery {
+    fn path(&self) -> String {
+        let mut params = form_urlencoded::Serializer::new(String::new());
+        params.append_pair("query", &self.query);
+        if let Some(instance_id) = self.instance_id {
+            params.append_pair("instance_id", &instance_id.0.to_string());
+        }
+        if let Some(category) = &self.category {
+            params.append_pair("category", category);
+        }
+        if self.page > 1 {
+            params.append_pair("page", &self.page.to_string());
+        }
+        format!("/search?{}", params.finish())
+    }
+}
+
 #[component]
 pub fn Search() -> impl IntoView {
     let params = use_query_map();
+    let search_query = Memo::new(move |_| {
+        let params = params.get();
+        SearchQuery {
+            query: params.get("query").unwrap_or_default(),
+            instance_id: params
+                .get("instance_id")
+                .and_then(|i| i.parse().ok())
+                .map(InstanceId),
+            category: params.get("category").filter(|c| !c.is_empty()),
+            page: params.get("page").and_then(|p| p.parse().ok()).unwrap_or(1),
+        }
+    });
     let (error, set_error) = signal(None::<String>);
+    let instances = Resource::new(
+        move || (),
+        |_| async move {
+            let params = PaginationParams {
+                page: None,
+                limit: Some(50),
+            };
+            CLIENT
+                .list_instances(params)
+                .await
+                .map(|i| i.into_iter().map(|i| i.instance).collect::<Vec<_>>())
+                .unwrap_or_default()
+        },
+    );
     let search_results = Resource::new(
-        move || params.get().get("query").unwrap_or_default(),
-        move |query| async move {
+        move || search_query.get(),
+        move |search_query| async move {
             set_error.set(None);
             let mut search_results = SearchResults::default();
-            let url = Url::parse(&query);
-            let search_data = SearchArticleParams { query };
+            let query = search_query.query;
+            // Only resolve on the first page, otherwise the same object would be shown again
+            let resolve_id = match Url::parse(&query) {
+                _ if search_query.page != 1 => None,
+                Ok(url) => Some(url),
+                Err(_) if query.contains('@') => {
+                    Url::parse(&format!("acct:{}", query.trim_start_matches('@'))).ok()
+                }
+                Err(_) => None,
+            };
+            let search_data = SearchArticleParams {
+                query,
+                instance_id: search_query.instance_id,
+                category: search_query.category,
+                page: Some(search_query.page),
+                limit: Some(DEFAULT_PAGE_LIMIT),
+            };
             let search = CLIENT.search(&search_data);
 
             match search.await {
@@ -37,13 +109,13 @@ pub fn Search() -> impl IntoView {
                 Err(e) => set_error.set(Some(e.to_string())),
             }
 
-            // If its a valid url, also attempt to resolve as federation object
-            if let Ok(url) = url {
-                match CLIENT.resolve_article(url.clone()).await {
-                    Ok(a) => search_results.articles.push(a.article),
+            // If its a valid url or handle, also attempt to resolve as federation object
+            if let Some(id) = resolve_id {
+                match CLIENT.resolve_article(id.clone()).await {
+                    Ok(a) => search_results.articles.push(a.article.into()),
                     Err(e) => set_error.set(Some(e.to_string())),
                 }
-                match CLIENT.resolve_instance(url).await {
+                match CLIENT.resolve_instance(id).await {
                     Ok(a) => search_results.instance = Some(a),
                     Err(e) => set_error.set(Some(e.to_string())),
                 }
@@ -53,10 +125,65 @@ pub fn Search() -> impl IntoView {
     );
 
     view! {
-        <Title text=move || format!("Search - {}", params.get().get("query").unwrap_or_default()) />
+        <Title text=move || format!("Search - {}", search_query.get().query) />
         <h1 class="flex-auto my-6 font-serif text-4xl font-bold grow">
-            "Search results for " {move || params.get().get("query").unwrap_or_default()}
+            "Search results for " {move || search_query.get().query}
         </h1>
+        <Suspense>
+            <select
+                class="mb-4 select select-bordered select-sm"
+                on:change=move |ev| {
+                    let instance_id = event_target_value(&ev).parse().ok().map(InstanceId);
+                    let navigate = use_navigate();
+                    let path = SearchQuery {
+                        instance_id,
+                        page: 1,
+                        ..search_query.get()
+                    }
+                        .path();
+                    navigate(&path, Default::default());
+                }
+            >
+                <option value="" selected=move || search_query.get().instance_id.is_none()>
+                    "All instances"
+                </option>
+                {move || {
+                    instances
+                        .get()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|i| {
+                            let id = i.id;
+                            view! {
+                                <option
+                                    value=id.0
+                                    selected=move || search_query.get().instance_id == Some(id)
+                                >
+                                    {i.domain}
+                                </option>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </select>
+            <input
+                type="text"
+                class="mb-4 ml-2 input input-bordered input-sm"
+                placeholder="Category"
+                prop:value=move || search_query.get().category.unwrap_or_default()
+                on:change=move |ev| {
+                    let category = Some(event_target_value(&ev)).filter(|c| !c.is_empty());
+                    let navigate = use_navigate();
+                    let path = SearchQuery {
+                        category,
+                        page: 1,
+                        ..search_query.get()
+                    }
+                        .path();
+                    navigate(&path, Default::default());
+                }
+            />
+        </Suspense>
         <Suspense fallback=|| {
             view! { "Loading..." }
         }>
@@ -65,6 +192,7 @@ pub fn Search() -> impl IntoView {
                     .get()
                     .map(move |search_results| {
                         let is_empty = search_results.is_empty();
+                        let has_next = search_results.articles.len() as i64 >= DEFAULT_PAGE_LIMIT;
                         view! {
                             <Show
                                 when=move || !is_empty
@@ -105,10 +233,14 @@ pub fn Search() -> impl IntoView {
                                         .iter()
                                         .map(|a| {
                                             view! {
-                                                <li>
-                                                    <a class="text-lg link" href=article_path(a)>
-                                                        {article_title(a)}
+                                                <li class="mb-2">
+                                                    <a
+                                                        class="text-lg link"
+                                                        href=article_path_from(&a.title, a.local, &a.ap_id)
+                                                    >
+                                                        {article_title_from(&a.title, a.local, &a.ap_id)}
                                                     </a>
+                                                    <p>{snippet_view(&a.snippet)}</p>
                                                 </li>
                                             }
                                         })
@@ -116,6 +248,38 @@ pub fn Search() -> impl IntoView {
 
                                 </ul>
                             </Show>
+                            <div class="flex gap-2 my-4">
+                                <Show when=move || { search_query.get().page > 1 }>
+                                    <a
+                                        class="btn btn-sm"
+                                        href=move || {
+                                            let query = search_query.get();
+                                            SearchQuery {
+                                                page: query.page - 1,
+                                                ..query
+                                            }
+                                                .path()
+                                        }
+                                    >
+                                        "Previous"
+                                    </a>
+                                </Show>
+                                <Show when=move || has_next>
+                                    <a
+                                        class="btn btn-sm"
+                                        href=move || {
+                                            let query = search_query.get();
+                                            SearchQuery {
+                                                page: query.page + 1,
+                                                ..query
+                                            }
+                                                .path()
+                                        }
+                                    >
+                                        "Next"
+                                    </a>
+                                </Show>
+                            </div>
                         }
                     })
             }}
@@ -123,3 +287,23 @@ pub fn Search() -> impl IntoView {
         </Suspense>
     }
 }
+
+/// Render the snippet returned by the search api. Text is rendered as plain text so that html
+/// in the article is not interpreted, only the `<mark>` tags around matched terms are converted.
+fn snippet_view(snippet: &str) -> impl IntoView {
+    snippet
+        .split("<mark>")
+        .enumerate()
+        .map(|(i, part)| {
+            if i == 0 {
+                Either::Left(part.to_string())
+            } else {
+                let (marked, rest) = part.split_once("</mark>").unwrap_or((part, ""));
+                Either::Right(view! {
+                    <mark>{marked.to_string()}</mark>
+                    {rest.to_string()}
+                })
+            }
+        })
+        .collect_view()
+}
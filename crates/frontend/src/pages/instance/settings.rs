@@ -1,5 +1,13 @@
-use crate::{components::suspense_error::SuspenseError, utils::resources::site};
-use ibis_api_client::{CLIENT, errors::FrontendResultExt, instance::UpdateInstanceParams};
+use crate::{
+    components::suspense_error::SuspenseError,
+    utils::{formatting::user_link, resources::site},
+};
+use ibis_api_client::{
+    CLIENT,
+    errors::FrontendResultExt,
+    instance::{BlockInstanceParams, UpdateInstanceParams},
+    user::BanUserParams,
+};
 use leptos::prelude::*;
 use leptos_meta::Title;
 
@@ -70,6 +78,9 @@ pub fn InstanceSettings() -> impl IntoView {
                                 Submit
                             </button>
 
+                            <InstanceBlocks />
+                            <BannedUsers />
+
                             <Show when=move || saved.get()>
                                 <div class="toast">
                                     <div class="alert alert-info">
@@ -84,3 +95,142 @@ pub fn InstanceSettings() -> impl IntoView {
         </SuspenseError>
     }
 }
+
+/// List of blocked instances, where admins can add and remove blocks.
+#[component]
+fn InstanceBlocks() -> impl IntoView {
+    let blocks = Resource::new(
+        move || (),
+        |_| async move { CLIENT.list_instance_blocks().await },
+    );
+    let (domain, set_domain) = signal(String::new());
+    let (purge, set_purge) = signal(false);
+    let block_action = Action::new(move |params: &BlockInstanceParams| {
+        let params = params.clone();
+        async move {
+            CLIENT.block_instance(&params).await.error_popup(|_| {
+                set_domain.set(String::new());
+                set_purge.set(false);
+                blocks.refetch();
+            });
+        }
+    });
+
+    view! {
+        <h2 class="mt-8 mb-2 font-serif text-2xl font-bold">"Blocked Instances"</h2>
+        <Suspense>
+            <ul class="mb-2">
+                {move || {
+                    blocks
+                        .get()
+                        .and_then(|b| b.ok())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|b| {
+                            let domain = b.domain.clone();
+                            view! {
+                                <li class="flex flex-row items-center mb-1">
+                                    <span class="w-80">{b.domain}</span>
+                                    <button
+                                        class="btn btn-sm btn-secondary"
+                                        on:click=move |_| {
+                                            block_action
+                                                .dispatch(BlockInstanceParams {
+                                                    domain: domain.clone(),
+                                                    block: false,
+                                                    purge: false,
+                                                });
+                                        }
+                                    >
+                                        "Unblock"
+                                    </button>
+                                </li>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </ul>
+        </Suspense>
+        <div class="flex flex-row items-center mb-2">
+            <input
+                type="text"
+                placeholder="example.com"
+                class="mr-2 w-80 input input-secondary input-bordered"
+                bind:value=(domain, set_domain)
+            />
+            <label class="mr-2 label">
+                <input type="checkbox" class="mr-1 checkbox" bind:checked=(purge, set_purge) />
+                "Remove existing content"
+            </label>
+            <button
+                class="btn btn-secondary"
+                disabled=move || domain.get().is_empty()
+                on:click=move |_| {
+                    block_action
+                        .dispatch(BlockInstanceParams {
+                            domain: domain.get(),
+                            block: true,
+                            purge: purge.get(),
+                        });
+                }
+            >
+                "Block"
+            </button>
+        </div>
+    }
+}
+
+/// List of banned users. New bans are added from the user profile.
+#[component]
+fn BannedUsers() -> impl IntoView {
+    let banned = Resource::new(
+        move || (),
+        |_| async move { CLIENT.list_banned_users().await },
+    );
+    let unban_action = Action::new(move |params: &BanUserParams| {
+        let params = params.clone();
+        async move {
+            CLIENT
+                .ban_user(&params)
+                .await
+                .error_popup(|_| banned.refetch());
+        }
+    });
+
+    view! {
+        <h2 class="mt-8 mb-2 font-serif text-2xl font-bold">"Banned Users"</h2>
+        <Suspense>
+            <ul class="mb-2">
+                {move || {
+                    banned
+                        .get()
+                        .and_then(|b| b.ok())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|person| {
+                            let person_id = person.id;
+                            view! {
+                                <li class="flex flex-row items-center mb-1">
+                                    <span class="w-80">{user_link(&person)}</span>
+                                    <button
+                                        class="btn btn-sm btn-secondary"
+                                        on:click=move |_| {
+                                            unban_action
+                                                .dispatch(BanUserParams {
+                                                    person_id,
+                                                    ban: false,
+                                                    remove_content: false,
+                                                });
+                                        }
+                                    >
+                                        "Unban"
+                                    </button>
+                                </li>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </ul>
+        </Suspense>
+    }
+}
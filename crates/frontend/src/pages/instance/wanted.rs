@@ -0,0 +1,64 @@
+use crate::components::{
+    pagination::{Pagination, has_next_page, use_page},
+    suspense_error::SuspenseError,
+};
+use ibis_api_client::CLIENT;
+use ibis_database::common::{PaginationParams, link::WantedArticle};
+use leptos::prelude::*;
+use leptos_meta::Title;
+
+/// Local articles which are linked from other articles, but don't exist yet.
+#[component]
+pub fn WantedArticles() -> impl IntoView {
+    let page = use_page();
+    let wanted = Resource::new(
+        move || page.get(),
+        |page| async move {
+            let params = PaginationParams {
+                page: Some(page),
+                limit: None,
+            };
+            CLIENT.list_wanted_articles(params).await
+        },
+    );
+
+    view! {
+        <Title text="Wanted Articles" />
+        <h1 class="my-4 font-serif text-4xl font-bold">Wanted Articles</h1>
+        <SuspenseError result=wanted>
+            {move || Suspend::new(async move {
+                wanted
+                    .await
+                    .map(|wanted| {
+                        let has_next = has_next_page(wanted.len());
+                        let is_empty = wanted.is_empty();
+                        view! {
+                            <Show when=move || is_empty>
+                                <p class="my-4">"All linked articles exist."</p>
+                            </Show>
+                            <ul class="my-4 list-disc list-inside">
+                                {wanted.into_iter().map(wanted_item).collect::<Vec<_>>()}
+                            </ul>
+                            <Pagination has_next />
+                        }
+                    })
+            })}
+        </SuspenseError>
+    }
+}
+
+fn wanted_item(wanted: WantedArticle) -> impl IntoView {
+    let links = if wanted.link_count == 1 {
+        "1 link".to_string()
+    } else {
+        format!("{} links", wanted.link_count)
+    };
+    view! {
+        <li>
+            <a class="link link-error" href=format!("/create-article?title={}", wanted.title)>
+                {wanted.title.replace('_', " ")}
+            </a>
+            " (" {links} ")"
+        </li>
+    }
+}
@@ -1,7 +1,11 @@
-use ibis_api_client::{CLIENT, article::GetArticleParams, errors::FrontendResult};
+use ibis_api_client::{
+    CLIENT,
+    article::{GetArticleParams, GetEditList},
+    errors::FrontendResult,
+};
 use ibis_database::common::{
     MAIN_PAGE_NAME,
-    article::{ArticleView, EditView},
+    article::{ArticleView, EditVersion, EditView},
 };
 use leptos::prelude::*;
 use leptos_router::hooks::use_params_map;
@@ -33,15 +37,32 @@ fn article_resource() -> Resource<FrontendResult<ArticleView>> {
     })
 }
 
+/// Domain of the article instance to resolve templates and links, `None` for local articles.
+fn article_domain(article: &ArticleView) -> Option<String> {
+    (!article.instance.local).then(|| article.instance.domain.clone())
+}
+
+/// Edits of the article, optionally only the edit with the given version.
 async fn article_edits_resource(
     article: Resource<FrontendResult<ArticleView>>,
+    page: Memo<i64>,
+    version: Option<EditVersion>,
 ) -> Resource<FrontendResult<Vec<EditView>>> {
     let id = article.await.map(|a| a.article.id);
     Resource::new(
-        move || article.get(),
-        move |_| {
+        move || (article.get(), page.get()),
+        move |(_, page)| {
             let id = id.clone();
-            async move { CLIENT.get_article_edits(id?).await }
+            let version = version.clone();
+            async move {
+                let params = GetEditList {
+                    article_id: Some(id?),
+                    version,
+                    page: Some(page),
+                    ..Default::default()
+                };
+                CLIENT.list_edits(&params).await
+            }
         },
     )
 }
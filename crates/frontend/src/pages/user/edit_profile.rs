@@ -1,7 +1,28 @@
-use crate::{components::suspense_error::SuspenseError, utils::resources::site};
-use ibis_api_client::{CLIENT, errors::FrontendResultExt, user::UpdateUserParams};
+use crate::{
+    components::suspense_error::SuspenseError,
+    utils::{
+        formatting::time_ago,
+        resources::{email_enabled, site},
+    },
+};
+use ibis_api_client::{
+    CLIENT,
+    errors::FrontendResultExt,
+    user::{
+        ChangePasswordParams,
+        CreateApiTokenParams,
+        DeleteAccountParams,
+        DisableTotpParams,
+        UpdateUserParams,
+    },
+};
+use ibis_database::common::{
+    newtypes::{ApiTokenId, SessionId},
+    user::ApiTokenScope,
+};
 use leptos::prelude::*;
 use leptos_meta::Title;
+use leptos_router::hooks::use_navigate;
 
 #[component]
 pub fn UserEditProfile() -> impl IntoView {
@@ -34,6 +55,21 @@ pub fn UserEditProfile() -> impl IntoView {
                         let (bio, set_bio) = signal(
                             my_profile.person.bio.clone().unwrap_or_default(),
                         );
+                        let (bot, set_bot) = signal(my_profile.person.bot);
+                        let (hide_bot_edits, set_hide_bot_edits) = signal(
+                            my_profile.local_user.hide_bot_edits,
+                        );
+                        let current_email = my_profile.local_user.email.clone().unwrap_or_default();
+                        let (email, set_email) = signal(current_email.clone());
+                        let (password, set_password) = signal(String::new());
+                        let email_changed = Memo::new({
+                            let current_email = current_email.clone();
+                            move |_| email.get() != current_email
+                        });
+                        let (email_notifications, set_email_notifications) = signal(
+                            my_profile.local_user.email_notifications,
+                        );
+                        let email_verified = my_profile.local_user.email_verified;
                         view! {
                             <h1 class="flex-auto my-6 font-serif text-4xl font-bold grow">
                                 Edit Profile
@@ -59,12 +95,83 @@ pub fn UserEditProfile() -> impl IntoView {
                                     bio.get()
                                 </textarea>
                             </div>
+                            <div class="flex flex-row mb-2">
+                                <label class="block w-40" for="bot">
+                                    "Bot account"
+                                </label>
+                                <input
+                                    type="checkbox"
+                                    id="bot"
+                                    class="checkbox"
+                                    bind:checked=(bot, set_bot)
+                                />
+                            </div>
+                            <div class="flex flex-row mb-2">
+                                <label class="block w-40" for="hide_bot_edits">
+                                    "Hide bot edits"
+                                </label>
+                                <input
+                                    type="checkbox"
+                                    id="hide_bot_edits"
+                                    class="checkbox"
+                                    bind:checked=(hide_bot_edits, set_hide_bot_edits)
+                                />
+                            </div>
+                            <Show when=email_enabled>
+                                <div class="flex flex-row items-center mb-2">
+                                    <label class="block w-40" for="email">
+                                        Email
+                                    </label>
+                                    <input
+                                        type="email"
+                                        id="email"
+                                        class="w-80 input input-secondary input-bordered"
+                                        bind:value=(email, set_email)
+                                    />
+                                    <Show when=move || {
+                                        !email_verified && !email.get().is_empty()
+                                    }>
+                                        <span class="ml-2 badge badge-warning">Not verified</span>
+                                    </Show>
+                                </div>
+                                <Show when=move || email_changed.get()>
+                                    <div class="flex flex-row mb-2">
+                                        <label class="block w-40" for="email_password">
+                                            "Current password"
+                                        </label>
+                                        <input
+                                            type="password"
+                                            id="email_password"
+                                            class="w-80 input input-secondary input-bordered"
+                                            bind:value=(password, set_password)
+                                        />
+                                    </div>
+                                </Show>
+                                <div class="flex flex-row mb-2">
+                                    <label class="block w-40" for="email_notifications">
+                                        "Daily notification email"
+                                    </label>
+                                    <input
+                                        type="checkbox"
+                                        id="email_notifications"
+                                        class="checkbox"
+                                        bind:checked=(email_notifications, set_email_notifications)
+                                    />
+                                </div>
+                            </Show>
                             <button
                                 class="btn btn-primary"
                                 on:click=move |_| {
                                     let form = UpdateUserParams {
                                         display_name: Some(display_name.get()),
                                         bio: Some(bio.get()),
+                                        bot: Some(bot.get()),
+                                        hide_bot_edits: Some(hide_bot_edits.get()),
+                                        email: Some(email.get())
+                                            .filter(|e| e != &current_email),
+                                        password: Some(password.get())
+                                            .filter(|_| email_changed.get()),
+                                        email_notifications: Some(email_notifications.get()),
                                     };
                                     submit_action.dispatch(form);
                                 }
@@ -72,6 +179,12 @@ pub fn UserEditProfile() -> impl IntoView {
                                 Submit
                             </button>
 
+                            <ChangePassword />
+                            <TwoFactorAuth enabled=my_profile.local_user.totp_enabled />
+                            <Sessions />
+                            <ApiTokens is_admin=my_profile.local_user.admin />
+                            <AccountData />
+
                             <Show when=move || saved.get()>
                                 <div class="toast">
                                     <div class="alert alert-info">
@@ -86,3 +199,429 @@ pub fn UserEditProfile() -> impl IntoView {
         </SuspenseError>
     }
 }
+
+#[component]
+fn ChangePassword() -> impl IntoView {
+    let (old_password, set_old_password) = signal(String::new());
+    let (new_password, set_new_password) = signal(String::new());
+    let (changed, set_changed) = signal(false);
+    let change_action = Action::new(move |params: &ChangePasswordParams| {
+        let params = params.clone();
+        async move {
+            CLIENT.change_password(&params).await.error_popup(|_| {
+                set_old_password.set(String::new());
+                set_new_password.set(String::new());
+                set_changed.set(true);
+            });
+        }
+    });
+
+    view! {
+        <h2 class="mt-8 mb-2 font-serif text-2xl font-bold">"Change Password"</h2>
+        <div class="flex flex-row mb-2">
+            <label class="block w-40">Current password</label>
+            <input
+                type="password"
+                class="w-80 input input-secondary input-bordered"
+                bind:value=(old_password, set_old_password)
+            />
+        </div>
+        <div class="flex flex-row mb-2">
+            <label class="block w-40">New password</label>
+            <input
+                type="password"
+                class="w-80 input input-secondary input-bordered"
+                bind:value=(new_password, set_new_password)
+            />
+        </div>
+        <button
+            class="btn btn-primary"
+            on:click=move |_| {
+                change_action
+                    .dispatch(ChangePasswordParams {
+                        old_password: old_password.get(),
+                        new_password: new_password.get(),
+                    });
+            }
+        >
+            Change Password
+        </button>
+        <Show when=move || changed.get()>
+            <span class="ml-4">Password changed</span>
+        </Show>
+    }
+}
+
+/// Setup of two factor authentication with an authenticator app, or disabling it.
+#[component]
+fn TwoFactorAuth(enabled: bool) -> impl IntoView {
+    let (enabled, set_enabled) = signal(enabled);
+    let (code, set_code) = signal(String::new());
+    let (password, set_password) = signal(String::new());
+    let setup_action = Action::new(move |_: &()| async move { CLIENT.setup_totp().await.ok() });
+    let enable_action = Action::new(move |code: &String| {
+        let code = code.clone();
+        async move {
+            let res = CLIENT.enable_totp(code).await;
+            res.clone().error_popup(|_| {
+                set_code.set(String::new());
+                set_enabled.set(true);
+            });
+            res.ok().map(|r| r.recovery_codes)
+        }
+    });
+    let disable_action = Action::new(move |params: &DisableTotpParams| {
+        let params = params.clone();
+        async move {
+            CLIENT.disable_totp(&params).await.error_popup(|_| {
+                set_code.set(String::new());
+                set_password.set(String::new());
+                set_enabled.set(false);
+                setup_action.value().set(None);
+            });
+        }
+    });
+
+    let code_input = move || {
+        view! {
+            <div class="flex flex-row mb-2">
+                <label class="block w-40">Code</label>
+                <input
+                    type="text"
+                    autocomplete="one-time-code"
+                    class="w-80 input input-secondary input-bordered"
+                    bind:value=(code, set_code)
+                />
+            </div>
+        }
+    };
+
+    view! {
+        <h2 class="mt-8 mb-2 font-serif text-2xl font-bold">"Two Factor Authentication"</h2>
+        <Show
+            when=move || enabled.get()
+            fallback=move || {
+                view! {
+                    {move || match setup_action.value().get().flatten() {
+                        None => {
+                            view! {
+                                <button
+                                    class="btn btn-primary"
+                                    on:click=move |_| {
+                                        setup_action.dispatch(());
+                                    }
+                                >
+                                    Set up
+                                </button>
+                            }
+                                .into_any()
+                        }
+                        Some(setup) => {
+                            view! {
+                                <p class="mb-2">
+                                    "Add this account to your authenticator app by opening the "
+                                    <a class="link" href=setup.uri>
+                                        "setup link"
+                                    </a> " or entering the secret " <code>{setup.secret}</code>
+                                    ". Then confirm with the current code from the app."
+                                </p>
+                                {code_input()}
+                                <button
+                                    class="btn btn-primary"
+                                    on:click=move |_| {
+                                        enable_action.dispatch(code.get());
+                                    }
+                                >
+                                    Enable
+                                </button>
+                            }
+                                .into_any()
+                        }
+                    }}
+                }
+            }
+        >
+            {move || {
+                enable_action
+                    .value()
+                    .get()
+                    .flatten()
+                    .map(|codes| {
+                        view! {
+                            <p class="mb-2">
+                                "Two factor authentication is enabled. Store these recovery codes in a safe place, each of them can be used once if you lose access to the authenticator app:"
+                            </p>
+                            <ul class="mb-2 font-mono">
+                                {codes
+                                    .into_iter()
+                                    .map(|c| view! { <li>{c}</li> })
+                                    .collect::<Vec<_>>()}
+                            </ul>
+                        }
+                    })
+            }}
+            <div class="flex flex-row mb-2">
+                <label class="block w-40">Password</label>
+                <input
+                    type="password"
+                    class="w-80 input input-secondary input-bordered"
+                    bind:value=(password, set_password)
+                />
+            </div>
+            {code_input()}
+            <button
+                class="btn btn-secondary"
+                on:click=move |_| {
+                    disable_action
+                        .dispatch(DisableTotpParams {
+                            password: password.get(),
+                            code: code.get(),
+                        });
+                }
+            >
+                Disable
+            </button>
+        </Show>
+    }
+}
+
+/// Devices where the user is logged in, which can be logged out individually or all at once.
+#[component]
+fn Sessions() -> impl IntoView {
+    let sessions = Resource::new(move || (), |_| async move { CLIENT.list_sessions().await });
+    let delete_action = Action::new(move |id: &SessionId| {
+        let id = *id;
+        async move {
+            CLIENT
+                .delete_session(id)
+                .await
+                .error_popup(|_| sessions.refetch());
+        }
+    });
+    let logout_all_action = Action::new(move |_: &()| async move {
+        CLIENT.logout_all().await.error_popup(|_| {
+            site().refetch();
+            let navigate = use_navigate();
+            navigate("/", Default::default());
+        });
+    });
+
+    view! {
+        <h2 class="mt-8 mb-2 font-serif text-2xl font-bold">"Sessions"</h2>
+        <Suspense>
+            <ul class="mb-2">
+                {move || {
+                    sessions
+                        .get()
+                        .and_then(|s| s.ok())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|s| {
+                            let id = s.session.id;
+                            view! {
+                                <li class="flex flex-row items-center mb-1">
+                                    <span class="w-80">
+                                        {s.session.user_agent.unwrap_or("Unknown device".to_string())}
+                                    </span>
+                                    <span class="w-40">
+                                        "Last used "{time_ago(s.session.last_used)}
+                                    </span>
+                                    <Show
+                                        when=move || !s.current
+                                        fallback=|| view! { <span class="badge">Current</span> }
+                                    >
+                                        <button
+                                            class="btn btn-sm btn-secondary"
+                                            on:click=move |_| {
+                                                delete_action.dispatch(id);
+                                            }
+                                        >
+                                            Log out
+                                        </button>
+                                    </Show>
+                                </li>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </ul>
+        </Suspense>
+        <button
+            class="btn btn-secondary"
+            on:click=move |_| {
+                logout_all_action.dispatch(());
+            }
+        >
+            Log out everywhere
+        </button>
+    }
+}
+
+/// Api tokens for bots and scripts. A new token is only shown once after creating it.
+#[component]
+fn ApiTokens(is_admin: bool) -> impl IntoView {
+    let tokens = Resource::new(
+        move || (),
+        |_| async move { CLIENT.list_api_tokens().await },
+    );
+    let (name, set_name) = signal(String::new());
+    let (scope, set_scope) = signal(ApiTokenScope::Read);
+    let (new_token, set_new_token) = signal(None::<String>);
+    let create_action = Action::new(move |params: &CreateApiTokenParams| {
+        let params = params.clone();
+        async move {
+            CLIENT.create_api_token(&params).await.error_popup(|res| {
+                set_name.set(String::new());
+                set_new_token.set(Some(res.token));
+                tokens.refetch();
+            });
+        }
+    });
+    let delete_action = Action::new(move |id: &ApiTokenId| {
+        let id = *id;
+        async move {
+            CLIENT
+                .delete_api_token(id)
+                .await
+                .error_popup(|_| tokens.refetch());
+        }
+    });
+    let mut scopes = vec![ApiTokenScope::Read, ApiTokenScope::Edit];
+    if is_admin {
+        scopes.push(ApiTokenScope::Admin);
+    }
+
+    view! {
+        <h2 class="mt-8 mb-2 font-serif text-2xl font-bold">"Api Tokens"</h2>
+        <Suspense>
+            <ul class="mb-2">
+                {move || {
+                    tokens
+                        .get()
+                        .and_then(|t| t.ok())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|t| {
+                            let id = t.id;
+                            view! {
+                                <li class="flex flex-row items-center mb-1">
+                                    <span class="w-60">{t.name}</span>
+                                    <span class="w-20">{format!("{:?}", t.scope)}</span>
+                                    <span class="w-40">
+                                        {t
+                                            .last_used
+                                            .map(|l| format!("Last used {}", time_ago(l)))
+                                            .unwrap_or("Never used".to_string())}
+                                    </span>
+                                    <button
+                                        class="btn btn-sm btn-secondary"
+                                        on:click=move |_| {
+                                            delete_action.dispatch(id);
+                                        }
+                                    >
+                                        Revoke
+                                    </button>
+                                </li>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </ul>
+        </Suspense>
+        <div class="flex flex-row mb-2">
+            <input
+                type="text"
+                placeholder="Token name"
+                class="mr-2 w-60 input input-secondary input-bordered"
+                bind:value=(name, set_name)
+            />
+            <select
+                class="mr-2 select select-bordered"
+                on:change=move |ev| {
+                    let scope = match event_target_value(&ev).as_str() {
+                        "Admin" => ApiTokenScope::Admin,
+                        "Edit" => ApiTokenScope::Edit,
+                        _ => ApiTokenScope::Read,
+                    };
+                    set_scope.set(scope);
+                }
+            >
+                {scopes
+                    .into_iter()
+                    .map(|s| {
+                        let s = format!("{s:?}");
+                        view! { <option value=s.clone()>{s.clone()}</option> }
+                    })
+                    .collect::<Vec<_>>()}
+            </select>
+            <button
+                class="btn btn-primary"
+                on:click=move |_| {
+                    create_action
+                        .dispatch(CreateApiTokenParams {
+                            name: name.get(),
+                            scope: scope.get(),
+                        });
+                }
+            >
+                Create Token
+            </button>
+        </div>
+        {move || {
+            new_token
+                .get()
+                .map(|token| {
+                    view! {
+                        <p class="mb-2">
+                            "New token, copy it now as it wont be shown again: " <code>{token}</code>
+                        </p>
+                    }
+                })
+        }}
+    }
+}
+
+/// Export of user data and account deletion.
+#[component]
+fn AccountData() -> impl IntoView {
+    let (password, set_password) = signal(String::new());
+    let delete_action = Action::new(move |params: &DeleteAccountParams| {
+        let params = params.clone();
+        async move {
+            CLIENT.delete_account(&params).await.error_popup(|_| {
+                site().refetch();
+                let navigate = use_navigate();
+                navigate("/", Default::default());
+            });
+        }
+    });
+
+    view! {
+        <h2 class="mt-8 mb-2 font-serif text-2xl font-bold">"Account Data"</h2>
+        <a class="mb-4 btn btn-secondary" href="/api/v1/account/export" download="ibis-export.json">
+            Export Data
+        </a>
+        <p class="mb-2">
+            "Deleting your account cannot be undone. Your edits and comments will be shown as written by a deleted user."
+        </p>
+        <div class="flex flex-row mb-2">
+            <label class="block w-40">Password</label>
+            <input
+                type="password"
+                class="w-80 input input-secondary input-bordered"
+                bind:value=(password, set_password)
+            />
+        </div>
+        <button
+            class="btn btn-error"
+            on:click=move |_| {
+                delete_action
+                    .dispatch(DeleteAccountParams {
+                        password: password.get(),
+                    });
+            }
+        >
+            Delete Account
+        </button>
+    }
+}
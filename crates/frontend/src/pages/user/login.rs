@@ -1,5 +1,9 @@
-use crate::{components::credentials::*, utils::resources::site};
-use ibis_api_client::{CLIENT, user::LoginUserParams};
+use crate::{
+    components::credentials::*,
+    utils::resources::{config, email_enabled, oauth_providers, site},
+};
+use ibis_api_client::{CLIENT, errors::FrontendResultExt, user::LoginUserParams};
+use ibis_database::error::ErrorType;
 use leptos::prelude::*;
 use leptos_meta::Title;
 use leptos_router::components::Redirect;
@@ -9,11 +13,18 @@ pub fn Login() -> impl IntoView {
     let (login_response, set_login_response) = signal(false);
     let (login_error, set_login_error) = signal(None::<String>);
     let (wait_for_response, set_wait_for_response) = signal(false);
+    // shown after the first login attempt if the account has two factor authentication
+    let (totp_required, set_totp_required) = signal(false);
+    let (totp_code, set_totp_code) = signal(String::new());
 
     let login_action = Action::new(move |(email, password): &(String, String)| {
         let username = email.to_string();
         let password = password.to_string();
-        let params = LoginUserParams { username, password };
+        let params = LoginUserParams {
+            username,
+            password,
+            totp_code: Some(totp_code.get_untracked()),
+        };
         async move {
             set_wait_for_response.update(|w| *w = true);
             let result = CLIENT.login(params).await;
@@ -24,6 +35,10 @@ pub fn Login() -> impl IntoView {
                     set_login_response.set(true);
                     set_login_error.update(|e| *e = None);
                 }
+                Err(err) if err.error_type() == ErrorType::TotpRequired => {
+                    set_totp_required.set(true);
+                    set_login_error.set(None);
+                }
                 Err(err) => {
                     let msg = err.to_string();
                     log::warn!("Unable to login: {msg}");
@@ -41,13 +56,40 @@ pub fn Login() -> impl IntoView {
             when=move || login_response.get()
             fallback=move || {
                 view! {
+                    <Show
+                        when=move || config().password_login
+                        fallback=|| {
+                            view! {
+                                <h1 class="my-4 font-serif text-4xl font-bold">Login</h1>
+                            }
+                        }
+                    >
                     <CredentialsForm
                         title="Login"
                         action_label="Login"
                         action=login_action
                         error=login_error.into()
                         disabled
-                    />
+                    >
+                        <Show when=move || totp_required.get()>
+                            <div class="h-2"></div>
+                            <input
+                                type="text"
+                                class="input input-primary input-bordered"
+                                placeholder="Two factor code or recovery code"
+                                autocomplete="one-time-code"
+                                prop:disabled=move || disabled.get()
+                                bind:value=(totp_code, set_totp_code)
+                            />
+                        </Show>
+                    </CredentialsForm>
+                    <Show when=email_enabled>
+                        <a class="link" href="/forgot_password">
+                            Forgot password?
+                        </a>
+                    </Show>
+                    </Show>
+                    <OAuthLogin />
                 }
             }
         >
@@ -56,3 +98,39 @@ pub fn Login() -> impl IntoView {
         </Show>
     }
 }
+
+/// Buttons for login with OpenID Connect providers. The provider redirects back to
+/// [crate::pages::user::oauth_callback::OAuthCallback] after login.
+#[component]
+fn OAuthLogin() -> impl IntoView {
+    let authorize_action = Action::new(move |provider: &String| {
+        let provider = provider.clone();
+        async move {
+            CLIENT.oauth_authorize(provider).await.error_popup(|res| {
+                let _ = window().location().set_href(&res.url);
+            });
+        }
+    });
+    view! {
+        <div class="flex flex-col my-4 max-w-80">
+            {move || {
+                oauth_providers()
+                    .into_iter()
+                    .map(|p| {
+                        view! {
+                            <button
+                                class="mb-2 btn btn-secondary"
+                                on:click=move |_| {
+                                    authorize_action.dispatch(p.id.clone());
+                                }
+                            >
+                                "Login with "
+                                {p.display_name.clone()}
+                            </button>
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }}
+        </div>
+    }
+}
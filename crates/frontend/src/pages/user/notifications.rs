@@ -1,5 +1,8 @@
 use crate::{
-    components::suspense_error::SuspenseError,
+    components::{
+        pagination::{Pagination, has_next_page, use_page},
+        suspense_error::SuspenseError,
+    },
     utils::formatting::{
         article_link,
         article_path,
@@ -16,13 +19,14 @@ use ibis_api_client::{
     errors::{FrontendError, FrontendResultExt},
 };
 use ibis_database::common::{
+    PaginationParams,
     article::{Article, Conflict, Edit},
     comment::Comment,
     newtypes::ArticleNotifId,
     notifications::ApiNotification,
-    user::Person,
+    user::{Person, RegistrationApplication},
 };
-use leptos::{either::EitherOf4, prelude::*};
+use leptos::{either::EitherOf5, prelude::*};
 use leptos_meta::Title;
 use phosphor_leptos::{CHECK, Icon, LINK};
 
@@ -30,9 +34,16 @@ type NotificationsResource = Resource<Result<Vec<ApiNotification>, FrontendError
 
 #[component]
 pub fn Notifications() -> impl IntoView {
+    let page = use_page();
     let notifications = Resource::new(
-        move || {},
-        |_| async move { CLIENT.notifications_list().await },
+        move || page.get(),
+        |page| async move {
+            let params = PaginationParams {
+                page: Some(page),
+                limit: None,
+            };
+            CLIENT.notifications_list(params).await
+        },
     );
 
     view! {
@@ -49,16 +60,21 @@ pub fn Notifications() -> impl IntoView {
                                     use ApiNotification::*;
                                     match notif {
                                         EditConflict(c, a) => {
-                                            EitherOf4::A(edit_conflict_view(c, a, notifications))
+                                            EitherOf5::A(edit_conflict_view(c, a, notifications))
                                         }
                                         ArticleApprovalRequired(a) => {
-                                            EitherOf4::B(article_approval_view(a, notifications))
+                                            EitherOf5::B(article_approval_view(a, notifications))
                                         }
                                         Comment(id, c, p, a) => {
-                                            EitherOf4::C(comment_view(*id, c, p, a, notifications))
+                                            EitherOf5::C(comment_view(*id, c, p, a, notifications))
                                         }
                                         Edit(id, e, p, a) => {
-                                            EitherOf4::D(edit_view(*id, e, p, a, notifications))
+                                            EitherOf5::D(edit_view(*id, e, p, a, notifications))
+                                        }
+                                        RegistrationApplication(r, p) => {
+                                            EitherOf5::E(
+                                                registration_application_view(r, p, notifications),
+                                            )
                                         }
                                     }
                                 })
@@ -67,6 +83,13 @@ pub fn Notifications() -> impl IntoView {
                 })}
 
             </ul>
+            {move || Suspend::new(async move {
+                let has_next = notifications
+                    .await
+                    .map(|n| has_next_page(n.len()))
+                    .unwrap_or(false);
+                view! { <Pagination has_next /> }
+            })}
         </SuspenseError>
     }
 }
@@ -144,6 +167,50 @@ fn article_approval_view(a: &Article, notifications: NotificationsResource) -> i
     }
 }
 
+fn registration_application_view(
+    application: &RegistrationApplication,
+    person: &Person,
+    notifications: NotificationsResource,
+) -> impl IntoView {
+    let id = application.id;
+    let click_approve = Action::new(move |_: &()| async move {
+        CLIENT
+            .approve_registration(id, true)
+            .await
+            .error_popup(|_| notifications.refetch());
+    });
+    let click_reject = Action::new(move |_: &()| async move {
+        CLIENT
+            .approve_registration(id, false)
+            .await
+            .error_popup(|_| notifications.refetch());
+    });
+    view! {
+        <li class="py-2">
+            <div class="text-lg">"New registration: " {user_link(person)}</div>
+            <div>{application.answer.clone()}</div>
+            <div class="mt-2 card-actions">
+                <button
+                    class="btn btn-sm btn-outline"
+                    on:click=move |_| {
+                        click_approve.dispatch(());
+                    }
+                >
+                    Approve
+                </button>
+                <button
+                    class="btn btn-sm btn-outline"
+                    on:click=move |_| {
+                        click_reject.dispatch(());
+                    }
+                >
+                    Reject
+                </button>
+            </div>
+        </li>
+    }
+}
+
 fn comment_view(
     id: ArticleNotifId,
     comment: &Comment,
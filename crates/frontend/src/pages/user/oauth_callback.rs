@@ -0,0 +1,82 @@
+use crate::utils::resources::site;
+use ibis_api_client::{CLIENT, user::OAuthLoginParams};
+use ibis_database::error::ErrorType;
+use leptos::prelude::*;
+use leptos_meta::Title;
+use leptos_router::{components::Redirect, hooks::use_query_map};
+
+/// The OpenID Connect provider redirects here after login, with the parameters which are needed
+/// to complete the login.
+#[component]
+pub fn OAuthCallback() -> impl IntoView {
+    let query = use_query_map().get_untracked();
+    let params = OAuthLoginParams {
+        code: query.get("code").unwrap_or_default(),
+        state: query.get("state").unwrap_or_default(),
+        totp_code: None,
+    };
+    let provider_error = query.get("error_description").or(query.get("error"));
+    // shown if the account has two factor authentication, the login is then repeated with code
+    let (totp_required, set_totp_required) = signal(false);
+    let (totp_code, set_totp_code) = signal(String::new());
+    let login_action = Action::new(move |params: &OAuthLoginParams| {
+        let params = params.clone();
+        async move {
+            let res = CLIENT.oauth_login(&params).await;
+            match &res {
+                Ok(_) => site().refetch(),
+                Err(e) if e.error_type() == ErrorType::TotpRequired => set_totp_required.set(true),
+                Err(_) => set_totp_required.set(false),
+            }
+            res.map_err(|e| e.to_string())
+        }
+    });
+    // only run in the browser, so that the code is not used up during server side rendering
+    let has_error = provider_error.is_some();
+    let initial_params = params.clone();
+    Effect::new(move || {
+        if !has_error {
+            login_action.dispatch(initial_params.clone());
+        }
+    });
+    let submit_totp = move || {
+        let mut params = params.clone();
+        params.totp_code = Some(totp_code.get_untracked());
+        login_action.dispatch(params);
+    };
+
+    view! {
+        <Title text="Login" />
+        <h1 class="my-4 font-serif text-4xl font-bold">Login</h1>
+        {move || match (provider_error.clone(), login_action.value().get()) {
+            (None, Some(Err(_))) if totp_required.get() => {
+                let submit_totp = submit_totp.clone();
+                view! {
+                    <div class="flex flex-col max-w-80">
+                        <input
+                            type="text"
+                            class="input input-primary input-bordered"
+                            placeholder="Two factor code or recovery code"
+                            autocomplete="one-time-code"
+                            prop:disabled=move || login_action.pending().get()
+                            bind:value=(totp_code, set_totp_code)
+                        />
+                        <button
+                            class="mt-2 btn btn-primary"
+                            prop:disabled=move || login_action.pending().get()
+                            on:click=move |_| submit_totp()
+                        >
+                            Login
+                        </button>
+                    </div>
+                }
+                    .into_any()
+            }
+            (Some(error), _) | (_, Some(Err(error))) => {
+                view! { <p class="alert alert-error">{error}</p> }.into_any()
+            }
+            (None, Some(Ok(_))) => view! { <Redirect path="/" /> }.into_any(),
+            (None, None) => view! { <p>"Logging in..."</p> }.into_any(),
+        }}
+    }
+}
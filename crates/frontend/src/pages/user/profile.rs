@@ -1,9 +1,18 @@
 use crate::{
-    components::{edit_list::EditList, suspense_error::SuspenseError},
-    markdown::render_article_markdown,
-    utils::formatting::user_title,
+    components::{
+        edit_list::EditList,
+        pagination::{Pagination, has_next_page, use_page},
+        suspense_error::SuspenseError,
+    },
+    markdown::{article_link::LinkContext, render_article_markdown},
+    utils::{formatting::user_title, resources::is_admin},
+};
+use ibis_api_client::{
+    CLIENT,
+    article::GetEditList,
+    errors::FrontendResultExt,
+    user::{BanUserParams, GetUserParams},
 };
-use ibis_api_client::{CLIENT, user::GetUserParams};
 use leptos::prelude::*;
 use leptos_meta::Title;
 use leptos_router::hooks::use_params_map;
@@ -11,6 +20,7 @@ use leptos_router::hooks::use_params_map;
 #[component]
 pub fn UserProfile() -> impl IntoView {
     let params = use_params_map();
+    let page = use_page();
     let name = move || params.get().get("name").clone().unwrap_or_default();
     let user_profile = Resource::new(name, move |mut name| async move {
         let mut domain = None;
@@ -21,26 +31,82 @@ pub fn UserProfile() -> impl IntoView {
         let params = GetUserParams { name, domain };
         CLIENT.get_user(params).await
     });
+    let (remove_content, set_remove_content) = signal(false);
+    let ban_action = Action::new(move |params: &BanUserParams| {
+        let params = params.clone();
+        async move {
+            CLIENT
+                .ban_user(&params)
+                .await
+                .error_popup(|_| user_profile.refetch());
+        }
+    });
 
     view! {
         <SuspenseError result=user_profile>
             {move || Suspend::new(async move {
                 let edits = Resource::new(
-                    move || user_profile.get(),
-                    move |_| async move { CLIENT.get_person_edits(user_profile.await?.id).await },
+                    move || (user_profile.get(), page.get()),
+                    move |(_, page)| async move {
+                        let params = GetEditList {
+                            person_id: Some(user_profile.await?.id),
+                            page: Some(page),
+                            ..Default::default()
+                        };
+                        CLIENT.list_edits(&params).await
+                    },
                 );
                 user_profile
                     .await
                     .map(|person| {
+                        let person_id = person.id;
+                        let banned = person.banned;
+                        let bot = person.bot;
                         view! {
                             <Title text=user_title(&person) />
                             <h1 class="flex-auto my-6 font-serif text-4xl font-bold grow">
                                 {user_title(&person)}
                             </h1>
+                            <Show when=move || banned>
+                                <div class="mb-2 badge badge-error">"Banned"</div>
+                            </Show>
+                            <Show when=move || bot>
+                                <div class="mb-2 badge badge-neutral">"Bot"</div>
+                            </Show>
+                            <Show when=is_admin>
+                                <div class="flex flex-row items-center mb-2">
+                                    <Show when=move || !banned>
+                                        <label class="mr-2 label">
+                                            <input
+                                                type="checkbox"
+                                                class="mr-1 checkbox"
+                                                bind:checked=(remove_content, set_remove_content)
+                                            />
+                                            "Remove comments and revert edits"
+                                        </label>
+                                    </Show>
+                                    <button
+                                        class="btn btn-sm btn-error"
+                                        on:click=move |_| {
+                                            ban_action
+                                                .dispatch(BanUserParams {
+                                                    person_id,
+                                                    ban: !banned,
+                                                    remove_content: remove_content.get(),
+                                                });
+                                        }
+                                    >
+                                        {if banned { "Unban" } else { "Ban" }}
+                                    </button>
+                                </div>
+                            </Show>
 
                             <div
                                 class="mb-2 max-w-full prose prose-slate"
-                                inner_html=render_article_markdown(&person.bio.unwrap_or_default())
+                                inner_html=render_article_markdown(
+                                    &person.bio.unwrap_or_default(),
+                                    &LinkContext::default(),
+                                )
                             ></div>
 
                             <SuspenseError result=user_profile>
@@ -48,9 +114,11 @@ pub fn UserProfile() -> impl IntoView {
                                     edits
                                         .await
                                         .map(|edits| {
+                                            let has_next = has_next_page(edits.len());
                                             view! {
                                                 <h2 class="font-serif text-xl font-bold">Edits</h2>
                                                 <EditList edits=edits for_article=false />
+                                                <Pagination has_next />
                                             }
                                         })
                                 })}
@@ -1,4 +1,7 @@
-use crate::{components::credentials::*, utils::resources::site};
+use crate::{
+    components::credentials::*,
+    utils::resources::{config, email_enabled, site},
+};
 use ibis_api_client::{CLIENT, user::RegisterUserParams};
 use leptos::prelude::*;
 use leptos_meta::Title;
@@ -6,29 +9,52 @@ use log::info;
 
 #[component]
 pub fn Register() -> impl IntoView {
-    let (register_response, set_register_response) = signal(false);
+    let (register_response, set_register_response) = signal(None::<bool>);
     let (register_error, set_register_error) = signal(None::<String>);
     let (wait_for_response, set_wait_for_response) = signal(false);
+    let (answer, set_answer) = signal(String::new());
+    let (email, set_email) = signal(String::new());
+    let (captcha_answer, set_captcha_answer) = signal(String::new());
+    let captcha = Resource::new(
+        move || config().registration_captcha,
+        |enabled| async move {
+            if enabled {
+                CLIENT.get_captcha().await.ok()
+            } else {
+                None
+            }
+        },
+    );
 
-    let register_action = Action::new(move |(email, password): &(String, String)| {
-        let username = email.to_string();
+    let register_action = Action::new(move |(username, password): &(String, String)| {
+        let username = username.to_string();
         let password = password.to_string();
-        let params = RegisterUserParams { username, password };
+        let params = RegisterUserParams {
+            username,
+            password,
+            answer: Some(answer.get_untracked()),
+            captcha_id: captcha.get_untracked().flatten().map(|c| c.id),
+            captcha_answer: Some(captcha_answer.get_untracked()),
+            email: Some(email.get_untracked()),
+        };
         info!("Try to register new account for {}", params.username);
         async move {
             set_wait_for_response.set(true);
             let result = CLIENT.register(params).await;
             set_wait_for_response.set(false);
             match result {
-                Ok(_res) => {
+                Ok(res) => {
                     site().refetch();
-                    set_register_response.set(true);
+                    set_register_response.set(Some(res.local_user.approved));
                     set_register_error.set(None);
                 }
                 Err(err) => {
                     let msg = err.to_string();
                     log::warn!("Unable to register new account: {msg}");
                     set_register_error.set(Some(msg));
+                    // each captcha can only be used once
+                    set_captcha_answer.set(String::new());
+                    captcha.refetch();
                 }
             }
         }
@@ -39,7 +65,7 @@ pub fn Register() -> impl IntoView {
     view! {
         <Title text="Register" />
         <Show
-            when=move || register_response.get()
+            when=move || register_response.get().is_some()
             fallback=move || {
                 view! {
                     <CredentialsForm
@@ -48,12 +74,66 @@ pub fn Register() -> impl IntoView {
                         action=register_action
                         error=register_error.into()
                         disabled
-                    />
+                    >
+                        <Show when=email_enabled>
+                            <div class="h-2"></div>
+                            <input
+                                type="email"
+                                class="input input-primary input-bordered"
+                                placeholder="Email (optional)"
+                                prop:disabled=move || disabled.get()
+                                bind:value=(email, set_email)
+                            />
+                        </Show>
+                        {move || {
+                            let config = config();
+                            config
+                                .registration_question
+                                .filter(|_| config.registration_approval)
+                                .map(|question| {
+                                    view! {
+                                        <label class="my-2">{question}</label>
+                                        <textarea
+                                            class="textarea textarea-primary"
+                                            prop:disabled=move || disabled.get()
+                                            bind:value=(answer, set_answer)
+                                        ></textarea>
+                                    }
+                                })
+                        }}
+                        <Suspense>
+                            {move || Suspend::new(async move {
+                                captcha
+                                    .await
+                                    .map(|captcha| {
+                                        view! {
+                                            <div class="my-2" inner_html=captcha.svg></div>
+                                            <input
+                                                type="text"
+                                                class="input input-primary input-bordered"
+                                                placeholder="Enter the characters shown above"
+                                                prop:disabled=move || disabled.get()
+                                                bind:value=(captcha_answer, set_captcha_answer)
+                                            />
+                                        }
+                                    })
+                            })}
+                        </Suspense>
+                    </CredentialsForm>
                 }
             }
         >
-
-            <p>"You have successfully registered."</p>
+            <p>
+                {move || {
+                    if register_response.get() == Some(false) {
+                        "Your registration was submitted, you can login after it is approved by an admin."
+                    } else if !email.get().is_empty() {
+                        "You have successfully registered. Please confirm your email address with the link which was sent to you."
+                    } else {
+                        "You have successfully registered."
+                    }
+                }}
+            </p>
         </Show>
     }
 }
@@ -0,0 +1,123 @@
+use ibis_api_client::{CLIENT, errors::FrontendResultExt, user::ResetPasswordParams};
+use leptos::prelude::*;
+use leptos_meta::Title;
+use leptos_router::hooks::use_query_map;
+
+/// Request an email with a password reset link.
+#[component]
+pub fn ForgotPassword() -> impl IntoView {
+    let (email, set_email) = signal(String::new());
+    let (sent, set_sent) = signal(false);
+    let request_action = Action::new(move |email: &String| {
+        let email = email.clone();
+        async move {
+            CLIENT
+                .request_password_reset(email)
+                .await
+                .error_popup(|_| set_sent.set(true));
+        }
+    });
+
+    view! {
+        <Title text="Forgot Password" />
+        <form class="form-control max-w-80" on:submit=|ev| ev.prevent_default()>
+            <h1 class="my-4 font-serif text-4xl font-bold">Forgot Password</h1>
+            <Show
+                when=move || sent.get()
+                fallback=move || {
+                    view! {
+                        <input
+                            type="email"
+                            class="input input-primary input-bordered"
+                            required
+                            placeholder="Email"
+                            bind:value=(email, set_email)
+                        />
+                        <div>
+                            <button
+                                class="my-2 btn btn-primary"
+                                prop:disabled=move || {
+                                    request_action.pending().get() || email.get().is_empty()
+                                }
+                                on:click=move |_| {
+                                    request_action.dispatch(email.get());
+                                }
+                            >
+                                Send reset link
+                            </button>
+                        </div>
+                    }
+                }
+            >
+                <p>
+                    "If an account with this verified email exists, a link to reset the password was sent to it."
+                </p>
+            </Show>
+        </form>
+    }
+}
+
+/// Opened from the link in the password reset email.
+#[component]
+pub fn ResetPassword() -> impl IntoView {
+    let token = use_query_map()
+        .get_untracked()
+        .get("token")
+        .unwrap_or_default();
+    let (new_password, set_new_password) = signal(String::new());
+    let (changed, set_changed) = signal(false);
+    let reset_action = Action::new(move |params: &ResetPasswordParams| {
+        let params = params.clone();
+        async move {
+            CLIENT
+                .reset_password(&params)
+                .await
+                .error_popup(|_| set_changed.set(true));
+        }
+    });
+
+    view! {
+        <Title text="Reset Password" />
+        <form class="form-control max-w-80" on:submit=|ev| ev.prevent_default()>
+            <h1 class="my-4 font-serif text-4xl font-bold">Reset Password</h1>
+            <Show
+                when=move || changed.get()
+                fallback=move || {
+                    let token = token.clone();
+                    view! {
+                        <input
+                            type="password"
+                            class="input input-primary input-bordered"
+                            required
+                            placeholder="New password"
+                            bind:value=(new_password, set_new_password)
+                        />
+                        <div>
+                            <button
+                                class="my-2 btn btn-primary"
+                                prop:disabled=move || {
+                                    reset_action.pending().get() || new_password.get().is_empty()
+                                }
+                                on:click=move |_| {
+                                    reset_action
+                                        .dispatch(ResetPasswordParams {
+                                            token: token.clone(),
+                                            new_password: new_password.get(),
+                                        });
+                                }
+                            >
+                                Set new password
+                            </button>
+                        </div>
+                    }
+                }
+            >
+                <p>
+                    "Your password was changed. You can now " <a class="link" href="/login">
+                        login
+                    </a> " with the new password."
+                </p>
+            </Show>
+        </form>
+    }
+}
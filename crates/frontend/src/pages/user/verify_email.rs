@@ -0,0 +1,49 @@
+use crate::utils::resources::site;
+use ibis_api_client::{CLIENT, errors::FrontendResultExt};
+use leptos::prelude::*;
+use leptos_meta::Title;
+use leptos_router::hooks::use_query_map;
+
+/// Opened from the link in the verification email. The token is only submitted after clicking
+/// the button, so that link previews by email clients dont use it up.
+#[component]
+pub fn VerifyEmail() -> impl IntoView {
+    let token = use_query_map()
+        .get_untracked()
+        .get("token")
+        .unwrap_or_default();
+    let (verified, set_verified) = signal(false);
+    let verify_action = Action::new(move |token: &String| {
+        let token = token.clone();
+        async move {
+            CLIENT.verify_email(token).await.error_popup(|_| {
+                set_verified.set(true);
+                site().refetch();
+            });
+        }
+    });
+
+    view! {
+        <Title text="Verify Email" />
+        <h1 class="my-4 font-serif text-4xl font-bold">Verify Email</h1>
+        <Show
+            when=move || verified.get()
+            fallback=move || {
+                let token = token.clone();
+                view! {
+                    <button
+                        class="btn btn-primary"
+                        prop:disabled=move || verify_action.pending().get()
+                        on:click=move |_| {
+                            verify_action.dispatch(token.clone());
+                        }
+                    >
+                        Confirm email address
+                    </button>
+                }
+            }
+        >
+            <p>Your email address is verified.</p>
+        </Show>
+    }
+}
@@ -1,24 +1,27 @@
 use chrono::{DateTime, Local, Utc};
-use ibis_database::common::{
-    article::{Article, Edit},
-    comment::Comment,
-    instance::Instance,
-    user::Person,
-    utils::extract_domain,
+use ibis_database::{
+    DbUrl,
+    common::{
+        article::{Article, Edit},
+        comment::Comment,
+        instance::Instance,
+        user::Person,
+        utils::extract_domain,
+    },
 };
 use leptos::prelude::*;
 use std::sync::OnceLock;
 use timeago::Formatter;
 
 pub fn article_path(article: &Article) -> String {
-    if article.local {
-        format!("/article/{}", article.title)
+    article_path_from(&article.title, article.local, &article.ap_id)
+}
+
+pub fn article_path_from(title: &str, local: bool, ap_id: &DbUrl) -> String {
+    if local {
+        format!("/article/{title}")
     } else {
-        format!(
-            "/article/{}@{}",
-            article.title,
-            extract_domain(article.ap_id.inner())
-        )
+        format!("/article/{title}@{}", extract_domain(ap_id.inner()))
     }
 }
 
@@ -31,12 +34,33 @@ pub fn article_link(article: &Article) -> impl IntoView {
     }
 }
 
+pub fn category_path(name: &str) -> String {
+    format!("/category/{name}")
+}
+
+/// Only the last level of subcategories, like `Physics` for `Science/Physics`.
+pub fn category_title(name: &str) -> String {
+    name.rsplit('/').next().unwrap_or(name).replace('_', " ")
+}
+
+pub fn category_link(name: &str) -> impl IntoView {
+    view! {
+        <a class="link" href=category_path(name)>
+            {category_title(name)}
+        </a>
+    }
+}
+
 pub fn article_title(article: &Article) -> String {
-    let title = article.title.replace('_', " ");
-    if article.local {
+    article_title_from(&article.title, article.local, &article.ap_id)
+}
+
+pub fn article_title_from(title: &str, local: bool, ap_id: &DbUrl) -> String {
+    let title = title.replace('_', " ");
+    if local {
         title
     } else {
-        format!("{}@{}", title, extract_domain(article.ap_id.inner()))
+        format!("{}@{}", title, extract_domain(ap_id.inner()))
     }
 }
 
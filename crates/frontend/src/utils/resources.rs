@@ -1,6 +1,6 @@
 use ibis_api_client::{CLIENT, errors::FrontendResult};
 use ibis_database::common::{
-    instance::{Options, SiteView},
+    instance::{OAuthProvider, Options, SiteView},
     user::LocalUserView,
 };
 use leptos::prelude::*;
@@ -30,6 +30,25 @@ pub fn config() -> Options {
     .unwrap_or_default()
 }
 
+/// Whether the instance can send emails, eg for password reset.
+pub fn email_enabled() -> bool {
+    match site_internal() {
+        Some(s) => s.map(|s| s.as_ref().is_ok_and(|s| s.email_enabled)),
+        None => None,
+    }
+    .unwrap_or_default()
+}
+
+/// OpenID Connect providers which can be used for login.
+pub fn oauth_providers() -> Vec<OAuthProvider> {
+    match site_internal() {
+        Some(s) => s.map(|s| s.as_ref().ok().map(|s| s.oauth_providers.clone())),
+        None => None,
+    }
+    .flatten()
+    .unwrap_or_default()
+}
+
 pub fn is_logged_in() -> bool {
     my_profile().is_some()
 }